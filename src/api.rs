@@ -0,0 +1,631 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::{Path, State},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, patch, post},
+    Json, Router,
+};
+use chrono::Utc;
+use redis::{Connection, RedisError};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use teloxide::Bot;
+
+use crate::{
+    dosage::{Dosage, WeightDose},
+    flows::{add_medication::build_medication, take_medicine::register_intake},
+    free_text::MAX_MINUTES_AGO,
+    frequency::Frequency,
+    i18n::{is_nothing, Language},
+    medication::Medication,
+    patient::Patient,
+    timezone::parse_timezone,
+    user::{get_token_user, get_user_language},
+};
+
+// kind of the user tokens giving access to the API
+pub const API_TOKEN: &str = "api";
+
+#[derive(Clone)]
+struct ApiState {
+    bot: Bot,
+    con: Arc<Mutex<Connection>>,
+}
+
+#[derive(Debug, PartialEq)]
+enum ApiError {
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    BadRequest(String),
+    // the new plan interacts with the ones the patient already has
+    Interactions(Vec<String>),
+    Internal,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error) = match self {
+            ApiError::Unauthorized => (
+                StatusCode::UNAUTHORIZED,
+                "missing or revoked token, get one with /apitoken".to_string(),
+            ),
+            ApiError::Forbidden => (
+                StatusCode::FORBIDDEN,
+                "you only have view access to this patient".to_string(),
+            ),
+            ApiError::NotFound => (StatusCode::NOT_FOUND, "not found".to_string()),
+            ApiError::BadRequest(error) => (StatusCode::BAD_REQUEST, error),
+            ApiError::Interactions(interactions) => {
+                return (
+                    StatusCode::CONFLICT,
+                    Json(json!({
+                        "error": "it interacts with other medications of the patient, send confirm_interactions to add it anyway",
+                        "interactions": interactions,
+                    })),
+                )
+                    .into_response()
+            }
+            ApiError::Internal => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "something went wrong".to_string(),
+            ),
+        };
+
+        (status, Json(json!({ "error": error }))).into_response()
+    }
+}
+
+impl From<RedisError> for ApiError {
+    fn from(e: RedisError) -> Self {
+        log::warn!("API request failed. Error {}", e);
+        ApiError::Internal
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct PatientView {
+    id: String,
+    name: String,
+    role: String,
+    weight_kg: Option<f64>,
+    timezone: String,
+}
+
+impl PatientView {
+    fn new(patient: &Patient, user_id: &str, con: Arc<Mutex<Connection>>) -> Self {
+        PatientView {
+            id: patient.id.clone(),
+            name: patient.name.clone(),
+            role: patient
+                .get_role(user_id)
+                .map(|role| role.to_string())
+                .unwrap_or_default(),
+            weight_kg: patient.weight_kg,
+            timezone: patient.get_timezone(con).name().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct MedicationView {
+    id: String,
+    patient_id: String,
+    medicine: String,
+    dosage: String,
+    frequency: String,
+    last_taken: Option<i64>,
+    can_take: bool,
+    next_dose: i64,
+}
+
+impl From<&Medication> for MedicationView {
+    fn from(medication: &Medication) -> Self {
+        MedicationView {
+            id: medication.id.clone(),
+            patient_id: medication.patient_id.clone(),
+            medicine: medication.medicine.clone(),
+            dosage: medication.dosage.to_string(),
+            frequency: medication.get_frequency().to_string(),
+            last_taken: medication.last_taken,
+            can_take: medication.can_take(),
+            next_dose: medication.get_can_take_next_date().timestamp(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct NewPatient {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PatientUpdate {
+    name: Option<String>,
+    weight_kg: Option<f64>,
+    // an IANA name or city, "none" goes back to the owner's
+    timezone: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NewMedication {
+    medicine: String,
+    dosage: String,
+    frequency: String,
+    #[serde(default)]
+    confirm_interactions: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct NewMedicationView {
+    #[serde(flatten)]
+    medication: MedicationView,
+    // over the maximum dose for the patient's weight
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warning: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MedicationUpdate {
+    dosage: Option<String>,
+    frequency: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NewIntake {
+    // unix timestamp, now when missing
+    taken_at: Option<i64>,
+}
+
+// Authorization: Bearer <token>
+fn authenticate(con: Arc<Mutex<Connection>>, headers: &HeaderMap) -> Result<String, ApiError> {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .and_then(|token| get_token_user(con, API_TOKEN, token.trim()))
+        .ok_or(ApiError::Unauthorized)
+}
+
+// patients the user has no access to don't exist as far as they're concerned
+fn get_patient(
+    con: Arc<Mutex<Connection>>,
+    patient_id: &str,
+    user_id: &str,
+    edit: bool,
+) -> Result<Patient, ApiError> {
+    let patient = Patient::get_by_id(patient_id, con).map_err(|_| ApiError::NotFound)?;
+
+    match patient.get_role(user_id) {
+        None => Err(ApiError::NotFound),
+        Some(role) if edit && !role.can_edit() => Err(ApiError::Forbidden),
+        Some(_) => Ok(patient),
+    }
+}
+
+fn get_medication(
+    con: Arc<Mutex<Connection>>,
+    medication_id: &str,
+    user_id: &str,
+    edit: bool,
+) -> Result<(Medication, Patient), ApiError> {
+    let medication =
+        Medication::get_by_id(medication_id, con.clone()).map_err(|_| ApiError::NotFound)?;
+    let patient = get_patient(con, &medication.patient_id, user_id, edit)?;
+
+    Ok((medication, patient))
+}
+
+// same as when adding a plan in Telegram, weight based ones need the patient's weight
+fn check_dosage(dosage: &str, patient: &Patient) -> Result<(), ApiError> {
+    match WeightDose::parse(dosage) {
+        Ok(_) if patient.weight_kg.is_none() => Err(ApiError::BadRequest(format!(
+            "set the weight of {} first for a weight based dosage",
+            patient.name
+        ))),
+        Ok(_) => Ok(()),
+        Err(_) => Dosage::parse(dosage)
+            .map(|_| ())
+            .map_err(|e| ApiError::BadRequest(e.to_string())),
+    }
+}
+
+// same as when adding a plan in Telegram, interactions have to be confirmed
+fn check_interactions(
+    medication: &Medication,
+    confirmed: bool,
+    lang: Language,
+    con: Arc<Mutex<Connection>>,
+) -> Result<(), ApiError> {
+    let others = Medication::get_all_by_patient_id(&medication.patient_id, con);
+    let interactions = medication.print_interactions(&others, lang);
+
+    match confirmed || interactions.is_empty() {
+        true => Ok(()),
+        false => Err(ApiError::Interactions(
+            interactions.lines().map(|line| line.to_string()).collect(),
+        )),
+    }
+}
+
+fn parse_frequency(frequency: &str) -> Result<Frequency, ApiError> {
+    Frequency::parse(frequency).map_err(|e| ApiError::BadRequest(e.to_string()))
+}
+
+async fn list_patients(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<PatientView>>, ApiError> {
+    let user_id = authenticate(state.con.clone(), &headers)?;
+
+    let patients = Patient::get_my_patients(&user_id, state.con.clone())?
+        .iter()
+        .map(|patient| PatientView::new(patient, &user_id, state.con.clone()))
+        .collect();
+
+    Ok(Json(patients))
+}
+
+async fn create_patient(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Json(new_patient): Json<NewPatient>,
+) -> Result<(StatusCode, Json<PatientView>), ApiError> {
+    let user_id = authenticate(state.con.clone(), &headers)?;
+
+    let name = new_patient.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::BadRequest("the name is empty".to_string()));
+    }
+
+    let patient = Patient::new(name.to_string(), user_id.clone());
+    patient.save(state.con.clone())?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(PatientView::new(&patient, &user_id, state.con)),
+    ))
+}
+
+async fn update_patient(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Path(patient_id): Path<String>,
+    Json(update): Json<PatientUpdate>,
+) -> Result<Json<PatientView>, ApiError> {
+    let con = state.con;
+    let user_id = authenticate(con.clone(), &headers)?;
+    let mut patient = get_patient(con.clone(), &patient_id, &user_id, true)?;
+
+    if let Some(name) = update.name.as_deref().map(str::trim) {
+        if name.is_empty() {
+            return Err(ApiError::BadRequest("the name is empty".to_string()));
+        }
+        patient.name = name.to_string();
+    }
+
+    if let Some(timezone) = update.timezone.as_deref() {
+        match parse_timezone(timezone) {
+            Some(tz) => patient.set_timezone(Some(tz)),
+            None if is_nothing(timezone) => patient.set_timezone(None),
+            None => {
+                return Err(ApiError::BadRequest(format!(
+                    "unknown timezone {}",
+                    timezone
+                )))
+            }
+        }
+    }
+
+    if let Some(weight_kg) = update.weight_kg {
+        let weight_kg = Patient::parse_weight(&weight_kg.to_string()).ok_or(
+            ApiError::BadRequest("the weight must be between 0 and 500 kg".to_string()),
+        )?;
+        patient.weight_kg = Some(weight_kg);
+    }
+
+    patient.save(con.clone())?;
+
+    // like in Telegram, weight based dosages follow the weight
+    if let Some(weight_kg) = update.weight_kg.and(patient.weight_kg) {
+        for mut medication in Medication::get_all_by_patient_id(&patient.id, con.clone()) {
+            if medication.recalculate_dosage(weight_kg) {
+                medication.save(con.clone())?;
+            }
+        }
+    }
+
+    Ok(Json(PatientView::new(&patient, &user_id, con)))
+}
+
+async fn list_medications(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Path(patient_id): Path<String>,
+) -> Result<Json<Vec<MedicationView>>, ApiError> {
+    let user_id = authenticate(state.con.clone(), &headers)?;
+    let patient = get_patient(state.con.clone(), &patient_id, &user_id, false)?;
+
+    let medications = Medication::get_all_by_patient_id(&patient.id, state.con)
+        .iter()
+        .map(MedicationView::from)
+        .collect();
+
+    Ok(Json(medications))
+}
+
+async fn create_medication(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Path(patient_id): Path<String>,
+    Json(new_medication): Json<NewMedication>,
+) -> Result<(StatusCode, Json<NewMedicationView>), ApiError> {
+    let con = state.con;
+    let user_id = authenticate(con.clone(), &headers)?;
+    let patient = get_patient(con.clone(), &patient_id, &user_id, true)?;
+
+    let medicine = new_medication.medicine.trim();
+    if medicine.is_empty() {
+        return Err(ApiError::BadRequest("the medicine is empty".to_string()));
+    }
+
+    check_dosage(&new_medication.dosage, &patient)?;
+    let frequency = parse_frequency(&new_medication.frequency)?;
+
+    let mut medication = build_medication(
+        con.clone(),
+        patient.id,
+        medicine.to_string(),
+        new_medication.dosage,
+        frequency,
+        user_id.clone(),
+    );
+
+    let lang = get_user_language(con.clone(), &user_id);
+    check_interactions(
+        &medication,
+        new_medication.confirm_interactions,
+        lang,
+        con.clone(),
+    )?;
+
+    medication.save(con)?;
+
+    let warning = patient
+        .weight_kg
+        .and_then(|weight_kg| medication.print_max_dose_warning(weight_kg, lang));
+
+    Ok((
+        StatusCode::CREATED,
+        Json(NewMedicationView {
+            medication: MedicationView::from(&medication),
+            warning,
+        }),
+    ))
+}
+
+async fn update_medication(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Path(medication_id): Path<String>,
+    Json(update): Json<MedicationUpdate>,
+) -> Result<Json<MedicationView>, ApiError> {
+    let con = state.con;
+    let user_id = authenticate(con.clone(), &headers)?;
+    let (mut medication, patient) = get_medication(con.clone(), &medication_id, &user_id, true)?;
+
+    if let Some(dosage) = update.dosage.as_deref() {
+        check_dosage(dosage, &patient)?;
+
+        match (WeightDose::parse(dosage), patient.weight_kg) {
+            (Ok(weight_dose), Some(weight_kg)) => {
+                medication.set_weight_dose(weight_dose, weight_kg)
+            }
+            _ => medication.set_dosage(
+                Dosage::parse(dosage).map_err(|e| ApiError::BadRequest(e.to_string()))?,
+            ),
+        }
+    }
+
+    if let Some(frequency) = update.frequency.as_deref() {
+        medication.set_frequency(parse_frequency(frequency)?);
+    }
+
+    medication.save(con.clone())?;
+
+    // reloaded so can_take takes the new frequency into account
+    let medication = Medication::get_by_id(&medication.id, con)?;
+
+    Ok(Json(MedicationView::from(&medication)))
+}
+
+async fn create_intake(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Path(medication_id): Path<String>,
+    intake: Option<Json<NewIntake>>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    let con = state.con;
+    let user_id = authenticate(con.clone(), &headers)?;
+    let (medication, _) = get_medication(con.clone(), &medication_id, &user_id, true)?;
+
+    let now = Utc::now().timestamp();
+    let taken_at = intake
+        .and_then(|Json(intake)| intake.taken_at)
+        .unwrap_or(now);
+
+    if taken_at > now + 60 {
+        return Err(ApiError::BadRequest(
+            "taken_at can't be in the future".to_string(),
+        ));
+    }
+
+    if taken_at < now - MAX_MINUTES_AGO * 60 {
+        return Err(ApiError::BadRequest(format!(
+            "taken_at can't be more than {} hours ago",
+            MAX_MINUTES_AGO / 60
+        )));
+    }
+
+    // everyone else with access is notified, like when it's registered in Telegram
+    let message = register_intake(
+        &state.bot,
+        con.clone(),
+        &medication.id,
+        taken_at,
+        &user_id,
+        &user_id,
+        get_user_language(con.clone(), &user_id),
+    )
+    .await
+    .map_err(|e| {
+        log::warn!("Failed to register intake from the API. Error {}", e);
+        ApiError::Internal
+    })?;
+
+    let medication = Medication::get_by_id(&medication.id, con)?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "medication": MedicationView::from(&medication),
+            "message": message,
+        })),
+    ))
+}
+
+/// Routes of the JSON API, authenticated with the token each user gets from /apitoken.
+pub fn router(bot: Bot, con: Arc<Mutex<Connection>>) -> Router {
+    Router::new()
+        .route("/api/v1/patients", get(list_patients).post(create_patient))
+        .route("/api/v1/patients/:patient_id", patch(update_patient))
+        .route(
+            "/api/v1/patients/:patient_id/medications",
+            get(list_medications).post(create_medication),
+        )
+        .route(
+            "/api/v1/medications/:medication_id",
+            patch(update_medication),
+        )
+        .route(
+            "/api/v1/medications/:medication_id/intakes",
+            post(create_intake),
+        )
+        .with_state(ApiState { bot, con })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patient::Role;
+    use crate::user::get_user_token;
+
+    fn create_redis_connection() -> redis::Connection {
+        // creating a real connection actually
+        let client = redis::Client::open("redis://127.0.0.1/").expect("Could not connect to Redis");
+        let mut redis_connection = client
+            .get_connection()
+            .expect("Could not get a Redis connection");
+
+        redis::cmd("SELECT")
+            .arg(1) // selecting db 1 for tests to preserve data on the other one (default, 0)
+            .exec(&mut redis_connection)
+            .unwrap();
+        redis::cmd("FLUSHDB").exec(&mut redis_connection).unwrap();
+
+        redis_connection
+    }
+
+    #[test]
+    fn test_authenticate() {
+        let con = Arc::new(Mutex::new(create_redis_connection()));
+        let token = get_user_token(con.clone(), "user", API_TOKEN).unwrap();
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            authenticate(con.clone(), &headers),
+            Err(ApiError::Unauthorized)
+        );
+
+        headers.insert(AUTHORIZATION, format!("Bearer {}", token).parse().unwrap());
+        assert_eq!(authenticate(con.clone(), &headers), Ok("user".to_string()));
+
+        // dashboard links don't open the API
+        let dashboard = get_user_token(con.clone(), "user", "dashboard").unwrap();
+        headers.insert(
+            AUTHORIZATION,
+            format!("Bearer {}", dashboard).parse().unwrap(),
+        );
+        assert_eq!(
+            authenticate(con.clone(), &headers),
+            Err(ApiError::Unauthorized)
+        );
+    }
+
+    #[test]
+    fn test_get_patient_checks_the_role() {
+        let con = Arc::new(Mutex::new(create_redis_connection()));
+
+        let mut patient = Patient::new("Xavi".to_string(), "1".to_string());
+        patient
+            .share_with_role(2, Role::Viewer, con.clone())
+            .unwrap();
+        patient.save(con.clone()).unwrap();
+
+        assert!(get_patient(con.clone(), &patient.id, "1", true).is_ok());
+        assert!(get_patient(con.clone(), &patient.id, "2", false).is_ok());
+        assert_eq!(
+            get_patient(con.clone(), &patient.id, "2", true).err(),
+            Some(ApiError::Forbidden)
+        );
+        assert_eq!(
+            get_patient(con.clone(), &patient.id, "3", false).err(),
+            Some(ApiError::NotFound)
+        );
+        assert_eq!(
+            get_patient(con.clone(), "missing", "1", false).err(),
+            Some(ApiError::NotFound)
+        );
+    }
+
+    #[test]
+    fn test_interactions_have_to_be_confirmed() {
+        let con = Arc::new(Mutex::new(create_redis_connection()));
+
+        let patient = Patient::new("Xavi".to_string(), "1".to_string());
+        patient.save(con.clone()).unwrap();
+
+        let mut warfarin = build_medication(
+            con.clone(),
+            patient.id.clone(),
+            "Warfarin".to_string(),
+            "5mg".to_string(),
+            Frequency::new(24 * 60),
+            "1".to_string(),
+        );
+        assert_eq!(
+            check_interactions(&warfarin, false, Language::English, con.clone()),
+            Ok(())
+        );
+        warfarin.save(con.clone()).unwrap();
+
+        let ibuprofen = build_medication(
+            con.clone(),
+            patient.id.clone(),
+            "Ibuprofen".to_string(),
+            "200mg".to_string(),
+            Frequency::new(8 * 60),
+            "1".to_string(),
+        );
+        assert!(matches!(
+            check_interactions(&ibuprofen, false, Language::English, con.clone()),
+            Err(ApiError::Interactions(interactions)) if interactions.len() == 1
+        ));
+        assert_eq!(
+            check_interactions(&ibuprofen, true, Language::English, con.clone()),
+            Ok(())
+        );
+    }
+}
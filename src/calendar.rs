@@ -0,0 +1,204 @@
+use std::fmt::Write;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use chrono_tz::Tz;
+
+use crate::medication::Medication;
+
+pub const DEFAULT_CALENDAR_DAYS: i64 = 7;
+pub const MAX_CALENDAR_DAYS: i64 = 90;
+
+// how long each dose shows up for in the calendar
+const EVENT_MINUTES: i64 = 15;
+
+/// iCalendar with a repeating event per medication for the doses due over the next `days`,
+/// starting when each medication can be taken next. Daily plans repeat on the wall clock of the
+/// patient's timezone `tz`.
+pub fn render_calendar(
+    patient_name: &str,
+    medications: &[Medication],
+    days: i64,
+    tz: &Tz,
+) -> String {
+    let now = Utc::now();
+    let until = now + TimeDelta::days(days);
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//MediBot//Medication schedule//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!(
+            "X-WR-CALNAME:{}",
+            escape(&format!("{} medication", patient_name))
+        ),
+    ];
+
+    for medication in medications {
+        let minutes = medication.get_frequency().get_minutes();
+        let start = medication.get_can_take_next_date();
+
+        if minutes <= 0 || start >= until {
+            continue;
+        }
+
+        let frequency = medication.get_frequency();
+        let count = (until - start).num_minutes() / minutes + 1;
+
+        let recurrence = if medication.follows_waking_hours() {
+            // spread over the waking hours, so there's no fixed interval to repeat
+            let dates = medication
+                .get_due_times(until)
+                .into_iter()
+                .skip(1)
+                .map(format_date)
+                .collect::<Vec<String>>();
+
+            match dates.is_empty() {
+                true => None,
+                false => Some(format!("RDATE:{}", dates.join(","))),
+            }
+        } else if frequency.keeps_time_of_day() {
+            // the calendar keeps the time across DST changes, like the reminders
+            Some(format!(
+                "RRULE:FREQ=DAILY;INTERVAL={};COUNT={}",
+                minutes / (24 * 60),
+                count
+            ))
+        } else {
+            let rule = match minutes % 60 {
+                0 => format!("FREQ=HOURLY;INTERVAL={}", minutes / 60),
+                _ => format!("FREQ=MINUTELY;INTERVAL={}", minutes),
+            };
+
+            Some(format!("RRULE:{};COUNT={}", rule, count))
+        };
+
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            // stable so importing a newer export updates the series instead of duplicating it
+            format!("UID:{}@medibot", medication.id),
+            format!("DTSTAMP:{}", format_date(now)),
+            match frequency.keeps_time_of_day() {
+                true => format!(
+                    "DTSTART;TZID={}:{}",
+                    tz.name(),
+                    start.with_timezone(tz).format("%Y%m%dT%H%M%S")
+                ),
+                false => format!("DTSTART:{}", format_date(start)),
+            },
+            format!("DURATION:PT{}M", EVENT_MINUTES),
+        ]);
+        lines.extend(recurrence);
+        lines.extend([
+            format!(
+                "SUMMARY:{}",
+                escape(&format!(
+                    "{} {} for {}",
+                    medication.medicine, medication.dosage, patient_name
+                ))
+            ),
+            "BEGIN:VALARM".to_string(),
+            "ACTION:DISPLAY".to_string(),
+            format!("DESCRIPTION:{}", escape(&medication.medicine)),
+            "TRIGGER:PT0M".to_string(),
+            "END:VALARM".to_string(),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().fold(String::new(), |mut ics, line| {
+        let _ = write!(ics, "{}\r\n", fold(line));
+        ics
+    })
+}
+
+fn format_date(date: DateTime<Utc>) -> String {
+    date.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// lines can't be longer than 75 bytes, longer ones continue on the next line after a space
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+
+        folded.push(c);
+        length += c.len_utf8();
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dosage::Dosage;
+    use crate::frequency::Frequency;
+
+    #[test]
+    fn test_render_calendar() {
+        let mut medication = Medication::new(
+            "patient".to_string(),
+            "Calpol, strawberry".to_string(),
+            Dosage::parse("5ml").unwrap(),
+            Frequency::new(6 * 60),
+            "user".to_string(),
+        );
+        medication.last_taken = Some((Utc::now() - TimeDelta::hours(2)).timestamp());
+
+        let ics = render_calendar("Xavi", &[medication], 1, &Tz::UTC);
+
+        // due in 4, 10, 16 and 22 hours
+        assert!(ics.contains("\r\nRRULE:FREQ=HOURLY;INTERVAL=6;COUNT=4\r\n"));
+        assert!(ics.contains("\r\nSUMMARY:Calpol\\, strawberry 5 ml for Xavi\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn test_render_daily_calendar() {
+        let mut medication = Medication::new(
+            "patient".to_string(),
+            "Vitamin D".to_string(),
+            Dosage::parse("1 drop").unwrap(),
+            Frequency::new(2 * 24 * 60),
+            "user".to_string(),
+        );
+        medication.last_taken = Some((Utc::now() - TimeDelta::hours(2)).timestamp());
+
+        let tz: Tz = "Europe/Madrid".parse().unwrap();
+        let ics = render_calendar("Xavi", &[medication], 7, &tz);
+
+        // due in 46 hours and then every other day, at the same time in Madrid
+        let start = (Utc::now() + TimeDelta::hours(46)).with_timezone(&tz);
+        assert!(ics.contains(&format!(
+            "\r\nDTSTART;TZID=Europe/Madrid:{}",
+            start.format("%Y%m%dT%H")
+        )));
+        assert!(ics.contains("\r\nRRULE:FREQ=DAILY;INTERVAL=2;COUNT=3\r\n"));
+    }
+
+    #[test]
+    fn test_fold() {
+        let line = "a".repeat(100);
+
+        assert_eq!(
+            fold(&line),
+            format!("{}\r\n {}", "a".repeat(75), "a".repeat(25))
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use redis::{Commands, Connection, RedisError};
+
+// bundled so ingredients can be resolved offline, one medicine per line
+const MEDICINES_CSV: &str = include_str!("data/medicines.csv");
+
+fn get_bundled_catalog() -> &'static HashMap<String, Vec<String>> {
+    static CATALOG: OnceLock<HashMap<String, Vec<String>>> = OnceLock::new();
+
+    CATALOG.get_or_init(|| {
+        MEDICINES_CSV
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let (name, ingredients) = line.split_once(',')?;
+                Some((
+                    name.trim().to_string(),
+                    ingredients
+                        .split(';')
+                        .map(|ingredient| ingredient.trim().to_string())
+                        .collect(),
+                ))
+            })
+            .collect()
+    })
+}
+
+fn normalize(medicine: &str) -> String {
+    medicine.trim().to_lowercase()
+}
+
+/// Ingredients of a medicine from the bundled catalog. Tries the whole name first and then its
+/// first word, so "Calpol 120mg" is still found.
+pub fn lookup_ingredients(medicine: &str) -> Option<Vec<String>> {
+    let catalog = get_bundled_catalog();
+    let name = normalize(medicine);
+
+    catalog.get(&name).cloned().or_else(|| {
+        name.split_whitespace()
+            .next()
+            .and_then(|first| catalog.get(first).cloned())
+    })
+}
+
+/// Ingredients the user entered take precedence over the bundled catalog. They're only theirs,
+/// so nobody else's safety checks can be changed with them.
+pub fn get_ingredients(
+    medicine: &str,
+    user_id: &str,
+    con: Arc<Mutex<Connection>>,
+) -> Option<Vec<String>> {
+    let custom = con
+        .lock()
+        .unwrap()
+        .smembers::<String, Vec<String>>(format!(
+            "medi:{}:ingredients:{}",
+            user_id,
+            normalize(medicine)
+        ))
+        .unwrap_or_default();
+
+    if custom.is_empty() {
+        lookup_ingredients(medicine)
+    } else {
+        Some(custom)
+    }
+}
+
+/// Replaces the ingredients the user entered before for the medicine, if any.
+pub fn save_ingredients(
+    medicine: &str,
+    user_id: &str,
+    ingredients: &[String],
+    con: Arc<Mutex<Connection>>,
+) -> Result<(), RedisError> {
+    let key = format!("medi:{}:ingredients:{}", user_id, normalize(medicine));
+
+    redis::pipe()
+        .atomic()
+        .del(&key)
+        .sadd(&key, ingredients)
+        .query::<()>(&mut *con.lock().unwrap())
+}
+
+// paracetamol, caffeine
+// ibuprofen and codeine
+pub fn parse_ingredients(ingredients: &str) -> Vec<String> {
+    ingredients
+        .to_lowercase()
+        .replace(" and ", ",")
+        .split([',', ';', '+'])
+        .map(|ingredient| ingredient.trim().to_string())
+        .filter(|ingredient| !ingredient.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_redis_connection() -> redis::Connection {
+        // creating a real connection actually
+        let client = redis::Client::open("redis://127.0.0.1/").expect("Could not connect to Redis");
+        let mut redis_connection = client
+            .get_connection()
+            .expect("Could not get a Redis connection");
+
+        redis::cmd("SELECT")
+            .arg(1) // selecting db 1 for tests to preserve data on the other one (default, 0)
+            .exec(&mut redis_connection)
+            .unwrap();
+        redis::cmd("FLUSHDB").exec(&mut redis_connection).unwrap();
+
+        redis_connection
+    }
+
+    #[test]
+    fn test_lookup_ingredients() {
+        assert_eq!(
+            lookup_ingredients("Calpol"),
+            Some(vec!["paracetamol".to_string()])
+        );
+        assert_eq!(
+            lookup_ingredients("Nurofen for children"),
+            Some(vec!["ibuprofen".to_string()])
+        );
+        assert_eq!(lookup_ingredients("magic potion"), None);
+    }
+
+    #[test]
+    fn test_parse_ingredients() {
+        assert_eq!(
+            parse_ingredients("Paracetamol, caffeine"),
+            vec!["paracetamol".to_string(), "caffeine".to_string()]
+        );
+        assert_eq!(
+            parse_ingredients("ibuprofen and codeine"),
+            vec!["ibuprofen".to_string(), "codeine".to_string()]
+        );
+        assert!(parse_ingredients(" , ").is_empty());
+    }
+
+    #[test]
+    fn test_ingredients_are_per_user() {
+        let redis_con = Arc::new(Mutex::new(create_redis_connection()));
+
+        save_ingredients(
+            "Magic Potion",
+            "1234",
+            &["unicorn".to_string()],
+            redis_con.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_ingredients("magic potion", "1234", redis_con.clone()),
+            Some(vec!["unicorn".to_string()])
+        );
+        assert_eq!(
+            get_ingredients("Magic Potion", "5678", redis_con.clone()),
+            None
+        );
+
+        // nor can anyone change what the bundled medicines contain for everyone else
+        save_ingredients("Calpol", "1234", &["water".to_string()], redis_con.clone()).unwrap();
+        assert_eq!(
+            get_ingredients("Calpol", "5678", redis_con.clone()),
+            Some(vec!["paracetamol".to_string()])
+        );
+    }
+    #[test]
+    fn test_ingredients_are_replaced() {
+        let redis_con = Arc::new(Mutex::new(create_redis_connection()));
+
+        save_ingredients(
+            "Magic Potion",
+            "1234",
+            &["unicorn".to_string(), "dragon".to_string()],
+            redis_con.clone(),
+        )
+        .unwrap();
+        save_ingredients(
+            "Magic Potion",
+            "1234",
+            &["fairy dust".to_string()],
+            redis_con.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_ingredients("Magic Potion", "1234", redis_con.clone()),
+            Some(vec!["fairy dust".to_string()])
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use chrono::{DateTime, NaiveDate, Timelike};
+use chrono_tz::Tz;
+
+use crate::stats::Dose;
+
+pub const CHART_DAYS: i64 = 14;
+
+// one column per 15 minutes, one row per day
+const SLOT_MINUTES: u32 = 15;
+const SLOTS_PER_DAY: u32 = 24 * 60 / SLOT_MINUTES;
+const SLOT_WIDTH: u32 = 6;
+const ROW_HEIGHT: u32 = 18;
+const MARGIN: u32 = 10;
+
+const BACKGROUND: [u8; 3] = [255, 255, 255];
+const ALTERNATE_ROW: [u8; 3] = [244, 244, 244];
+const HOUR_LINE: [u8; 3] = [225, 225, 225];
+const QUARTER_DAY_LINE: [u8; 3] = [160, 160, 160];
+const MISSED: [u8; 3] = [240, 150, 150];
+const ON_TIME: [u8; 3] = [46, 160, 67];
+const LATE: [u8; 3] = [235, 140, 20];
+
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32) -> Self {
+        Canvas {
+            width,
+            height,
+            pixels: BACKGROUND.repeat((width * height) as usize),
+        }
+    }
+
+    fn fill(&mut self, x: u32, y: u32, width: u32, height: u32, color: [u8; 3]) {
+        for row in y..(y + height).min(self.height) {
+            for column in x..(x + width).min(self.width) {
+                let i = ((row * self.width + column) * 3) as usize;
+                self.pixels[i..i + 3].copy_from_slice(&color);
+            }
+        }
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, png::EncodingError> {
+        let mut png_bytes = vec![];
+
+        let mut encoder = png::Encoder::new(&mut png_bytes, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.pixels)?;
+
+        Ok(png_bytes)
+    }
+}
+
+/// Timeline of the last `CHART_DAYS` days up to `today`, a row per day with the oldest at the
+/// top. Intakes are green, or orange when late, and missed doses are red.
+pub fn render_intake_chart(
+    intakes: &[i64],
+    doses: &[Dose],
+    tz: &Tz,
+    today: NaiveDate,
+) -> Result<Vec<u8>, png::EncodingError> {
+    let width = SLOTS_PER_DAY * SLOT_WIDTH + 2 * MARGIN;
+    let height = CHART_DAYS as u32 * ROW_HEIGHT + 2 * MARGIN;
+    let mut canvas = Canvas::new(width, height);
+
+    for day in (0..CHART_DAYS as u32).filter(|day| day % 2 == 1) {
+        canvas.fill(
+            MARGIN,
+            MARGIN + day * ROW_HEIGHT,
+            SLOTS_PER_DAY * SLOT_WIDTH,
+            ROW_HEIGHT,
+            ALTERNATE_ROW,
+        );
+    }
+
+    for hour in 0..=24 {
+        let color = if hour % 6 == 0 {
+            QUARTER_DAY_LINE
+        } else {
+            HOUR_LINE
+        };
+
+        canvas.fill(
+            MARGIN + hour * SLOT_WIDTH * 60 / SLOT_MINUTES,
+            MARGIN,
+            1,
+            CHART_DAYS as u32 * ROW_HEIGHT,
+            color,
+        );
+    }
+
+    let mut mark = |ts: i64, color: [u8; 3]| {
+        if let Some((row, slot)) = get_position(ts, tz, today) {
+            canvas.fill(
+                MARGIN + slot * SLOT_WIDTH,
+                MARGIN + row * ROW_HEIGHT + 2,
+                SLOT_WIDTH,
+                ROW_HEIGHT - 4,
+                color,
+            );
+        }
+    };
+
+    for dose in doses.iter().filter(|dose| dose.taken.is_none()) {
+        mark(dose.due, MISSED);
+    }
+
+    for ts in intakes {
+        mark(*ts, ON_TIME);
+    }
+
+    for dose in doses.iter().filter(|dose| dose.is_late()) {
+        mark(dose.taken.unwrap(), LATE);
+    }
+
+    canvas.encode()
+}
+
+fn get_position(ts: i64, tz: &Tz, today: NaiveDate) -> Option<(u32, u32)> {
+    let local = DateTime::from_timestamp(ts, 0)?.with_timezone(tz);
+    let days_ago = (today - local.date_naive()).num_days();
+
+    if !(0..CHART_DAYS).contains(&days_ago) {
+        return None;
+    }
+
+    let row = (CHART_DAYS - 1 - days_ago) as u32;
+    let slot = (local.hour() * 60 + local.minute()) / SLOT_MINUTES;
+
+    Some((row, slot))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_intake_chart() {
+        let today = NaiveDate::from_ymd_opt(2024, 11, 20).unwrap();
+        let tz: Tz = "Europe/Madrid".parse().unwrap();
+
+        // 2024-11-20 09:00 and 15:00 in Madrid
+        let intakes = [1732089600, 1732111200];
+        let doses = [
+            Dose {
+                due: 1732089600,
+                taken: Some(1732089600),
+            },
+            Dose {
+                due: 1732107600,
+                taken: Some(1732111200),
+            },
+        ];
+
+        let png_bytes = render_intake_chart(&intakes, &doses, &tz, today).unwrap();
+
+        let decoder = png::Decoder::new(png_bytes.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut pixels).unwrap();
+
+        assert_eq!(reader.info().width, SLOTS_PER_DAY * SLOT_WIDTH + 2 * MARGIN);
+
+        let pixel = |row: u32, slot: u32| {
+            let x = MARGIN + slot * SLOT_WIDTH + 1;
+            let y = MARGIN + row * ROW_HEIGHT + ROW_HEIGHT / 2;
+            let i = ((y * reader.info().width + x) * 3) as usize;
+            [pixels[i], pixels[i + 1], pixels[i + 2]]
+        };
+
+        // today is the last row
+        assert_eq!(pixel(13, 9 * 4), ON_TIME);
+        assert_eq!(pixel(13, 15 * 4), LATE);
+        assert_eq!(pixel(12, 9 * 4), BACKGROUND);
+    }
+}
@@ -1,44 +1,164 @@
 use crate::{
-    medication::Medication, patient::Patient, user::get_user_timezone, Command, ConfigParameters,
-    HandlerResult, MyDialogue,
+    api::API_TOKEN,
+    dashboard::{get_dashboard_link, DASHBOARD_TOKEN},
+    digest::{get_digest_time, parse_digest_time, set_default_digest_time, set_digest_time},
+    flows::timezone::ask_timezone,
+    i18n::{is_nothing, Language, Text},
+    invite::Invite,
+    medication::Medication,
+    patient::Patient,
+    timezone::parse_timezone,
+    user::{
+        get_user_language, get_user_timezone, get_user_token, has_user_timezone, revoke_user_token,
+        set_user_language, set_user_timezone,
+    },
+    ConfigParameters, HandlerResult, MyDialogue,
 };
-use chrono_tz::Tz;
-use redis::Commands;
+use medibot::State;
 use teloxide::{
     prelude::*,
     types::{KeyboardRemove, Message, ParseMode},
-    utils::command::BotCommands,
     Bot,
 };
 
-pub async fn start(bot: Bot, _dialogue: MyDialogue, msg: Message) -> HandlerResult {
-    bot.send_message(msg.chat.id,
-        "*Welcome to 💊 MediBot\\! 💉*\nI'll remind you of when to take your meds\\.\n\n".to_owned() +
-        "Add patients and medication plans with /addmedication\\. Register an intake with /take\\.\n\n" +
-        "Type /help to see all available commands\\."
-    )
-    .parse_mode(ParseMode::MarkdownV2)
+pub async fn start(
+    cfg: ConfigParameters,
+    bot: Bot,
+    dialogue: MyDialogue,
+    token: String,
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    let con = cfg.redis_connection.clone();
+    let user_id = msg.chat.id.to_string();
+
+    if !token.is_empty() {
+        redeem_invite(cfg, bot.clone(), token, lang, msg.clone()).await?;
+    } else {
+        set_default_digest_time(con.clone(), &user_id)?;
+
+        bot.send_message(msg.chat.id, lang.get(Text::Welcome))
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+    }
+
+    // reminders would be off by hours with the UTC fallback
+    if !has_user_timezone(con, &user_id) {
+        ask_timezone(&bot, msg.chat.id, lang).await?;
+        dialogue.update(State::SelectTimezone).await?;
+    }
 
-        .await?;
     Ok(())
 }
 
-pub async fn help(bot: Bot, msg: Message) -> HandlerResult {
-    bot.send_message(msg.chat.id, Command::descriptions().to_string())
-        .await?;
+async fn redeem_invite(
+    cfg: ConfigParameters,
+    bot: Bot,
+    token: String,
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    let con = cfg.redis_connection;
+    let user_id = msg.chat.id.to_string();
+
+    let invite = Invite::get(&token, con.clone())?;
+    let patient = invite
+        .as_ref()
+        .and_then(|invite| Patient::get_by_id(&invite.patient_id, con.clone()).ok());
+
+    match (invite, patient) {
+        (Some(invite), Some(mut patient)) => {
+            if patient.get_role(&user_id).is_some() {
+                bot.send_message(
+                    msg.chat.id,
+                    lang.fill(Text::AlreadyHasAccess, &[("patient", &patient.name)]),
+                )
+                .await?;
+                return Ok(());
+            }
+
+            // someone else could have redeemed it in the meantime
+            if !invite.consume(con.clone())? {
+                bot.send_message(msg.chat.id, lang.get(Text::InviteExpired))
+                    .await?;
+                return Ok(());
+            }
+
+            patient.share_with_role(msg.chat.id.0 as u64, invite.role, con.clone())?;
+            patient
+                .save(con.clone())
+                .expect("Error saving patient after redeeming invite");
+            set_default_digest_time(con.clone(), &user_id)?;
+
+            bot.send_message(
+                msg.chat.id,
+                lang.fill(
+                    Text::InviteRedeemed,
+                    &[
+                        ("patient", &patient.name),
+                        ("role", &invite.role.print(lang)),
+                    ],
+                ),
+            )
+            .await?;
+
+            let name = msg
+                .from
+                .map(|user| user.full_name())
+                .unwrap_or(user_id.clone());
+
+            let inviter_lang = get_user_language(con.clone(), &invite.created_by);
+
+            if let Err(e) = bot
+                .send_message(
+                    invite.created_by.clone(),
+                    inviter_lang.fill(
+                        Text::InviteAccepted,
+                        &[
+                            ("name", &name),
+                            ("patient", &patient.name),
+                            ("role", &invite.role.print(inviter_lang)),
+                        ],
+                    ),
+                )
+                .await
+            {
+                log::warn!(
+                    "Failed to notify inviting user: telegram user id {}. Error {}",
+                    &invite.created_by,
+                    e
+                )
+            }
+        }
+        _ => {
+            bot.send_message(msg.chat.id, lang.get(Text::InviteExpired))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn help(bot: Bot, lang: Language, msg: Message) -> HandlerResult {
+    bot.send_message(msg.chat.id, lang.print_help()).await?;
     Ok(())
 }
 
-pub async fn cancel(bot: Bot, dialogue: MyDialogue, msg: Message) -> HandlerResult {
-    bot.send_message(msg.chat.id, "Cancelling the current operation.")
+pub async fn cancel(bot: Bot, dialogue: MyDialogue, lang: Language, msg: Message) -> HandlerResult {
+    bot.send_message(msg.chat.id, lang.get(Text::Cancelling))
         .reply_markup(KeyboardRemove::new())
         .await?;
     dialogue.exit().await?;
     Ok(())
 }
 
-pub async fn cancel_with_edit(bot: Bot, dialogue: MyDialogue, msg: Message) -> HandlerResult {
-    bot.edit_message_text(msg.chat.id, msg.id, "Cancelling the current operation.")
+pub async fn cancel_with_edit(
+    bot: Bot,
+    dialogue: MyDialogue,
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    bot.edit_message_text(msg.chat.id, msg.id, lang.get(Text::Cancelling))
         .await?;
     dialogue.exit().await?;
     Ok(())
@@ -48,28 +168,45 @@ pub async fn get_all_command(
     cfg: ConfigParameters,
     bot: Bot,
     _: MyDialogue,
+    lang: Language,
     msg: Message,
 ) -> HandlerResult {
     let con = cfg.redis_connection.clone();
-
-    let all_patients: Vec<Patient> =
-        Patient::get_my_patients(&msg.chat.id.to_string(), con.clone()).unwrap();
+    let chat_id = msg.chat.id.to_string();
+
+    // in groups, the linked patients the user asking has access to
+    let all_patients: Vec<Patient> = match msg.chat.is_private() {
+        true => Patient::get_my_patients(&chat_id, con.clone()).unwrap(),
+        false => {
+            let user_id = msg.from.as_ref().map(|user| user.id.to_string());
+
+            Patient::get_group_patients(&chat_id, con.clone())
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|patient| {
+                    user_id
+                        .as_ref()
+                        .is_some_and(|user_id| patient.get_role(user_id).is_some())
+                })
+                .collect()
+        }
+    };
 
     let outgoing_msg = all_patients
         .iter()
         .map(|p| {
             let mut meds = Medication::get_all_by_patient_id(&p.id, con.clone());
 
-            let tz = get_user_timezone(con.clone(), &msg.chat.id.to_string());
+            let tz = get_user_timezone(con.clone(), &chat_id);
 
             meds.sort_by_key(|m| m.last_taken);
             meds.reverse();
 
             let listprint = match meds.len() {
-                0 => " - No medications taken yet.\n".to_string(),
+                0 => format!("{}\n", lang.get(Text::NoMedicationsTaken)),
                 _ => meds
                     .iter()
-                    .map(|m| m.print_in_list(&tz) + "\n")
+                    .map(|m| m.print_in_list(&tz, lang) + "\n")
                     .collect::<String>(),
             };
 
@@ -77,12 +214,12 @@ pub async fn get_all_command(
         })
         .collect::<String>();
 
-    if outgoing_msg.len() == 0 {
-        bot.send_message(
-            msg.chat.id,
-            "No medications added yet - try /addmedication to start.",
-        )
-        .await?;
+    if outgoing_msg.is_empty() && !msg.chat.is_private() {
+        bot.send_message(msg.chat.id, lang.get(Text::NoLinkedPatients))
+            .await?;
+    } else if outgoing_msg.is_empty() {
+        bot.send_message(msg.chat.id, lang.get(Text::NoMedications))
+            .await?;
     } else {
         bot.send_message(msg.chat.id, outgoing_msg).await?;
     }
@@ -90,31 +227,208 @@ pub async fn get_all_command(
     Ok(())
 }
 
-pub async fn set_timezone(
+pub async fn stats_command(
     cfg: ConfigParameters,
     bot: Bot,
     _: MyDialogue,
-    timezone: String,
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    let con = cfg.redis_connection.clone();
+
+    let all_patients: Vec<Patient> =
+        Patient::get_my_patients(&msg.chat.id.to_string(), con.clone()).unwrap();
+
+    let outgoing_msg = all_patients
+        .iter()
+        .map(|p| {
+            let meds = Medication::get_all_by_patient_id(&p.id, con.clone());
+
+            let summary = meds
+                .iter()
+                .map(|m| {
+                    let intakes = m.get_intake_history(con.clone()).unwrap_or_default();
+
+                    match (m.get_adherence(&intakes, 7), m.get_adherence(&intakes, 30)) {
+                        (Some(week), Some(month)) => format!(
+                            "{} ({}, {})\n - {}: {}\n - {}: {}\n",
+                            m.medicine,
+                            m.dosage,
+                            m.get_frequency().print(lang),
+                            lang.fill(Text::Days, &[("days", &7)]),
+                            week.print(lang),
+                            lang.fill(Text::Days, &[("days", &30)]),
+                            month.print(lang)
+                        ),
+                        _ => format!(
+                            "{}\n",
+                            lang.fill(Text::StatsNotTaken, &[("medicine", &m.medicine)])
+                        ),
+                    }
+                })
+                .collect::<String>();
+
+            match summary.is_empty() {
+                true => format!("{}\n{}\n\n", p.name, lang.get(Text::StatsNoMedications)),
+                false => format!("{}\n{}\n", p.name, summary),
+            }
+        })
+        .collect::<String>();
+
+    if outgoing_msg.is_empty() {
+        bot.send_message(msg.chat.id, lang.get(Text::NoMedications))
+            .await?;
+    } else {
+        bot.send_message(msg.chat.id, outgoing_msg).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn digest_command(
+    cfg: ConfigParameters,
+    bot: Bot,
+    _: MyDialogue,
+    time: String,
+    lang: Language,
     msg: Message,
 ) -> HandlerResult {
-    let tz: Result<Tz, chrono_tz::ParseError> = timezone.parse();
-    match tz {
-        Ok(_) => {
-            // save to redis
-            let con = cfg.redis_connection.clone();
-
-            con.lock().unwrap().set::<String, String, ()>(
-                format!("medi:{}:timezone", msg.from.unwrap().id),
-                timezone.clone(),
-            )?;
-
-            bot.send_message(msg.chat.id, format!("Timezone set for {}", &timezone))
+    let con = cfg.redis_connection.clone();
+    let user_id = msg.chat.id.to_string();
+
+    // a group's digest is set by those who can edit its patients, like linking them
+    if !msg.chat.is_private() {
+        let sender_id = msg.from.as_ref().map(|user| user.id.to_string());
+        let can_edit = Patient::get_group_patients(&user_id, con.clone())
+            .unwrap_or_default()
+            .iter()
+            .any(|patient| sender_id.as_ref().is_some_and(|id| patient.can_edit(id)));
+
+        if !can_edit {
+            bot.send_message(msg.chat.id, lang.get(Text::NoLinkedPatients))
                 .await?;
+            return Ok(());
+        }
+    }
+
+    let reply = if time.trim().is_empty() {
+        match get_digest_time(con.clone(), &user_id) {
+            Some(time) => lang.fill(Text::DigestOn, &[("time", &time.format("%H:%M"))]),
+            None => lang.get(Text::DigestIsOff).to_string(),
+        }
+    } else if is_nothing(&time) {
+        set_digest_time(con.clone(), &user_id, None)?;
+        lang.get(Text::DigestTurnedOff).to_string()
+    } else {
+        match parse_digest_time(&time) {
+            Some(time) => {
+                set_digest_time(con.clone(), &user_id, Some(time))?;
+                lang.fill(
+                    Text::DigestSet,
+                    &[
+                        ("time", &time.format("%H:%M")),
+                        ("timezone", &get_user_timezone(con.clone(), &user_id)),
+                    ],
+                )
+            }
+            None => lang.fill(Text::DigestUnknownTime, &[("time", &time)]),
+        }
+    };
+
+    bot.send_message(msg.chat.id, reply).await?;
+
+    Ok(())
+}
+
+pub async fn dashboard_command(
+    cfg: ConfigParameters,
+    bot: Bot,
+    _: MyDialogue,
+    answer: String,
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    let con = cfg.redis_connection.clone();
+    let user_id = msg.chat.id.to_string();
+
+    // only served along with the webhook
+    let Some(base) = cfg.dashboard_url.as_ref() else {
+        bot.send_message(msg.chat.id, lang.get(Text::DashboardUnavailable))
+            .await?;
+        return Ok(());
+    };
+
+    let reply = if is_nothing(&answer) {
+        revoke_user_token(con, &user_id, DASHBOARD_TOKEN)?;
+        lang.get(Text::DashboardRevoked).to_string()
+    } else {
+        let token = get_user_token(con, &user_id, DASHBOARD_TOKEN)?;
+
+        match get_dashboard_link(base, &token) {
+            Some(link) => lang.fill(Text::DashboardLink, &[("link", &link)]),
+            None => lang.get(Text::DashboardUnavailable).to_string(),
+        }
+    };
+
+    bot.send_message(msg.chat.id, reply).await?;
+
+    Ok(())
+}
+
+pub async fn api_token_command(
+    cfg: ConfigParameters,
+    bot: Bot,
+    _: MyDialogue,
+    answer: String,
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    let con = cfg.redis_connection.clone();
+    let user_id = msg.chat.id.to_string();
+
+    let reply = if !cfg.api_enabled {
+        lang.get(Text::ApiUnavailable).to_string()
+    } else if is_nothing(&answer) {
+        revoke_user_token(con, &user_id, API_TOKEN)?;
+        lang.get(Text::ApiTokenRevoked).to_string()
+    } else {
+        let token = get_user_token(con, &user_id, API_TOKEN)?;
+        lang.fill(Text::ApiToken, &[("token", &token)])
+    };
+
+    bot.send_message(msg.chat.id, reply).await?;
+
+    Ok(())
+}
+
+pub async fn set_timezone(
+    cfg: ConfigParameters,
+    bot: Bot,
+    dialogue: MyDialogue,
+    timezone: String,
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    if timezone.trim().is_empty() {
+        ask_timezone(&bot, msg.chat.id, lang).await?;
+        dialogue.update(State::SelectTimezone).await?;
+        return Ok(());
+    }
+
+    match parse_timezone(&timezone) {
+        Some(tz) => {
+            set_user_timezone(cfg.redis_connection, &msg.chat.id.to_string(), &tz)?;
+
+            bot.send_message(
+                msg.chat.id,
+                lang.fill(Text::TimezoneSet, &[("timezone", &tz.name())]),
+            )
+            .await?;
         }
-        Err(_) => {
+        None => {
             bot.send_message(
                 msg.chat.id,
-                format!("Sorry, I don't recognise the timezone: {}", timezone),
+                lang.fill(Text::TimezoneUnknown, &[("timezone", &timezone.trim())]),
             )
             .await?;
         }
@@ -123,10 +437,47 @@ pub async fn set_timezone(
     Ok(())
 }
 
-#[cfg(test)]
+pub async fn language_command(
+    cfg: ConfigParameters,
+    bot: Bot,
+    _: MyDialogue,
+    language: String,
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    let languages = Language::ALL
+        .iter()
+        .map(|language| format!("{} ({})", language.code(), language.name()))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    let reply = if language.trim().is_empty() {
+        lang.fill(
+            Text::LanguageCurrent,
+            &[("language", &lang.name()), ("languages", &languages)],
+        )
+    } else {
+        match Language::parse(&language) {
+            Some(language) => {
+                set_user_language(cfg.redis_connection, &msg.chat.id.to_string(), language)?;
+                language.get(Text::LanguageSet).to_string()
+            }
+            None => lang.fill(
+                Text::LanguageUnknown,
+                &[("language", &language.trim()), ("languages", &languages)],
+            ),
+        }
+    };
+
+    bot.send_message(msg.chat.id, reply).await?;
 
+    Ok(())
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dosage::Dosage;
     use crate::frequency::Frequency;
     use std::sync::{Arc, Mutex};
 
@@ -156,8 +507,8 @@ mod tests {
         let mut medication = Medication::new(
             patient.id.clone(),
             "nurofen".to_string(),
-            "5ml".to_string(),
-            Frequency::new(3),
+            Dosage::parse("5ml").unwrap(),
+            Frequency::new(3 * 60),
             user_id.clone(),
         );
 
@@ -173,7 +524,7 @@ mod tests {
         assert_eq!(all_records.len(), 1);
 
         let other_records: Vec<Medication> =
-            Medication::get_all_by_patient_id(&"hello", redis_con.clone());
+            Medication::get_all_by_patient_id("hello", redis_con.clone());
         assert_eq!(other_records.len(), 0);
     }
 }
@@ -0,0 +1,251 @@
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Html,
+    routing::get,
+    Router,
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use chrono_tz::Tz;
+use redis::Connection;
+use url::Url;
+
+use crate::{
+    i18n::{Language, Text},
+    medication::Medication,
+    patient::Patient,
+    report::escape,
+    user::{get_token_user, get_user_language, get_user_timezone},
+};
+
+// the page reloads itself, so it can be left open on a screen
+const REFRESH_SECONDS: i64 = 300;
+const HISTORY_DAYS: i64 = 7;
+
+const STYLE: &str = "body{font-family:sans-serif;font-size:18px;margin:1em 2em}\
+h1{font-size:26px}h2{font-size:22px;margin-top:1.5em}\
+table{border-collapse:collapse;width:100%}th,td{border-bottom:1px solid #ccc;padding:6px;text-align:left}\
+.updated{color:#777}";
+
+// kind of the user tokens opening dashboards
+pub const DASHBOARD_TOKEN: &str = "dashboard";
+
+pub fn get_dashboard_link(base: &Url, token: &str) -> Option<Url> {
+    base.join(&format!("/dashboard/{}", token)).ok()
+}
+
+/// Read-only page with the `patients` the viewer has access to. `medications` and `intakes` hold
+/// the plans of each patient and the intake history of each plan, in the same order.
+pub fn render_dashboard(
+    patients: &[Patient],
+    medications: &[Vec<Medication>],
+    intakes: &[Vec<Vec<i64>>],
+    tz: &str,
+    lang: Language,
+) -> String {
+    let viewer_tz = tz.parse::<Tz>().unwrap_or(Tz::UTC);
+    let format_ts = |ts: i64| {
+        DateTime::from_timestamp(ts, 0)
+            .unwrap()
+            .with_timezone(&viewer_tz)
+            .format("%a %d %H:%M")
+            .to_string()
+    };
+
+    let mut html = String::new();
+
+    let _ = write!(
+        html,
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\">\
+        <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+        <meta http-equiv=\"refresh\" content=\"{REFRESH_SECONDS}\">\
+        <title>{title}</title><style>{STYLE}</style></head><body><h1>{title}</h1>\
+        <p class=\"updated\">{updated}</p>",
+        title = escape(lang.get(Text::DashboardTitle)),
+        updated = escape(&lang.fill(
+            Text::DashboardUpdated,
+            &[
+                (
+                    "time",
+                    &Utc::now().with_timezone(&viewer_tz).format("%H:%M")
+                ),
+                ("minutes", &(REFRESH_SECONDS / 60)),
+            ],
+        )),
+    );
+
+    if patients.is_empty() {
+        let _ = write!(html, "<p>{}</p>", escape(lang.get(Text::NoMedications)));
+    }
+
+    let since = (Utc::now() - TimeDelta::days(HISTORY_DAYS)).timestamp();
+
+    for ((patient, medications), intakes) in patients.iter().zip(medications).zip(intakes) {
+        let _ = write!(html, "<h2>{}</h2>", escape(&patient.name));
+
+        if medications.is_empty() {
+            let _ = write!(html, "<p>{}</p>", escape(lang.get(Text::NoMedications)));
+            continue;
+        }
+
+        html.push_str("<table><tr>");
+        for column in [
+            Text::ColumnMedicine,
+            Text::ColumnDosage,
+            Text::ColumnFrequency,
+            Text::ColumnLastTaken,
+            Text::ColumnNext,
+            Text::ColumnStock,
+        ] {
+            let _ = write!(html, "<th>{}</th>", escape(lang.get(column)));
+        }
+        html.push_str("</tr>");
+
+        for medication in medications {
+            let _ = write!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{} {}</td><td>{}</td></tr>",
+                escape(&medication.medicine),
+                escape(&medication.dosage.to_string()),
+                escape(&medication.get_frequency().print(lang)),
+                escape(&medication.print_last_taken(tz, lang)),
+                medication.can_take_emoji(),
+                escape(&medication.print_can_take_next(tz, lang)),
+                escape(&medication.print_stock(tz, lang).unwrap_or_default()),
+            );
+        }
+
+        html.push_str("</table>");
+
+        // the latest first, it's what's looked at the most
+        let mut history: Vec<(i64, &str)> = medications
+            .iter()
+            .zip(intakes)
+            .flat_map(|(medication, intakes)| {
+                intakes
+                    .iter()
+                    .filter(|ts| **ts >= since)
+                    .map(|ts| (*ts, medication.medicine.as_str()))
+            })
+            .collect();
+
+        history.sort_by_key(|(ts, _)| -ts);
+
+        let _ = write!(
+            html,
+            "<h3>{}</h3>",
+            escape(&lang.fill(Text::DashboardHistory, &[("days", &HISTORY_DAYS)]))
+        );
+
+        if history.is_empty() {
+            let _ = write!(
+                html,
+                "<p>{}</p>",
+                escape(lang.get(Text::DashboardNoIntakes))
+            );
+        } else {
+            html.push_str("<table>");
+            for (ts, medicine) in history {
+                let _ = write!(
+                    html,
+                    "<tr><td>{}</td><td>{}</td></tr>",
+                    format_ts(ts),
+                    escape(medicine)
+                );
+            }
+            html.push_str("</table>");
+        }
+    }
+
+    html.push_str("</body></html>");
+    html
+}
+
+async fn dashboard_page(
+    State(con): State<Arc<Mutex<Connection>>>,
+    Path(token): Path<String>,
+) -> (StatusCode, Html<String>) {
+    let Some(user_id) = get_token_user(con.clone(), DASHBOARD_TOKEN, &token) else {
+        // there's no way to tell the language of whoever opened it
+        return (
+            StatusCode::NOT_FOUND,
+            Html(escape(Language::default().get(Text::DashboardNotFound))),
+        );
+    };
+
+    let patients = Patient::get_my_patients(&user_id, con.clone()).unwrap_or_default();
+
+    let medications: Vec<Vec<Medication>> = patients
+        .iter()
+        .map(|patient| Medication::get_all_by_patient_id(&patient.id, con.clone()))
+        .collect();
+
+    let intakes: Vec<Vec<Vec<i64>>> = medications
+        .iter()
+        .map(|medications| {
+            medications
+                .iter()
+                .map(|medication| {
+                    medication
+                        .get_intake_history(con.clone())
+                        .unwrap_or_default()
+                })
+                .collect()
+        })
+        .collect();
+
+    let html = render_dashboard(
+        &patients,
+        &medications,
+        &intakes,
+        &get_user_timezone(con.clone(), &user_id),
+        get_user_language(con, &user_id),
+    );
+
+    (StatusCode::OK, Html(html))
+}
+
+/// Routes of the dashboard, served next to the webhook.
+pub fn router(con: Arc<Mutex<Connection>>) -> Router {
+    Router::new()
+        .route("/dashboard/:token", get(dashboard_page))
+        .with_state(con)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dosage::Dosage;
+    use crate::frequency::Frequency;
+
+    #[test]
+    fn test_render_dashboard() {
+        let patient = Patient::new("Xavi <3".to_string(), "user".to_string());
+        let medication = Medication::new(
+            patient.id.clone(),
+            "Calpol".to_string(),
+            Dosage::parse("5ml").unwrap(),
+            Frequency::new(6 * 60),
+            "user".to_string(),
+        );
+
+        let now = Utc::now().timestamp();
+        // the one from two weeks ago is left out of the history
+        let intakes = vec![now - 3600, now - 14 * 24 * 3600];
+
+        let html = render_dashboard(
+            &[patient],
+            &[vec![medication]],
+            &[vec![intakes]],
+            "UTC",
+            Language::English,
+        );
+
+        assert!(html.contains("<h2>Xavi &lt;3</h2>"));
+        assert!(html.contains("<td>Calpol</td><td>5 ml</td><td>every 6 hours</td>"));
+        assert_eq!(html.matches("<td>Calpol</td></tr>").count(), 1);
+    }
+}
@@ -0,0 +1,350 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta, Utc};
+use chrono_tz::Tz;
+use redis::{Commands, Connection, RedisError};
+use teloxide::{prelude::*, types::InlineKeyboardMarkup};
+
+use crate::{
+    flows::group::generate_group_intake_keyboard,
+    i18n::{Language, Text},
+    medication::Medication,
+    patient::Patient,
+    timezone::{print_patient_time, resolve_local},
+    user::{get_user_language, get_user_timezone},
+};
+
+const DEFAULT_DIGEST_TIME: &str = "08:00";
+
+// stored when turned off, so the default isn't applied again
+const DIGEST_OFF: &str = "off";
+
+// digests that couldn't go out on time (ie, the bot was down) are still sent within this window
+const DIGEST_WINDOW_MINUTES: i64 = 60;
+
+/// Digest time of every user that has it enabled, by user id.
+fn get_digest_times(con: Arc<Mutex<Connection>>) -> Vec<(String, NaiveTime)> {
+    con.lock()
+        .unwrap()
+        .hgetall::<&str, Vec<(String, String)>>("medi:digests")
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(user_id, time)| Some((user_id, parse_digest_time(&time)?)))
+        .collect()
+}
+
+pub fn get_digest_time(con: Arc<Mutex<Connection>>, user_id: &str) -> Option<NaiveTime> {
+    con.lock()
+        .unwrap()
+        .hget::<&str, &str, Option<String>>("medi:digests", user_id)
+        .ok()
+        .flatten()
+        .and_then(|time| parse_digest_time(&time))
+}
+
+pub fn set_digest_time(
+    con: Arc<Mutex<Connection>>,
+    user_id: &str,
+    time: Option<NaiveTime>,
+) -> Result<(), RedisError> {
+    let mut con = con.lock().unwrap();
+
+    match time {
+        Some(time) => con.hset::<&str, &str, String, ()>(
+            "medi:digests",
+            user_id,
+            time.format("%H:%M").to_string(),
+        ),
+        None => con.hset::<&str, &str, &str, ()>("medi:digests", user_id, DIGEST_OFF),
+    }
+}
+
+/// Turns the digest on at the default time, unless the user already set it or turned it off.
+pub fn set_default_digest_time(
+    con: Arc<Mutex<Connection>>,
+    user_id: &str,
+) -> Result<(), RedisError> {
+    con.lock().unwrap().hset_nx::<&str, &str, &str, ()>(
+        "medi:digests",
+        user_id,
+        DEFAULT_DIGEST_TIME,
+    )
+}
+
+/// Users with patients from before digests existed get the default one too. Only runs once.
+pub fn migrate_default_digests(con: Arc<Mutex<Connection>>) -> Result<(), RedisError> {
+    let user_ids: Vec<String> = {
+        let mut con = con.lock().unwrap();
+
+        if !con.set_nx::<&str, i64, bool>("medi:migrations:default_digests", 1)? {
+            return Ok(());
+        }
+
+        con.scan_match::<&str, String>("medi:user_patient:*")?
+            .filter_map(|key| Some(key.strip_prefix("medi:user_patient:")?.to_string()))
+            .collect()
+    };
+
+    for user_id in user_ids {
+        set_default_digest_time(con.clone(), &user_id)?;
+    }
+
+    Ok(())
+}
+
+// 8, 8:30, 08:30
+pub fn parse_digest_time(time: &str) -> Option<NaiveTime> {
+    let time = time.trim();
+
+    NaiveTime::parse_from_str(time, "%H:%M")
+        .ok()
+        .or_else(|| NaiveTime::from_hms_opt(time.parse().ok()?, 0, 0))
+}
+
+/// The day whose digest is due at `now`, yesterday's when it's sent after midnight.
+fn get_due_digest(now: DateTime<Utc>, time: NaiveTime, tz: &Tz) -> Option<NaiveDate> {
+    let today = now.with_timezone(tz).date_naive();
+
+    [Some(today), today.pred_opt()]
+        .into_iter()
+        .flatten()
+        .find(|date| {
+            let minutes_since = (now - resolve_local(date.and_time(time), tz)).num_minutes();
+            (0..DIGEST_WINDOW_MINUTES).contains(&minutes_since)
+        })
+}
+
+fn get_local_midnight(tz: &Tz, date: NaiveDate) -> DateTime<Utc> {
+    resolve_local(date.and_time(NaiveTime::MIN), tz)
+}
+
+/// A medication's doses for the patient's day, `tz` being the patient's timezone and `viewer_tz`
+/// the one of the user the digest is for.
+fn print_medication(
+    medication: &Medication,
+    intakes: &[i64],
+    tz: &Tz,
+    viewer_tz: &Tz,
+    today: NaiveDate,
+    lang: Language,
+) -> String {
+    let today_start = get_local_midnight(tz, today);
+    let tomorrow_start = get_local_midnight(tz, today + TimeDelta::days(1));
+    let yesterday_start = get_local_midnight(tz, today - TimeDelta::days(1));
+
+    let due_times = medication
+        .get_due_times(tomorrow_start)
+        .iter()
+        .map(|due| print_patient_time(*due, "%H:%M", tz, viewer_tz, lang))
+        .collect::<Vec<String>>();
+
+    let due = match due_times.len() {
+        0 => lang.get(Text::NothingDueToday).to_string(),
+        _ => lang.fill(Text::DueAt, &[("times", &due_times.join(", "))]),
+    };
+
+    // yesterday's doses, the last ones might have been missed only after midnight
+    let missed = medication
+        .get_schedule_since(intakes, yesterday_start)
+        .unwrap_or_default()
+        .iter()
+        .filter(|dose| dose.due < today_start.timestamp() && dose.taken.is_none())
+        .count();
+
+    let missed = match missed {
+        0 => "".to_string(),
+        1 => lang.get(Text::MissedOne).to_string(),
+        n => lang.fill(Text::MissedMany, &[("count", &n)]),
+    };
+
+    format!(
+        " - {} ({}): {}.{}\n",
+        medication.medicine, medication.dosage, due, missed
+    )
+}
+
+pub fn print_digest(
+    con: Arc<Mutex<Connection>>,
+    user_id: &str,
+    tz: &Tz,
+    lang: Language,
+) -> Option<String> {
+    // groups follow the patients linked to them
+    let mut patients = Patient::get_my_patients(user_id, con.clone()).ok()?;
+    patients.extend(Patient::get_group_patients(user_id, con.clone()).ok()?);
+
+    let digest = patients
+        .iter()
+        .map(|patient| {
+            let patient_tz = patient.get_timezone(con.clone());
+            let today = Utc::now().with_timezone(&patient_tz).date_naive();
+
+            let medications = Medication::get_all_by_patient_id(&patient.id, con.clone());
+
+            let list = medications
+                .iter()
+                .map(|m| {
+                    let intakes = m.get_intake_history(con.clone()).unwrap_or_default();
+                    print_medication(m, &intakes, &patient_tz, tz, today, lang)
+                })
+                .collect::<String>();
+
+            match list.is_empty() {
+                true => "".to_string(),
+                false => format!("{}\n{}\n", patient.name, list),
+            }
+        })
+        .collect::<String>();
+
+    match digest.is_empty() {
+        true => None,
+        false => Some(lang.fill(Text::DigestGreeting, &[("digest", &digest)])),
+    }
+}
+
+/// Sends the daily digests as they become due, checking every minute.
+pub async fn run_digests(bot: Bot, con: Arc<Mutex<Connection>>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(60));
+
+    loop {
+        interval.tick().await;
+
+        for (user_id, time) in get_digest_times(con.clone()) {
+            let tz: Tz = get_user_timezone(con.clone(), &user_id)
+                .parse()
+                .unwrap_or(Tz::UTC);
+            let Some(due) = get_due_digest(Utc::now(), time, &tz) else {
+                continue;
+            };
+            let due = due.to_string();
+
+            let sent_key = format!("medi:{}:digest_sent", user_id);
+            let sent = con
+                .lock()
+                .unwrap()
+                .get::<&str, Option<String>>(&sent_key)
+                .unwrap_or_default();
+
+            if sent.as_ref() == Some(&due) {
+                continue;
+            }
+
+            let _ = con.lock().unwrap().set::<&str, &str, ()>(&sent_key, &due);
+
+            let lang = get_user_language(con.clone(), &user_id);
+
+            if let Some(digest) = print_digest(con.clone(), &user_id, &tz, lang) {
+                let mut request = bot.send_message(user_id.clone(), digest);
+
+                // so anyone in a group can register the doses right from it
+                let group_patients =
+                    Patient::get_group_patients(&user_id, con.clone()).unwrap_or_default();
+                if !group_patients.is_empty() {
+                    request = request.reply_markup(InlineKeyboardMarkup::new(
+                        generate_group_intake_keyboard(&group_patients, con.clone(), lang),
+                    ));
+                }
+
+                if let Err(e) = request.await {
+                    log::warn!(
+                        "Failed to send daily digest: telegram user id {}. Error {}",
+                        &user_id,
+                        e
+                    )
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_redis_connection() -> redis::Connection {
+        // creating a real connection actually
+        let client = redis::Client::open("redis://127.0.0.1/").expect("Could not connect to Redis");
+        let mut redis_connection = client
+            .get_connection()
+            .expect("Could not get a Redis connection");
+
+        redis::cmd("SELECT")
+            .arg(1) // selecting db 1 for tests to preserve data on the other one (default, 0)
+            .exec(&mut redis_connection)
+            .unwrap();
+        redis::cmd("FLUSHDB").exec(&mut redis_connection).unwrap();
+
+        redis_connection
+    }
+
+    #[test]
+    fn test_parse_digest_time() {
+        assert_eq!(
+            parse_digest_time("08:30"),
+            NaiveTime::from_hms_opt(8, 30, 0)
+        );
+        assert_eq!(parse_digest_time("7:05"), NaiveTime::from_hms_opt(7, 5, 0));
+        assert_eq!(parse_digest_time(" 9 "), NaiveTime::from_hms_opt(9, 0, 0));
+        assert_eq!(parse_digest_time("25:00"), None);
+        assert_eq!(parse_digest_time("off"), None);
+    }
+
+    #[test]
+    fn test_local_midnight() {
+        let tz: Tz = "Europe/Madrid".parse().unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 11, 20).unwrap();
+
+        assert_eq!(
+            get_local_midnight(&tz, today).to_rfc3339(),
+            "2024-11-19T23:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_due_digest_across_midnight() {
+        let tz: Tz = "Europe/Madrid".parse().unwrap();
+        let time = NaiveTime::from_hms_opt(23, 50, 0).unwrap();
+        let at = |local: &str| {
+            resolve_local(
+                chrono::NaiveDateTime::parse_from_str(local, "%Y-%m-%d %H:%M").unwrap(),
+                &tz,
+            )
+        };
+
+        assert_eq!(get_due_digest(at("2024-11-20 23:40"), time, &tz), None);
+        assert_eq!(
+            get_due_digest(at("2024-11-20 23:55"), time, &tz),
+            NaiveDate::from_ymd_opt(2024, 11, 20)
+        );
+        // sent late, it's still the one of the 20th
+        assert_eq!(
+            get_due_digest(at("2024-11-21 00:10"), time, &tz),
+            NaiveDate::from_ymd_opt(2024, 11, 20)
+        );
+        assert_eq!(get_due_digest(at("2024-11-21 01:00"), time, &tz), None);
+    }
+
+    #[test]
+    fn test_default_digest_time() {
+        let con = Arc::new(Mutex::new(create_redis_connection()));
+        let eight = parse_digest_time(DEFAULT_DIGEST_TIME);
+
+        let mut patient = Patient::new("Xavi".to_string(), "1".to_string());
+        patient.share(2, con.clone()).unwrap();
+        patient.save(con.clone()).unwrap();
+        set_digest_time(con.clone(), "2", None).unwrap();
+
+        migrate_default_digests(con.clone()).unwrap();
+        assert_eq!(get_digest_time(con.clone(), "1"), eight);
+        assert_eq!(get_digest_time(con.clone(), "2"), None);
+
+        // turning it off or changing it sticks
+        set_digest_time(con.clone(), "1", None).unwrap();
+        set_default_digest_time(con.clone(), "1").unwrap();
+        assert_eq!(get_digest_time(con.clone(), "1"), None);
+
+        set_default_digest_time(con.clone(), "3").unwrap();
+        assert_eq!(get_digest_time(con.clone(), "3"), eight);
+    }
+}
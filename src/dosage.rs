@@ -0,0 +1,412 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{Language, Text};
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum Unit {
+    Ml,
+    Mg,
+    Mcg,
+    G,
+    Tablet,
+    Capsule,
+    Drop,
+    Puff,
+    Iu,
+}
+
+impl Unit {
+    pub fn parse(unit: &str) -> Option<Self> {
+        match unit.trim_end_matches('.') {
+            "ml" | "mls" | "milliliter" | "milliliters" | "millilitre" | "millilitres" => {
+                Some(Unit::Ml)
+            }
+            "mg" | "mgs" | "milligram" | "milligrams" => Some(Unit::Mg),
+            "mcg" | "µg" | "ug" | "microgram" | "micrograms" => Some(Unit::Mcg),
+            "g" | "gr" | "gram" | "grams" => Some(Unit::G),
+            "tab" | "tabs" | "tablet" | "tablets" | "pill" | "pills" => Some(Unit::Tablet),
+            "cap" | "caps" | "capsule" | "capsules" => Some(Unit::Capsule),
+            "drop" | "drops" | "gtt" | "gtts" => Some(Unit::Drop),
+            "puff" | "puffs" | "inhalation" | "inhalations" => Some(Unit::Puff),
+            "iu" | "i.u" | "ui" | "unit" | "units" => Some(Unit::Iu),
+            _ => None,
+        }
+    }
+
+    // mass units convert between each other, the rest only convert to themselves
+    fn mg_factor(&self) -> Option<f64> {
+        match self {
+            Unit::Mcg => Some(0.001),
+            Unit::Mg => Some(1.0),
+            Unit::G => Some(1000.0),
+            _ => None,
+        }
+    }
+
+    pub fn conversion_factor(&self, to: Unit) -> Option<f64> {
+        if *self == to {
+            return Some(1.0);
+        }
+
+        Some(self.mg_factor()? / to.mg_factor()?)
+    }
+
+    pub fn name(&self, plural: bool) -> &'static str {
+        match (self, plural) {
+            (Unit::Ml, _) => "ml",
+            (Unit::Mg, _) => "mg",
+            (Unit::Mcg, _) => "mcg",
+            (Unit::G, _) => "g",
+            (Unit::Iu, _) => "IU",
+            (Unit::Tablet, false) => "tablet",
+            (Unit::Tablet, true) => "tablets",
+            (Unit::Capsule, false) => "capsule",
+            (Unit::Capsule, true) => "capsules",
+            (Unit::Drop, false) => "drop",
+            (Unit::Drop, true) => "drops",
+            (Unit::Puff, false) => "puff",
+            (Unit::Puff, true) => "puffs",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DosageError {
+    Empty,
+    MissingAmount,
+    InvalidAmount,
+    MissingUnit,
+    UnknownUnit(String),
+}
+
+impl DosageError {
+    pub fn print(&self, lang: Language) -> String {
+        match self {
+            DosageError::Empty => lang.get(Text::DosageEmpty).to_string(),
+            DosageError::MissingAmount => lang.get(Text::DosageMissingAmount).to_string(),
+            DosageError::InvalidAmount => lang.get(Text::DosageInvalidAmount).to_string(),
+            DosageError::MissingUnit => lang.get(Text::DosageMissingUnit).to_string(),
+            DosageError::UnknownUnit(unit) => lang.fill(Text::DosageUnknownUnit, &[("unit", unit)]),
+        }
+    }
+}
+
+impl Display for DosageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "{}", self.print(Language::English))
+    }
+}
+
+/// A dose of a medicine. Plans created before dosages were parsed keep their free text.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(from = "DosageRepr", into = "DosageRepr")]
+pub enum Dosage {
+    Quantity { amount: f64, unit: Unit },
+    Text(String),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum DosageRepr {
+    Quantity { amount: f64, unit: Unit },
+    Text(String),
+}
+
+impl From<DosageRepr> for Dosage {
+    fn from(repr: DosageRepr) -> Self {
+        match repr {
+            DosageRepr::Quantity { amount, unit } => Dosage::Quantity { amount, unit },
+            // old free-text values are upgraded when they can be understood
+            DosageRepr::Text(text) => Dosage::parse(&text).unwrap_or(Dosage::Text(text)),
+        }
+    }
+}
+
+impl From<Dosage> for DosageRepr {
+    fn from(dosage: Dosage) -> Self {
+        match dosage {
+            Dosage::Quantity { amount, unit } => DosageRepr::Quantity { amount, unit },
+            Dosage::Text(text) => DosageRepr::Text(text),
+        }
+    }
+}
+
+impl Dosage {
+    pub fn new(amount: f64, unit: Unit) -> Dosage {
+        Dosage::Quantity { amount, unit }
+    }
+
+    /// The amount of this dosage expressed in `unit`, if they're compatible.
+    pub fn convert_to(&self, unit: Unit) -> Option<f64> {
+        match self {
+            Dosage::Quantity { amount, unit: from } => Some(amount * from.conversion_factor(unit)?),
+            Dosage::Text(_) => None,
+        }
+    }
+
+    // 5ml
+    // 2 tabs
+    // 1/2 tablet
+    // 2,5 ml
+    pub fn parse(dosage: &str) -> Result<Self, DosageError> {
+        let lower = dosage.trim().to_lowercase();
+
+        if lower.is_empty() {
+            return Err(DosageError::Empty);
+        }
+
+        let split_at = lower
+            .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ',' || c == '/'))
+            .unwrap_or(lower.len());
+        let (number, unit) = lower.split_at(split_at);
+
+        if number.is_empty() {
+            return Err(DosageError::MissingAmount);
+        }
+
+        let amount = parse_amount(number).ok_or(DosageError::MissingAmount)?;
+
+        if amount <= 0.0 {
+            return Err(DosageError::InvalidAmount);
+        }
+
+        let unit = unit.trim();
+
+        if unit.is_empty() {
+            return Err(DosageError::MissingUnit);
+        }
+
+        match Unit::parse(unit) {
+            Some(unit) => Ok(Dosage::new(amount, unit)),
+            None => Err(DosageError::UnknownUnit(unit.to_string())),
+        }
+    }
+}
+
+fn parse_amount(number: &str) -> Option<f64> {
+    match number.split_once('/') {
+        Some((numerator, denominator)) => {
+            let numerator = numerator.parse::<f64>().ok()?;
+            let denominator = denominator.parse::<f64>().ok()?;
+
+            if denominator == 0.0 {
+                None
+            } else {
+                Some(numerator / denominator)
+            }
+        }
+        None => number.replace(',', ".").parse::<f64>().ok(),
+    }
+}
+
+impl Display for Dosage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            Dosage::Quantity { amount, unit } => {
+                // f64's Display already drops the trailing ".0"
+                let rounded = (amount * 100.0).round() / 100.0;
+                write!(f, "{} {}", rounded, unit.name(rounded != 1.0))
+            }
+            Dosage::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+/// A dose defined by the patient's weight, given from a product with a known concentration,
+/// like children's paracetamol at 15 mg/kg of a 120 mg/5 ml syrup.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct WeightDose {
+    pub mg_per_kg: f64,
+    concentration_mg: f64,
+    concentration_ml: f64,
+    pub max_mg: Option<f64>,
+}
+
+impl WeightDose {
+    pub fn new(mg_per_kg: f64, concentration_mg: f64, concentration_ml: f64) -> WeightDose {
+        WeightDose {
+            mg_per_kg,
+            concentration_mg,
+            concentration_ml,
+            max_mg: None,
+        }
+    }
+
+    // 15 mg/kg
+    pub fn parse_mg_per_kg(dose: &str) -> Result<f64, DosageError> {
+        match dose.trim().to_lowercase().split_once('/') {
+            Some((amount, per)) if per.trim() == "kg" => parse_mg(amount),
+            _ => Err(DosageError::MissingUnit),
+        }
+    }
+
+    // 120 mg/5 ml
+    // 40mg/ml
+    pub fn parse_concentration(concentration: &str) -> Result<(f64, f64), DosageError> {
+        let lower = concentration.trim().to_lowercase();
+        let (mg, ml) = lower.split_once('/').ok_or(DosageError::MissingUnit)?;
+
+        let ml = match ml.trim() {
+            "ml" => 1.0,
+            ml => Dosage::parse(ml)?
+                .convert_to(Unit::Ml)
+                .ok_or(DosageError::UnknownUnit(ml.to_string()))?,
+        };
+
+        Ok((parse_mg(mg)?, ml))
+    }
+
+    // 500 mg
+    pub fn parse_max(max: &str) -> Result<f64, DosageError> {
+        parse_mg(max)
+    }
+
+    // 15 mg/kg of 120 mg/5 ml, max 500 mg
+    pub fn parse(dose: &str) -> Result<Self, DosageError> {
+        let (mg_per_kg, rest) = dose.split_once(" of ").ok_or(DosageError::MissingUnit)?;
+        let (concentration, max) = match rest.split_once(", max ") {
+            Some((concentration, max)) => (concentration, Some(WeightDose::parse_max(max)?)),
+            None => (rest, None),
+        };
+        let (concentration_mg, concentration_ml) = WeightDose::parse_concentration(concentration)?;
+
+        Ok(WeightDose {
+            mg_per_kg: WeightDose::parse_mg_per_kg(mg_per_kg)?,
+            concentration_mg,
+            concentration_ml,
+            max_mg: max,
+        })
+    }
+
+    pub fn get_mg(&self, weight_kg: f64) -> f64 {
+        self.mg_per_kg * weight_kg
+    }
+
+    /// The volume to give to a patient of `weight_kg`.
+    pub fn get_dosage(&self, weight_kg: f64) -> Dosage {
+        let ml = self.get_mg(weight_kg) * self.concentration_ml / self.concentration_mg;
+        Dosage::new(ml, Unit::Ml)
+    }
+
+    pub fn exceeds_max(&self, weight_kg: f64) -> bool {
+        self.max_mg.is_some_and(|max| self.get_mg(weight_kg) > max)
+    }
+}
+
+fn parse_mg(amount: &str) -> Result<f64, DosageError> {
+    let amount = amount.trim();
+    Dosage::parse(amount)?
+        .convert_to(Unit::Mg)
+        .ok_or(DosageError::UnknownUnit(amount.to_string()))
+}
+
+impl Display for WeightDose {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "{} mg/kg of {} mg/{} ml",
+            self.mg_per_kg, self.concentration_mg, self.concentration_ml
+        )?;
+
+        match self.max_mg {
+            Some(max) => write!(f, ", max {} mg", max),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dosage() {
+        let cases = [
+            ("5ml", Dosage::new(5.0, Unit::Ml)),
+            ("5 ml", Dosage::new(5.0, Unit::Ml)),
+            ("2 tabs", Dosage::new(2.0, Unit::Tablet)),
+            ("400mg", Dosage::new(400.0, Unit::Mg)),
+            ("2,5 ML", Dosage::new(2.5, Unit::Ml)),
+            ("1/2 tablet", Dosage::new(0.5, Unit::Tablet)),
+            ("3 drops", Dosage::new(3.0, Unit::Drop)),
+            ("2 puffs", Dosage::new(2.0, Unit::Puff)),
+            ("1000 IU", Dosage::new(1000.0, Unit::Iu)),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(Dosage::parse(input), Ok(expected), "parsing {}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_dosage_errors() {
+        assert_eq!(Dosage::parse(""), Err(DosageError::Empty));
+        assert_eq!(Dosage::parse("some ml"), Err(DosageError::MissingAmount));
+        assert_eq!(Dosage::parse("0 ml"), Err(DosageError::InvalidAmount));
+        assert_eq!(Dosage::parse("5"), Err(DosageError::MissingUnit));
+        assert_eq!(
+            Dosage::parse("5 spoons"),
+            Err(DosageError::UnknownUnit("spoons".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_display_dosage() {
+        assert_eq!(Dosage::new(5.0, Unit::Ml).to_string(), "5 ml");
+        assert_eq!(Dosage::new(1.0, Unit::Tablet).to_string(), "1 tablet");
+        assert_eq!(Dosage::new(2.5, Unit::Tablet).to_string(), "2.5 tablets");
+    }
+
+    #[test]
+    fn test_convert_dosage() {
+        assert_eq!(Dosage::new(0.5, Unit::G).convert_to(Unit::Mg), Some(500.0));
+        assert_eq!(Dosage::new(5.0, Unit::Ml).convert_to(Unit::Ml), Some(5.0));
+        assert_eq!(Dosage::new(5.0, Unit::Ml).convert_to(Unit::Mg), None);
+        assert_eq!(
+            Dosage::Text("a spoon".to_string()).convert_to(Unit::Ml),
+            None
+        );
+    }
+
+    #[test]
+    fn test_weight_dose() {
+        assert_eq!(WeightDose::parse_mg_per_kg("15 mg/kg"), Ok(15.0));
+        assert_eq!(
+            WeightDose::parse_mg_per_kg("15 mg"),
+            Err(DosageError::MissingUnit)
+        );
+        assert_eq!(
+            WeightDose::parse_concentration("120mg/5ml"),
+            Ok((120.0, 5.0))
+        );
+        assert_eq!(WeightDose::parse_concentration("40 mg/ml"), Ok((40.0, 1.0)));
+
+        let mut dose = WeightDose::new(15.0, 120.0, 5.0);
+        dose.max_mg = Some(500.0);
+
+        assert_eq!(dose.to_string(), "15 mg/kg of 120 mg/5 ml, max 500 mg");
+        assert_eq!(WeightDose::parse(&dose.to_string()), Ok(dose.clone()));
+
+        assert_eq!(dose.get_dosage(12.0), Dosage::new(7.5, Unit::Ml));
+        assert!(!dose.exceeds_max(12.0));
+        assert!(dose.exceeds_max(40.0));
+    }
+
+    #[test]
+    fn test_deserialize_old_dosage() {
+        assert_eq!(
+            serde_json::from_str::<Dosage>("\"5ml\"").unwrap(),
+            Dosage::new(5.0, Unit::Ml)
+        );
+        assert_eq!(
+            serde_json::from_str::<Dosage>("\"half a spoon\"").unwrap(),
+            Dosage::Text("half a spoon".to_string())
+        );
+
+        let dosage = Dosage::new(400.0, Unit::Mg);
+        let json = serde_json::to_string(&dosage).unwrap();
+        assert_eq!(serde_json::from_str::<Dosage>(&json).unwrap(), dosage);
+    }
+}
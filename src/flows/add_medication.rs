@@ -1,30 +1,32 @@
 use std::error::Error;
 
+use crate::catalog;
 use crate::commands::cancel_with_edit;
+use crate::dosage::{Dosage, WeightDose};
 use crate::frequency::Frequency;
+use crate::i18n::{is_nothing, Language, Text};
 use crate::medication::Medication;
 use crate::patient::Patient;
 use crate::{ConfigParameters, HandlerResult, MyDialogue, State};
+use redis::Connection;
+use std::sync::{Arc, Mutex};
 use teloxide::prelude::*;
-use teloxide::types::{InlineKeyboardMarkup, Message, ParseMode};
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, Message, ParseMode};
 use teloxide::Bot;
 
-const ERROR_NO_TEXT: &str = "Sorry, couldn't understand that - please send a text message.";
-
-const START_FLOW_TEXT: &str =
-    "💊 *Adding a new medication plan\\.* 💊 \n\n Please start by selecting the patient\\:";
-
 pub async fn start_add_medication(
     cfg: ConfigParameters,
     bot: Bot,
     dialogue: MyDialogue,
+    lang: Language,
     msg: Message,
 ) -> HandlerResult {
     let con = cfg.redis_connection;
 
-    let keyboard = Patient::generate_patient_keyboard(con.clone(), msg.chat.id.to_string(), true);
+    let keyboard =
+        Patient::generate_patient_keyboard(con.clone(), msg.chat.id.to_string(), true, lang);
 
-    bot.send_message(msg.chat.id, START_FLOW_TEXT)
+    bot.send_message(msg.chat.id, lang.get(Text::StartAddMedication))
         .reply_markup(InlineKeyboardMarkup::new(keyboard))
         .parse_mode(ParseMode::MarkdownV2)
         .await?;
@@ -38,17 +40,18 @@ pub async fn receive_name_callback_handler(
     cfg: ConfigParameters,
     bot: Bot,
     dialogue: MyDialogue,
+    lang: Language,
     q: CallbackQuery,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
     if let Some(ref patient_id) = q.data {
         if patient_id == "cancel" {
-            cancel_with_edit(bot, dialogue, q.regular_message().unwrap().to_owned()).await?;
+            cancel_with_edit(bot, dialogue, lang, q.regular_message().unwrap().to_owned()).await?;
         } else if patient_id == "add_new" {
             let message = q.regular_message().unwrap();
             bot.edit_message_text(
                 message.chat.id,
                 message.id,
-                "Ok, tell me what's the new patient's name.",
+                lang.get(Text::AskNewPatientName),
             )
             .await?;
 
@@ -61,13 +64,21 @@ pub async fn receive_name_callback_handler(
             let patient = Patient::get_by_id(patient_id, cfg.redis_connection).unwrap();
 
             if let Some(message) = q.regular_message() {
+                if !patient.can_edit(&q.from.id.to_string()) {
+                    bot.edit_message_text(
+                        message.chat.id,
+                        message.id,
+                        lang.fill(Text::ViewOnly, &[("patient", &patient.name)]),
+                    )
+                    .await?;
+                    dialogue.exit().await?;
+                    return Ok(());
+                }
+
                 bot.edit_message_text(
                     message.chat.id,
                     message.id,
-                    format!(
-                        "Great. Now what's the name of the medicine {} is going to be taking?",
-                        patient.name
-                    ),
+                    lang.fill(Text::AskMedicine, &[("patient", &patient.name)]),
                 )
                 .await?;
                 dialogue
@@ -86,6 +97,7 @@ pub async fn receive_name(
     cfg: ConfigParameters,
     bot: Bot,
     dialogue: MyDialogue,
+    lang: Language,
     msg: Message,
 ) -> HandlerResult {
     match msg.text() {
@@ -95,10 +107,7 @@ pub async fn receive_name(
 
             bot.send_message(
                 msg.chat.id,
-                format!(
-                    "Great. Now what's the name of the medicine {} is going to be taking?",
-                    patient.name
-                ),
+                lang.fill(Text::AskMedicine, &[("patient", &patient.name)]),
             )
             .await?;
 
@@ -109,7 +118,8 @@ pub async fn receive_name(
                 .await?;
         }
         None => {
-            bot.send_message(msg.chat.id, ERROR_NO_TEXT).await?;
+            bot.send_message(msg.chat.id, lang.get(Text::SendTextPlease))
+                .await?;
         }
     }
 
@@ -117,24 +127,92 @@ pub async fn receive_name(
 }
 
 pub async fn receive_medicine(
+    cfg: ConfigParameters,
     bot: Bot,
     dialogue: MyDialogue,
     patient_id: String,
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    match msg.text() {
+        Some(text) => {
+            match catalog::get_ingredients(text, &msg.chat.id.to_string(), cfg.redis_connection) {
+                Some(ingredients) => {
+                    bot.send_message(
+                        msg.chat.id,
+                        lang.fill(
+                            Text::MedicineContains,
+                            &[
+                                ("medicine", &text),
+                                ("ingredients", &ingredients.join(", ")),
+                            ],
+                        ),
+                    )
+                    .await?;
+                    dialogue
+                        .update(State::ReceiveDosage {
+                            patient_id,
+                            medicine: text.into(),
+                        })
+                        .await?;
+                }
+                None => {
+                    bot.send_message(
+                        msg.chat.id,
+                        lang.fill(Text::AskIngredients, &[("medicine", &text)]),
+                    )
+                    .await?;
+                    dialogue
+                        .update(State::ReceiveIngredients {
+                            patient_id,
+                            medicine: text.into(),
+                        })
+                        .await?;
+                }
+            }
+        }
+        None => {
+            bot.send_message(msg.chat.id, lang.get(Text::SendTextPlease))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn receive_ingredients(
+    cfg: ConfigParameters,
+    bot: Bot,
+    dialogue: MyDialogue,
+    (patient_id, medicine): (String, String),
+    lang: Language,
     msg: Message,
 ) -> HandlerResult {
     match msg.text() {
         Some(text) => {
-            bot.send_message(msg.chat.id, "And what's the dosage?")
+            let ingredients = catalog::parse_ingredients(text);
+
+            if !is_nothing(text) && !ingredients.is_empty() {
+                catalog::save_ingredients(
+                    &medicine,
+                    &msg.chat.id.to_string(),
+                    &ingredients,
+                    cfg.redis_connection,
+                )?;
+            }
+
+            bot.send_message(msg.chat.id, lang.get(Text::AskDosage))
                 .await?;
             dialogue
                 .update(State::ReceiveDosage {
                     patient_id,
-                    medicine: text.into(),
+                    medicine,
                 })
                 .await?;
         }
         None => {
-            bot.send_message(msg.chat.id, ERROR_NO_TEXT).await?;
+            bot.send_message(msg.chat.id, lang.get(Text::SendTextPlease))
+                .await?;
         }
     }
 
@@ -142,26 +220,174 @@ pub async fn receive_medicine(
 }
 
 pub async fn receive_dosage(
+    cfg: ConfigParameters,
     bot: Bot,
     dialogue: MyDialogue,
     (patient_id, medicine): (String, String),
+    lang: Language,
     msg: Message,
 ) -> HandlerResult {
     match msg.text() {
-        Some(dosage) => {
-            bot.send_message(msg.chat.id, "Finally, what's the medication frequency? \\(e\\.g\\., `every 6 hours`, or `3 times a day`\\)")
+        Some(text) if text.to_lowercase().contains("/kg") => {
+            match WeightDose::parse_mg_per_kg(text) {
+                Ok(mg_per_kg) => {
+                    let patient = Patient::get_by_id(&patient_id, cfg.redis_connection).unwrap();
+
+                    if patient.weight_kg.is_none() {
+                        bot.send_message(
+                            msg.chat.id,
+                            lang.fill(Text::NeedWeight, &[("patient", &patient.name)]),
+                        )
+                        .await?;
+                    } else {
+                        bot.send_message(msg.chat.id, lang.get(Text::AskConcentration))
+                            .await?;
+                        dialogue
+                            .update(State::ReceiveConcentration {
+                                patient_id,
+                                medicine,
+                                mg_per_kg,
+                            })
+                            .await?;
+                    }
+                }
+                Err(e) => {
+                    bot.send_message(
+                        msg.chat.id,
+                        lang.fill(
+                            Text::TryAgain,
+                            &[("error", &e.print(lang)), ("examples", &"15 mg/kg")],
+                        ),
+                    )
+                    .await?;
+                }
+            }
+        }
+        Some(text) => match Dosage::parse(text) {
+            Ok(dosage) => {
+                bot.send_message(msg.chat.id, lang.get(Text::AskFrequency))
+                    .parse_mode(ParseMode::MarkdownV2)
+                    .await?;
+                dialogue
+                    .update(State::ReceiveFrequency {
+                        patient_id,
+                        medicine,
+                        dosage: dosage.to_string(),
+                    })
+                    .await?;
+            }
+            Err(e) => {
+                bot.send_message(
+                    msg.chat.id,
+                    lang.fill(
+                        Text::TryAgain,
+                        &[
+                            ("error", &e.print(lang)),
+                            ("examples", &lang.get(Text::DosageExamples)),
+                        ],
+                    ),
+                )
+                .await?;
+            }
+        },
+        None => {
+            bot.send_message(msg.chat.id, lang.get(Text::SendTextPlease))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn receive_concentration(
+    bot: Bot,
+    dialogue: MyDialogue,
+    (patient_id, medicine, mg_per_kg): (String, String, f64),
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    match msg.text().map(WeightDose::parse_concentration) {
+        Some(Ok((concentration_mg, concentration_ml))) => {
+            let weight_dose = WeightDose::new(mg_per_kg, concentration_mg, concentration_ml);
+
+            bot.send_message(msg.chat.id, lang.get(Text::AskMaxDose))
+                .await?;
+            dialogue
+                .update(State::ReceiveMaxDose {
+                    patient_id,
+                    medicine,
+                    weight_dose: weight_dose.to_string(),
+                })
+                .await?;
+        }
+        Some(Err(e)) => {
+            bot.send_message(
+                msg.chat.id,
+                lang.fill(
+                    Text::TryAgain,
+                    &[
+                        ("error", &e.print(lang)),
+                        ("examples", &"120 mg/5 ml, 40 mg/ml,..."),
+                    ],
+                ),
+            )
+            .await?;
+        }
+        None => {
+            bot.send_message(msg.chat.id, lang.get(Text::SendTextPlease))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn receive_max_dose(
+    bot: Bot,
+    dialogue: MyDialogue,
+    (patient_id, medicine, weight_dose): (String, String, String),
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    let mut weight_dose = WeightDose::parse(&weight_dose).expect("Malformed weight dose in state");
+
+    let max = match msg.text() {
+        Some(text) if is_nothing(text) => Ok(None),
+        Some(text) => WeightDose::parse_max(text).map(Some),
+        None => {
+            bot.send_message(msg.chat.id, lang.get(Text::SendTextPlease))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    match max {
+        Ok(max) => {
+            weight_dose.max_mg = max;
+
+            bot.send_message(msg.chat.id, lang.get(Text::AskFrequency))
                 .parse_mode(ParseMode::MarkdownV2)
                 .await?;
             dialogue
                 .update(State::ReceiveFrequency {
                     patient_id,
                     medicine,
-                    dosage: dosage.into(),
+                    dosage: weight_dose.to_string(),
                 })
                 .await?;
         }
-        None => {
-            bot.send_message(msg.chat.id, ERROR_NO_TEXT).await?;
+        Err(e) => {
+            bot.send_message(
+                msg.chat.id,
+                lang.fill(
+                    Text::TryAgain,
+                    &[
+                        ("error", &e.print(lang)),
+                        ("examples", &lang.get(Text::MaxDoseExamples)),
+                    ],
+                ),
+            )
+            .await?;
         }
     }
 
@@ -173,46 +399,186 @@ pub async fn receive_frequency(
     bot: Bot,
     dialogue: MyDialogue,
     (patient_id, medicine, dosage): (String, String, String),
+    lang: Language,
     msg: Message,
 ) -> HandlerResult {
     match msg.text() {
-        Some(frequency_str) => {
-            if let Some(frequency) = Frequency::parse(frequency_str) {
-                let mut medication = Medication::new(
-                    patient_id,
-                    medicine,
-                    dosage,
-                    frequency.clone(),
+        Some(frequency_str) => match Frequency::parse(frequency_str) {
+            Ok(frequency) => {
+                let con = cfg.redis_connection;
+                let medication = build_medication(
+                    con.clone(),
+                    patient_id.clone(),
+                    medicine.clone(),
+                    dosage.clone(),
+                    frequency,
                     dialogue.chat_id().to_string(),
                 );
 
-                medication.save(cfg.redis_connection).unwrap();
-
-                let report = format!(
-                    "
-Got it\\. Adding a new plan of `{}` to `{}`'s plan: `{}`, `{}`\\.
+                let others = Medication::get_all_by_patient_id(&patient_id, con.clone());
+                let interactions = medication.print_interactions(&others, lang);
 
-When giving the first dose, run /take\\.
-",
-                    medication.medicine,
-                    medication.patient_name.clone().unwrap(),
-                    medication.dosage,
-                    frequency.to_string(),
-                );
+                if interactions.is_empty() {
+                    save_medication_plan(con, bot, dialogue, lang, medication).await?;
+                } else {
+                    let keyboard = vec![vec![
+                        InlineKeyboardButton::callback(
+                            lang.get(Text::AddAnyway).to_string(),
+                            "confirm".to_string(),
+                        ),
+                        InlineKeyboardButton::callback(
+                            lang.get(Text::Cancel).to_string(),
+                            "cancel".to_string(),
+                        ),
+                    ]];
 
-                bot.send_message(msg.chat.id, report)
-                    .parse_mode(ParseMode::MarkdownV2)
+                    bot.send_message(
+                        msg.chat.id,
+                        lang.fill(
+                            Text::InteractionWarning,
+                            &[("medicine", &medicine), ("interactions", &interactions)],
+                        ),
+                    )
+                    .reply_markup(InlineKeyboardMarkup::new(keyboard))
                     .await?;
 
-                dialogue.exit().await?;
-            } else {
-                bot.send_message(msg.chat.id, "Didn't quite get that. Can you try again? (ie, every 6 hours, 3 times a day,...)").await?;
+                    dialogue
+                        .update(State::ConfirmInteraction {
+                            patient_id,
+                            medicine,
+                            dosage,
+                            frequency: frequency_str.to_string(),
+                        })
+                        .await?;
+                }
             }
-        }
+            Err(e) => {
+                bot.send_message(
+                    msg.chat.id,
+                    lang.fill(
+                        Text::TryAgain,
+                        &[
+                            ("error", &e.print(lang)),
+                            ("examples", &lang.get(Text::FrequencyExamples)),
+                        ],
+                    ),
+                )
+                .await?;
+            }
+        },
         None => {
-            bot.send_message(msg.chat.id, ERROR_NO_TEXT).await?;
+            bot.send_message(msg.chat.id, lang.get(Text::SendTextPlease))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn confirm_interaction_callback_handler(
+    cfg: ConfigParameters,
+    bot: Bot,
+    dialogue: MyDialogue,
+    (patient_id, medicine, dosage, frequency): (String, String, String, String),
+    lang: Language,
+    q: CallbackQuery,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let message = q.regular_message().unwrap();
+    bot.answer_callback_query(&q.id).await?;
+
+    match q.data.as_deref() {
+        Some("confirm") => {
+            bot.edit_message_reply_markup(message.chat.id, message.id)
+                .await?;
+
+            let frequency = Frequency::parse(&frequency).expect("Malformed frequency in state");
+            let con = cfg.redis_connection;
+            let medication = build_medication(
+                con.clone(),
+                patient_id,
+                medicine,
+                dosage,
+                frequency,
+                dialogue.chat_id().to_string(),
+            );
+
+            save_medication_plan(con, bot, dialogue, lang, medication).await?;
+        }
+        _ => {
+            cancel_with_edit(bot, dialogue, lang, message.to_owned()).await?;
         }
     }
 
     Ok(())
 }
+
+pub fn build_medication(
+    con: Arc<Mutex<Connection>>,
+    patient_id: String,
+    medicine: String,
+    dosage: String,
+    frequency: Frequency,
+    user_id: String,
+) -> Medication {
+    let weight_dose = WeightDose::parse(&dosage).ok();
+    let weight_kg = Patient::get_by_id(&patient_id, con.clone())
+        .ok()
+        .and_then(|p| p.weight_kg);
+    let ingredients = catalog::get_ingredients(&medicine, &user_id, con);
+
+    let mut medication = Medication::new(
+        patient_id,
+        medicine,
+        Dosage::parse(&dosage).unwrap_or(Dosage::Text(dosage)),
+        frequency,
+        user_id,
+    );
+
+    if let (Some(weight_dose), Some(weight_kg)) = (weight_dose, weight_kg) {
+        medication.set_weight_dose(weight_dose, weight_kg);
+    }
+
+    if let Some(ingredients) = ingredients {
+        medication.set_ingredients(ingredients);
+    }
+
+    medication
+}
+
+async fn save_medication_plan(
+    con: Arc<Mutex<Connection>>,
+    bot: Bot,
+    dialogue: MyDialogue,
+    lang: Language,
+    mut medication: Medication,
+) -> HandlerResult {
+    medication.save(con.clone()).unwrap();
+
+    let report = lang.fill(
+        Text::PlanAdded,
+        &[
+            ("medicine", &medication.medicine),
+            ("patient", &medication.patient_name.clone().unwrap()),
+            ("dosage", &medication.dosage),
+            ("frequency", &medication.get_frequency().print(lang)),
+        ],
+    );
+
+    bot.send_message(dialogue.chat_id(), report)
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
+
+    let weight_kg = Patient::get_by_id(&medication.patient_id, con)
+        .ok()
+        .and_then(|p| p.weight_kg);
+
+    if let Some(warning) =
+        weight_kg.and_then(|weight_kg| medication.print_max_dose_warning(weight_kg, lang))
+    {
+        bot.send_message(dialogue.chat_id(), warning).await?;
+    }
+
+    dialogue.exit().await?;
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use std::error::Error;
+
+use crate::calendar::{render_calendar, DEFAULT_CALENDAR_DAYS, MAX_CALENDAR_DAYS};
+use crate::commands::cancel_with_edit;
+use crate::i18n::{Language, Text};
+use crate::medication::Medication;
+use crate::{patient::Patient, ConfigParameters, HandlerResult, MyDialogue, State};
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardMarkup, InputFile, Message};
+use teloxide::Bot;
+
+pub async fn calendar_command(
+    cfg: ConfigParameters,
+    bot: Bot,
+    dialogue: MyDialogue,
+    days: String,
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    let days = days
+        .trim()
+        .parse::<i64>()
+        .unwrap_or(DEFAULT_CALENDAR_DAYS)
+        .clamp(1, MAX_CALENDAR_DAYS);
+
+    let keyboard = Patient::generate_patient_keyboard(
+        cfg.redis_connection.clone(),
+        msg.chat.id.to_string(),
+        false,
+        lang,
+    );
+
+    bot.send_message(
+        msg.chat.id,
+        lang.fill(Text::AskCalendarPatient, &[("days", &days)]),
+    )
+    .reply_markup(InlineKeyboardMarkup::new(keyboard))
+    .await?;
+
+    dialogue
+        .update(State::SelectCalendarPatient { days })
+        .await?;
+
+    Ok(())
+}
+
+pub async fn calendar_patient_callback_handler(
+    cfg: ConfigParameters,
+    bot: Bot,
+    dialogue: MyDialogue,
+    days: i64,
+    lang: Language,
+    q: CallbackQuery,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let message = q.regular_message().unwrap();
+    bot.answer_callback_query(&q.id).await?;
+
+    if let Some(ref patient_id) = q.data {
+        if patient_id == "cancel" {
+            cancel_with_edit(bot, dialogue, lang, message.to_owned()).await?;
+            return Ok(());
+        }
+
+        let con = cfg.redis_connection;
+        let patient = Patient::get_by_id(patient_id, con.clone()).unwrap();
+        let medications = Medication::get_all_by_patient_id(patient_id, con.clone());
+
+        if medications.is_empty() {
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                lang.fill(Text::NoPlans, &[("patient", &patient.name)]),
+            )
+            .await?;
+        } else {
+            let ics = render_calendar(
+                &patient.name,
+                &medications,
+                days,
+                &patient.get_timezone(con.clone()),
+            );
+
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                lang.fill(
+                    Text::CalendarReady,
+                    &[("patient", &patient.name), ("days", &days)],
+                ),
+            )
+            .await?;
+
+            bot.send_document(
+                message.chat.id,
+                InputFile::memory(ics.into_bytes()).file_name(format!("{}.ics", patient.name)),
+            )
+            .await?;
+        }
+
+        dialogue.exit().await?;
+    }
+
+    Ok(())
+}
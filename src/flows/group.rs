@@ -0,0 +1,275 @@
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use redis::Connection;
+
+use crate::digest::set_default_digest_time;
+use crate::flows::take_medicine::register_intake;
+use crate::i18n::{Language, Text};
+use crate::medication::Medication;
+use crate::user::{detect_user_language, get_user_language, has_user_timezone, set_user_timezone};
+use crate::{patient::Patient, ConfigParameters, HandlerResult};
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, Message};
+use teloxide::Bot;
+
+/// One button per medication of the patients linked to a group. The callbacks don't depend on
+/// the dialogue, so they keep working for everyone in the group, and on old digests too.
+pub fn generate_group_intake_keyboard(
+    patients: &[Patient],
+    con: Arc<Mutex<Connection>>,
+    lang: Language,
+) -> Vec<Vec<InlineKeyboardButton>> {
+    let buttons: Vec<InlineKeyboardButton> = patients
+        .iter()
+        .flat_map(|patient| {
+            Medication::get_all_by_patient_id(&patient.id, con.clone())
+                .into_iter()
+                .map(|medication| {
+                    InlineKeyboardButton::callback(
+                        format!(
+                            "{} {}",
+                            medication.can_take_emoji(),
+                            lang.fill(
+                                Text::IntakeCandidate,
+                                &[
+                                    ("medicine", &medication.medicine),
+                                    ("patient", &patient.name),
+                                ],
+                            )
+                        ),
+                        format!("take:{}", medication.id),
+                    )
+                })
+                .collect::<Vec<InlineKeyboardButton>>()
+        })
+        .collect();
+
+    buttons.chunks(2).map(|row| row.to_vec()).collect()
+}
+
+pub async fn link_command(
+    cfg: ConfigParameters,
+    bot: Bot,
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    if msg.chat.is_private() {
+        bot.send_message(msg.chat.id, lang.get(Text::LinkInGroup))
+            .await?;
+        return Ok(());
+    }
+
+    let Some(user) = msg.from.as_ref() else {
+        return Ok(());
+    };
+
+    let user_id = user.id.to_string();
+    let chat_id = msg.chat.id.to_string();
+
+    let patients: Vec<Patient> = Patient::get_my_patients(&user_id, cfg.redis_connection)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|patient| patient.can_edit(&user_id))
+        .collect();
+
+    if patients.is_empty() {
+        bot.send_message(msg.chat.id, lang.get(Text::NoPatientsToLink))
+            .await?;
+        return Ok(());
+    }
+
+    // linked ones are ticked, pressing them again unlinks them
+    let buttons: Vec<InlineKeyboardButton> = patients
+        .iter()
+        .map(|patient| {
+            let name = match patient.is_linked_to(&chat_id) {
+                true => format!("✅ {}", patient.name),
+                false => patient.name.clone(),
+            };
+
+            InlineKeyboardButton::callback(name, format!("link:{}", patient.id))
+        })
+        .collect();
+
+    let keyboard: Vec<Vec<InlineKeyboardButton>> =
+        buttons.chunks(3).map(|row| row.to_vec()).collect();
+
+    bot.send_message(msg.chat.id, lang.get(Text::AskLinkPatients))
+        .reply_markup(InlineKeyboardMarkup::new(keyboard))
+        .await?;
+
+    Ok(())
+}
+
+pub fn is_group_link(q: CallbackQuery) -> bool {
+    q.data.is_some_and(|data| data.starts_with("link:"))
+}
+
+// groups get the digest at the default time, in the timezone and language of the first
+// patient and user linking them, until changed
+fn set_group_defaults(
+    con: Arc<Mutex<Connection>>,
+    chat_id: &str,
+    patient: &Patient,
+    lang: Language,
+) -> HandlerResult {
+    if !has_user_timezone(con.clone(), chat_id) {
+        set_user_timezone(con.clone(), chat_id, &patient.get_timezone(con.clone()))?;
+    }
+
+    set_default_digest_time(con.clone(), chat_id)?;
+
+    detect_user_language(con, chat_id, Some(lang.code()));
+
+    Ok(())
+}
+
+// works regardless of the dialogue state, anyone in the group can press it
+pub async fn link_callback_handler(
+    cfg: ConfigParameters,
+    bot: Bot,
+    lang: Language,
+    q: CallbackQuery,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let message = q.regular_message().unwrap();
+    let con = cfg.redis_connection;
+
+    let patient_id = q
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix("link:"))
+        .unwrap_or_default();
+
+    let Ok(mut patient) = Patient::get_by_id(patient_id, con.clone()) else {
+        bot.answer_callback_query(&q.id).await?;
+        return Ok(());
+    };
+
+    if !patient.can_edit(&q.from.id.to_string()) {
+        bot.answer_callback_query(&q.id)
+            .text(lang.fill(Text::ViewOnly, &[("patient", &patient.name)]))
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    }
+
+    bot.answer_callback_query(&q.id).await?;
+
+    let chat_id = message.chat.id.to_string();
+
+    let reply = if patient.is_linked_to(&chat_id) {
+        patient.unlink_group(&chat_id, con.clone())?;
+        Text::PatientUnlinked
+    } else {
+        patient.link_group(&chat_id, con.clone())?;
+        set_group_defaults(con.clone(), &chat_id, &patient, lang)?;
+        Text::PatientLinked
+    };
+
+    patient
+        .save(con.clone())
+        .expect("Error saving patient after linking a group");
+
+    bot.edit_message_text(
+        message.chat.id,
+        message.id,
+        lang.fill(reply, &[("patient", &patient.name)]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub async fn group_take_command(
+    cfg: ConfigParameters,
+    bot: Bot,
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    let con = cfg.redis_connection;
+    let patients =
+        Patient::get_group_patients(&msg.chat.id.to_string(), con.clone()).unwrap_or_default();
+
+    if patients.is_empty() {
+        bot.send_message(msg.chat.id, lang.get(Text::NoLinkedPatients))
+            .await?;
+        return Ok(());
+    }
+
+    bot.send_message(msg.chat.id, lang.get(Text::AskGroupIntake))
+        .reply_markup(InlineKeyboardMarkup::new(generate_group_intake_keyboard(
+            &patients, con, lang,
+        )))
+        .await?;
+
+    Ok(())
+}
+
+pub fn is_group_intake(q: CallbackQuery) -> bool {
+    q.data.is_some_and(|data| data.starts_with("take:"))
+}
+
+// works regardless of the dialogue state, checking the user that pressed it rather than the chat
+pub async fn group_intake_callback_handler(
+    cfg: ConfigParameters,
+    bot: Bot,
+    lang: Language,
+    q: CallbackQuery,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let message = q.regular_message().unwrap();
+    let con = cfg.redis_connection;
+
+    let medication_id = q
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix("take:"))
+        .unwrap_or_default();
+
+    let patient = Medication::get_by_id(medication_id, con.clone())
+        .and_then(|medication| Patient::get_by_id(&medication.patient_id, con.clone()));
+
+    let Ok(patient) = patient else {
+        bot.answer_callback_query(&q.id).await?;
+        return Ok(());
+    };
+
+    let user_id = q.from.id.to_string();
+    let chat_id = message.chat.id.to_string();
+
+    if !patient.is_linked_to(&chat_id) || !patient.can_edit(&user_id) {
+        bot.answer_callback_query(&q.id)
+            .text(lang.fill(Text::ViewOnly, &[("patient", &patient.name)]))
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    }
+
+    bot.answer_callback_query(&q.id).await?;
+
+    // the whole group reads the confirmation
+    let group_lang = get_user_language(con.clone(), &chat_id);
+
+    let intake = register_intake(
+        &bot,
+        con,
+        medication_id,
+        Utc::now().timestamp(),
+        &user_id,
+        &chat_id,
+        group_lang,
+    )
+    .await?;
+
+    bot.send_message(
+        message.chat.id,
+        group_lang.fill(
+            Text::IntakeBy,
+            &[("intake", &intake), ("name", &q.from.full_name())],
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
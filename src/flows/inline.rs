@@ -0,0 +1,221 @@
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use redis::Connection;
+
+use crate::flows::take_medicine::register_intake;
+use crate::i18n::{Language, Text};
+use crate::medication::Medication;
+use crate::user::get_user_timezone;
+use crate::{patient::Patient, ConfigParameters, HandlerResult};
+use teloxide::prelude::*;
+use teloxide::types::{
+    InlineKeyboardButton, InlineKeyboardMarkup, InlineQueryResult, InlineQueryResultArticle,
+    InputMessageContent, InputMessageContentText,
+};
+use teloxide::Bot;
+
+// the most results Telegram takes in a single answer
+const MAX_RESULTS: usize = 50;
+
+fn print_status_card(
+    patient: &Patient,
+    medication: &Medication,
+    tz: &str,
+    lang: Language,
+) -> String {
+    format!("{}\n{}", patient.name, medication.print_in_list(tz, lang))
+}
+
+fn generate_status_keyboard(medication: &Medication, lang: Language) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+        lang.get(Text::RegisterIntake).to_string(),
+        format!("inline:{}", medication.id),
+    )]])
+}
+
+// patients whose name contains the query, all of them when it's empty
+fn find_patients(user_id: &str, query: &str, con: Arc<Mutex<Connection>>) -> Vec<Patient> {
+    let query = query.trim().to_lowercase();
+
+    Patient::get_my_patients(user_id, con)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|patient| patient.name.to_lowercase().contains(&query))
+        .collect()
+}
+
+/// Answers `@bot name` with a status card per medication of the matching patients. Cards of
+/// patients the user can edit come with a button to register an intake from wherever they're sent.
+pub async fn inline_query_handler(
+    cfg: ConfigParameters,
+    bot: Bot,
+    lang: Language,
+    q: InlineQuery,
+) -> HandlerResult {
+    let con = cfg.redis_connection;
+    let user_id = q.from.id.to_string();
+    let tz = get_user_timezone(con.clone(), &user_id);
+
+    let results: Vec<InlineQueryResult> = find_patients(&user_id, &q.query, con.clone())
+        .iter()
+        .flat_map(|patient| {
+            Medication::get_all_by_patient_id(&patient.id, con.clone())
+                .into_iter()
+                .map(|medication| {
+                    let card = InputMessageContent::Text(InputMessageContentText::new(
+                        print_status_card(patient, &medication, &tz, lang),
+                    ));
+
+                    let mut article = InlineQueryResultArticle::new(
+                        medication.id.clone(),
+                        format!(
+                            "{} {}",
+                            medication.can_take_emoji(),
+                            lang.fill(
+                                Text::IntakeCandidate,
+                                &[
+                                    ("medicine", &medication.medicine),
+                                    ("patient", &patient.name),
+                                ],
+                            )
+                        ),
+                        card,
+                    )
+                    .description(lang.fill(
+                        Text::InlineStatus,
+                        &[
+                            ("last_taken", &medication.print_last_taken(&tz, lang)),
+                            ("next", &medication.print_can_take_next(&tz, lang)),
+                        ],
+                    ));
+
+                    if patient.can_edit(&user_id) {
+                        article = article.reply_markup(generate_status_keyboard(&medication, lang));
+                    }
+
+                    InlineQueryResult::Article(article)
+                })
+                .collect::<Vec<InlineQueryResult>>()
+        })
+        .take(MAX_RESULTS)
+        .collect();
+
+    // every user sees their own patients, and the statuses change with every intake
+    bot.answer_inline_query(&q.id, results)
+        .is_personal(true)
+        .cache_time(0)
+        .await?;
+
+    Ok(())
+}
+
+pub fn is_inline_intake(q: CallbackQuery) -> bool {
+    q.inline_message_id.is_some() && q.data.is_some_and(|data| data.starts_with("inline:"))
+}
+
+// sent with inline mode, so there's no chat nor dialogue, only the message and who pressed it
+pub async fn inline_intake_callback_handler(
+    cfg: ConfigParameters,
+    bot: Bot,
+    lang: Language,
+    q: CallbackQuery,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let inline_message_id = q.inline_message_id.clone().unwrap_or_default();
+    let con = cfg.redis_connection;
+
+    let data = q.data.clone().unwrap_or_default();
+    let (medication_id, answer) = match data.trim_start_matches("inline:").split_once(':') {
+        Some((medication_id, answer)) => (medication_id.to_string(), Some(answer.to_string())),
+        None => (data.trim_start_matches("inline:").to_string(), None),
+    };
+
+    let found = Medication::get_by_id(&medication_id, con.clone()).and_then(|medication| {
+        Ok((
+            Patient::get_by_id(&medication.patient_id, con.clone())?,
+            medication,
+        ))
+    });
+
+    let Ok((patient, medication)) = found else {
+        bot.answer_callback_query(&q.id).await?;
+        return Ok(());
+    };
+
+    let user_id = q.from.id.to_string();
+
+    if !patient.can_edit(&user_id) {
+        bot.answer_callback_query(&q.id)
+            .text(lang.fill(Text::ViewOnly, &[("patient", &patient.name)]))
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    }
+
+    bot.answer_callback_query(&q.id).await?;
+
+    match answer.as_deref() {
+        // first press, the card could have been sent long ago so it's confirmed first
+        None => {
+            let keyboard = InlineKeyboardMarkup::new(vec![vec![
+                InlineKeyboardButton::callback(
+                    lang.get(Text::RegisterIt).to_string(),
+                    format!("inline:{}:yes", medication.id),
+                ),
+                InlineKeyboardButton::callback(
+                    lang.get(Text::Cancel).to_string(),
+                    format!("inline:{}:cancel", medication.id),
+                ),
+            ]]);
+
+            bot.edit_message_text_inline(
+                &inline_message_id,
+                lang.fill(
+                    Text::ConfirmInlineIntake,
+                    &[
+                        ("medicine", &medication.medicine),
+                        ("dosage", &medication.dosage),
+                        ("patient", &patient.name),
+                    ],
+                ),
+            )
+            .reply_markup(keyboard)
+            .await?;
+        }
+        Some("yes") => {
+            let intake = register_intake(
+                &bot,
+                con,
+                &medication.id,
+                Utc::now().timestamp(),
+                &user_id,
+                &user_id,
+                lang,
+            )
+            .await?;
+
+            bot.edit_message_text_inline(
+                &inline_message_id,
+                lang.fill(
+                    Text::IntakeBy,
+                    &[("intake", &intake), ("name", &q.from.full_name())],
+                ),
+            )
+            .await?;
+        }
+        // back to the card, up to date
+        Some(_) => {
+            let tz = get_user_timezone(con, &user_id);
+
+            bot.edit_message_text_inline(
+                &inline_message_id,
+                print_status_card(&patient, &medication, &tz, lang),
+            )
+            .reply_markup(generate_status_keyboard(&medication, lang))
+            .await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,222 @@
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+use crate::commands::cancel_with_edit;
+use crate::i18n::{Language, Text};
+use crate::inventory::{Stock, DEFAULT_ALERT_DAYS};
+use crate::medication::Medication;
+use crate::user::{get_user_language, get_user_timezone};
+use crate::{ConfigParameters, HandlerResult, MyDialogue, State};
+use redis::Connection;
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, Message};
+use teloxide::Bot;
+
+pub async fn select_stock_medication_callback_handler(
+    cfg: ConfigParameters,
+    bot: Bot,
+    dialogue: MyDialogue,
+    lang: Language,
+    q: CallbackQuery,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let message = q.regular_message().unwrap();
+    bot.answer_callback_query(&q.id).await?;
+
+    if let Some(ref medication_id) = q.data {
+        if medication_id == "cancel" {
+            cancel_with_edit(bot, dialogue, lang, message.to_owned()).await?;
+        } else {
+            let medication = Medication::get_by_id(medication_id, cfg.redis_connection).unwrap();
+
+            let current = match medication.get_stock() {
+                Some(stock) => lang.fill(Text::CurrentStock, &[("stock", stock)]),
+                None => "".to_string(),
+            };
+
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                lang.fill(
+                    Text::AskStock,
+                    &[
+                        ("medicine", &medication.medicine),
+                        ("current", &current),
+                        ("examples", &lang.get(Text::StockExamples)),
+                    ],
+                ),
+            )
+            .await?;
+
+            dialogue
+                .update(State::ReceiveStock {
+                    medication_id: medication_id.to_string(),
+                })
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn receive_stock(
+    bot: Bot,
+    dialogue: MyDialogue,
+    medication_id: String,
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    match msg.text().map(Stock::parse) {
+        Some(Ok(stock)) => {
+            let keyboard = vec![
+                [1, DEFAULT_ALERT_DAYS, 7]
+                    .iter()
+                    .map(|days| {
+                        InlineKeyboardButton::callback(
+                            match days {
+                                1 => lang.get(Text::OneDay).to_string(),
+                                days => lang.fill(Text::Days, &[("days", days)]),
+                            },
+                            format!("{}:{}", days, stock),
+                        )
+                    })
+                    .collect(),
+                vec![InlineKeyboardButton::callback(
+                    lang.get(Text::Cancel).to_string(),
+                    "cancel".to_string(),
+                )],
+            ];
+
+            bot.send_message(
+                msg.chat.id,
+                lang.fill(Text::AskStockAlert, &[("stock", &stock)]),
+            )
+            .reply_markup(InlineKeyboardMarkup::new(keyboard))
+            .await?;
+
+            dialogue
+                .update(State::SelectStockAlertDays { medication_id })
+                .await?;
+        }
+        Some(Err(e)) => {
+            bot.send_message(
+                msg.chat.id,
+                lang.fill(
+                    Text::TryAgain,
+                    &[("error", &e), ("examples", &lang.get(Text::StockExamples))],
+                ),
+            )
+            .await?;
+        }
+        None => {
+            bot.send_message(msg.chat.id, lang.get(Text::DidntGetThat))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn stock_alert_days_callback_handler(
+    cfg: ConfigParameters,
+    bot: Bot,
+    dialogue: MyDialogue,
+    medication_id: String,
+    lang: Language,
+    q: CallbackQuery,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let message = q.regular_message().unwrap();
+    bot.answer_callback_query(&q.id).await?;
+
+    if let Some(ref data) = q.data {
+        if data == "cancel" {
+            cancel_with_edit(bot, dialogue, lang, message.to_owned()).await?;
+            return Ok(());
+        }
+
+        // the stock travels in the callback data, so it doesn't need to be kept in the state
+        let parsed = data
+            .split_once(':')
+            .and_then(|(days, stock)| Some((days.parse::<i64>().ok()?, Stock::parse(stock).ok()?)));
+
+        match parsed {
+            Some((days, mut stock)) => {
+                let con = cfg.redis_connection;
+                let mut medication = Medication::get_by_id(&medication_id, con.clone()).unwrap();
+
+                stock.alert_days = days;
+                medication.set_stock(stock);
+                medication.save(con.clone())?;
+
+                let tz = get_user_timezone(con.clone(), &message.chat.id.to_string());
+
+                bot.edit_message_text(
+                    message.chat.id,
+                    message.id,
+                    lang.fill(
+                        Text::StockUpdated,
+                        &[
+                            ("medicine", &medication.medicine),
+                            (
+                                "stock",
+                                &medication.print_stock(&tz, lang).unwrap_or_default(),
+                            ),
+                            ("days", &days),
+                        ],
+                    ),
+                )
+                .await?;
+            }
+            None => {
+                bot.edit_message_text(
+                    message.chat.id,
+                    message.id,
+                    lang.get(Text::DidntQuiteGetThat),
+                )
+                .await?;
+            }
+        }
+
+        dialogue.exit().await?;
+    }
+
+    Ok(())
+}
+
+pub async fn notify_low_stock(
+    bot: &Bot,
+    con: Arc<Mutex<Connection>>,
+    medication: &Medication,
+    users: Vec<String>,
+    tz: &str,
+) {
+    for telegram_user in users {
+        let lang = get_user_language(con.clone(), &telegram_user);
+
+        if let Err(e) = bot
+            .send_message(
+                telegram_user.clone(),
+                lang.fill(
+                    Text::LowStock,
+                    &[
+                        ("medicine", &medication.medicine),
+                        (
+                            "patient",
+                            &medication.patient_name.clone().unwrap_or_default(),
+                        ),
+                        (
+                            "stock",
+                            &medication.print_stock(tz, lang).unwrap_or_default(),
+                        ),
+                    ],
+                ),
+            )
+            .await
+        {
+            log::warn!(
+                "Failed to notify shared user of low stock: telegram user id {}. Error {}",
+                &telegram_user,
+                e
+            )
+        }
+    }
+}
@@ -0,0 +1,386 @@
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use chrono_tz::Tz;
+use redis::Connection;
+
+use crate::commands::cancel_with_edit;
+use crate::i18n::{is_nothing, Language, Text};
+use crate::measurement::{Measurement, MeasurementKind, Reading, Threshold};
+use crate::medication::Medication;
+use crate::user::get_user_language;
+use crate::{patient::Patient, ConfigParameters, HandlerResult, MyDialogue, State};
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButton, Message};
+use teloxide::Bot;
+
+// how far back the timeline goes, and how many entries it shows at most
+const TIMELINE_DAYS: i64 = 7;
+const TIMELINE_ENTRIES: usize = 30;
+
+pub fn generate_measurement_keyboard(
+    kinds: impl Iterator<Item = MeasurementKind>,
+    lang: Language,
+) -> Vec<Vec<InlineKeyboardButton>> {
+    let buttons: Vec<InlineKeyboardButton> = kinds
+        .map(|kind| {
+            InlineKeyboardButton::callback(
+                format!("{} {}", kind.emoji(), kind.name(lang)),
+                kind.to_string(),
+            )
+        })
+        .collect();
+
+    let mut keyboard: Vec<Vec<InlineKeyboardButton>> =
+        buttons.chunks(2).map(|row| row.to_vec()).collect();
+
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        lang.get(Text::Cancel).to_string(),
+        "cancel".to_string(),
+    )]);
+
+    keyboard
+}
+
+pub async fn select_measurement_kind_callback_handler(
+    bot: Bot,
+    dialogue: MyDialogue,
+    patient_id: String,
+    lang: Language,
+    q: CallbackQuery,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let message = q.regular_message().unwrap();
+    bot.answer_callback_query(&q.id).await?;
+
+    match q.data.as_deref().and_then(MeasurementKind::parse) {
+        Some(kind) => {
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                lang.fill(
+                    Text::AskReading,
+                    &[
+                        ("emoji", &kind.emoji()),
+                        ("kind", &kind.name(lang)),
+                        ("example", &kind.example(lang)),
+                    ],
+                ),
+            )
+            .await?;
+
+            dialogue
+                .update(State::ReceiveMeasurement {
+                    patient_id,
+                    kind: kind.to_string(),
+                })
+                .await?;
+        }
+        None => {
+            cancel_with_edit(bot, dialogue, lang, message.to_owned()).await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn receive_measurement(
+    cfg: ConfigParameters,
+    bot: Bot,
+    dialogue: MyDialogue,
+    (patient_id, kind): (String, String),
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    let kind = MeasurementKind::parse(&kind).unwrap_or(MeasurementKind::Symptom);
+
+    match msg.text().map(|text| Reading::parse(kind, text)) {
+        Some(Ok(reading)) => {
+            let con = cfg.redis_connection;
+            let patient = Patient::get_by_id(&patient_id, con.clone()).unwrap();
+
+            let user_id = msg.chat.id.to_string();
+            let user_name = msg
+                .from
+                .as_ref()
+                .map(|user| user.full_name())
+                .unwrap_or(user_id.clone());
+
+            let measurement = Measurement::new(reading, user_id.clone(), user_name);
+            measurement.save(&patient_id, con.clone())?;
+
+            let exceeded = patient.get_threshold(kind).filter(|threshold| {
+                measurement
+                    .reading
+                    .get_value()
+                    .is_some_and(|value| threshold.is_exceeded(value))
+            });
+
+            match exceeded {
+                Some(threshold) => {
+                    let alert = |lang: Language| {
+                        lang.fill(
+                            Text::MeasurementAlert,
+                            &[
+                                ("patient", &patient.name),
+                                ("kind", &kind.name(lang).to_lowercase()),
+                                ("reading", &measurement.reading),
+                                ("threshold", &threshold.print(lang)),
+                                ("name", &measurement.user_name),
+                            ],
+                        )
+                    };
+
+                    bot.send_message(msg.chat.id, alert(lang)).await?;
+
+                    notify_caregivers(&bot, con.clone(), &patient, &user_id, alert).await;
+                }
+                None => {
+                    bot.send_message(
+                        msg.chat.id,
+                        lang.fill(
+                            Text::MeasurementLogged,
+                            &[
+                                ("emoji", &kind.emoji()),
+                                ("kind", &kind.name(lang)),
+                                ("patient", &patient.name),
+                                ("reading", &measurement.reading),
+                            ],
+                        ),
+                    )
+                    .await?;
+                }
+            }
+
+            dialogue.exit().await?;
+        }
+        Some(Err(e)) => {
+            bot.send_message(
+                msg.chat.id,
+                lang.fill(
+                    Text::TryAgain,
+                    &[("error", &e.print(lang)), ("examples", &kind.example(lang))],
+                ),
+            )
+            .await?;
+        }
+        None => {
+            bot.send_message(msg.chat.id, lang.get(Text::DidntGetThat))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn notify_caregivers(
+    bot: &Bot,
+    con: Arc<Mutex<Connection>>,
+    patient: &Patient,
+    logged_by: &str,
+    alert: impl Fn(Language) -> String,
+) {
+    for telegram_user in patient.get_all_shared_users() {
+        if telegram_user == logged_by {
+            continue;
+        }
+
+        let lang = get_user_language(con.clone(), &telegram_user);
+
+        if let Err(e) = bot.send_message(telegram_user.clone(), alert(lang)).await {
+            log::warn!(
+                "Failed to notify shared user of a measurement: telegram user id {}. Error {}",
+                &telegram_user,
+                e
+            )
+        }
+    }
+}
+
+pub async fn select_threshold_kind_callback_handler(
+    cfg: ConfigParameters,
+    bot: Bot,
+    dialogue: MyDialogue,
+    patient_id: String,
+    lang: Language,
+    q: CallbackQuery,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let message = q.regular_message().unwrap();
+    bot.answer_callback_query(&q.id).await?;
+
+    match q.data.as_deref().and_then(MeasurementKind::parse) {
+        Some(kind) => {
+            let patient = Patient::get_by_id(&patient_id, cfg.redis_connection).unwrap();
+
+            let current = match patient.get_threshold(kind) {
+                Some(threshold) => lang.fill(
+                    Text::CurrentThreshold,
+                    &[("threshold", &threshold.print(lang))],
+                ),
+                None => "".to_string(),
+            };
+
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                lang.fill(
+                    Text::AskThreshold,
+                    &[
+                        ("patient", &patient.name),
+                        ("kind", &kind.name(lang).to_lowercase()),
+                        ("current", &current),
+                    ],
+                ),
+            )
+            .await?;
+
+            dialogue
+                .update(State::ReceiveThreshold {
+                    patient_id,
+                    kind: kind.to_string(),
+                })
+                .await?;
+        }
+        None => {
+            cancel_with_edit(bot, dialogue, lang, message.to_owned()).await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn receive_threshold(
+    cfg: ConfigParameters,
+    bot: Bot,
+    dialogue: MyDialogue,
+    (patient_id, kind): (String, String),
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    let Some(kind) = MeasurementKind::parse(&kind) else {
+        dialogue.exit().await?;
+        return Ok(());
+    };
+
+    let text = msg.text().unwrap_or_default().trim().to_lowercase();
+
+    let threshold = match is_nothing(&text) {
+        true => None,
+        false => match Threshold::parse(kind, &text) {
+            Some(threshold) => Some(threshold),
+            None => {
+                bot.send_message(msg.chat.id, lang.get(Text::NotAThreshold))
+                    .await?;
+                return Ok(());
+            }
+        },
+    };
+
+    let con = cfg.redis_connection;
+    let mut patient = Patient::get_by_id(&patient_id, con.clone()).unwrap();
+
+    patient.set_threshold(kind, threshold);
+    patient
+        .save(con.clone())
+        .expect("Error saving patient after setting a threshold");
+
+    let reply = match threshold {
+        Some(threshold) => lang.fill(
+            Text::ThresholdSet,
+            &[
+                ("patient", &patient.name),
+                ("kind", &kind.name(lang).to_lowercase()),
+                ("threshold", &threshold.print(lang)),
+            ],
+        ),
+        None => lang.fill(
+            Text::ThresholdOff,
+            &[
+                ("patient", &patient.name),
+                ("kind", &kind.name(lang).to_lowercase()),
+            ],
+        ),
+    };
+
+    bot.send_message(msg.chat.id, reply).await?;
+
+    dialogue.exit().await?;
+
+    Ok(())
+}
+
+/// Intakes and measurements of the last days, most recent first.
+pub fn print_timeline(
+    patient: &Patient,
+    con: Arc<Mutex<Connection>>,
+    tz: &Tz,
+    lang: Language,
+) -> String {
+    let since = (Utc::now() - TimeDelta::days(TIMELINE_DAYS)).timestamp();
+
+    let mut entries: Vec<(i64, String)> = vec![];
+
+    for medication in Medication::get_all_by_patient_id(&patient.id, con.clone()) {
+        let intakes = medication
+            .get_intake_history(con.clone())
+            .unwrap_or_default();
+
+        entries.extend(intakes.into_iter().take_while(|ts| *ts >= since).map(|ts| {
+            (
+                ts,
+                format!("💊 {} {}", medication.medicine, medication.dosage),
+            )
+        }));
+    }
+
+    let measurements =
+        Measurement::get_by_patient_id(&patient.id, TIMELINE_ENTRIES as isize, con.clone())
+            .unwrap_or_default();
+
+    entries.extend(
+        measurements
+            .into_iter()
+            .filter(|m| m.taken_at >= since)
+            .map(|m| {
+                (
+                    m.taken_at,
+                    format!(
+                        "{} {} ({})",
+                        m.reading.kind().emoji(),
+                        m.reading,
+                        m.user_name
+                    ),
+                )
+            }),
+    );
+
+    entries.sort_by_key(|(ts, _)| std::cmp::Reverse(*ts));
+
+    if entries.is_empty() {
+        return lang.fill(
+            Text::TimelineEmpty,
+            &[("patient", &patient.name), ("days", &TIMELINE_DAYS)],
+        );
+    }
+
+    let lines = entries
+        .into_iter()
+        .take(TIMELINE_ENTRIES)
+        .map(|(ts, entry)| {
+            format!(
+                " - {} {}\n",
+                DateTime::from_timestamp(ts, 0)
+                    .unwrap()
+                    .with_timezone(tz)
+                    .format("%d/%m %H:%M"),
+                entry
+            )
+        })
+        .collect::<String>();
+
+    format!(
+        "{}\n\n{}",
+        lang.fill(Text::TimelineHeader, &[("patient", &patient.name)]),
+        lines
+    )
+}
@@ -1,3 +1,9 @@
 pub mod add_medication;
+pub mod calendar;
+pub mod group;
+pub mod inline;
+pub mod inventory;
+pub mod measurements;
 pub mod patients;
 pub mod take_medicine;
+pub mod timezone;
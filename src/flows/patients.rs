@@ -1,8 +1,17 @@
+use crate::chart::{render_intake_chart, CHART_DAYS};
 use crate::commands::cancel_with_edit;
+use crate::flows::measurements::{generate_measurement_keyboard, print_timeline};
+use crate::i18n::{is_nothing, Language, Text};
+use crate::invite::Invite;
+use crate::measurement::MeasurementKind;
 use crate::medication::Medication;
-use crate::user::get_user_timezone;
+use crate::patient::Role;
+use crate::report::{render_report, REPORT_PERIODS};
+use crate::timezone::{find_timezone, parse_timezone};
+use crate::user::{get_user_language, get_user_timezone};
+use crate::waking_hours::WakingHours;
 use crate::{patient::Patient, ConfigParameters, HandlerResult, MyDialogue, State};
-use chrono::DateTime;
+use chrono::{DateTime, Utc};
 use chrono_tz::Tz;
 use std::error::Error;
 use teloxide::payloads::EditMessageTextSetters;
@@ -10,7 +19,7 @@ use teloxide::prelude::*;
 use teloxide::types::{
     ButtonRequest, CallbackQuery, KeyboardButton, KeyboardButtonRequestUsers, KeyboardRemove,
 };
-use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode};
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, InputFile, ParseMode};
 use teloxide::types::{KeyboardMarkup, Message};
 use teloxide::Bot;
 
@@ -18,19 +27,18 @@ pub async fn patients_command(
     cfg: ConfigParameters,
     bot: Bot,
     dialogue: MyDialogue,
+    lang: Language,
     msg: Message,
 ) -> HandlerResult {
     let con = cfg.redis_connection;
 
-    let keyboard = Patient::generate_patient_keyboard(con.clone(), msg.chat.id.to_string(), true);
+    let keyboard =
+        Patient::generate_patient_keyboard(con.clone(), msg.chat.id.to_string(), true, lang);
 
-    bot.send_message(
-        msg.chat.id,
-        "🤒 Here are the patients you have access to\\. 🤕\n\nSelect one for more options or add a new one below:"
-    )
-    .reply_markup(InlineKeyboardMarkup::new(keyboard))
-    .parse_mode(ParseMode::MarkdownV2)
-    .await?;
+    bot.send_message(msg.chat.id, lang.get(Text::PatientsIntro))
+        .reply_markup(InlineKeyboardMarkup::new(keyboard))
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
 
     dialogue.update(State::SelectPatient).await?;
     Ok(())
@@ -40,6 +48,7 @@ pub async fn select_patient_callback_handler(
     cfg: ConfigParameters,
     bot: Bot,
     dialogue: MyDialogue,
+    lang: Language,
     q: CallbackQuery,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
     let message = q.regular_message().unwrap();
@@ -47,55 +56,109 @@ pub async fn select_patient_callback_handler(
         bot.answer_callback_query(&q.id).await?;
 
         if patient_id == "cancel" {
-            cancel_with_edit(bot, dialogue, message.to_owned()).await?;
+            cancel_with_edit(bot, dialogue, lang, message.to_owned()).await?;
         } else if patient_id == "add_new" {
-            bot.edit_message_text(
-                message.chat.id,
-                message.id,
-                "Ok, adding a new patient. Please tell me what's their name:",
-            )
-            .await?;
+            bot.edit_message_text(message.chat.id, message.id, lang.get(Text::AskPatientName))
+                .await?;
             dialogue.update(State::ReceivePatientName).await?;
         } else {
             let patient = Patient::get_by_id(patient_id, cfg.redis_connection.clone()).unwrap();
-            let keyboard: Vec<Vec<InlineKeyboardButton>> = vec![
+            let mut keyboard: Vec<Vec<InlineKeyboardButton>> = vec![
                 vec![InlineKeyboardButton::callback(
-                    "Register medicine intake ".to_string(),
+                    lang.get(Text::RegisterIntake).to_string(),
                     "take".to_string(),
                 )],
                 vec![
                     InlineKeyboardButton::callback(
-                        "All medications".to_string(),
+                        lang.get(Text::AllMedications).to_string(),
                         "list_medication".to_string(),
                     ),
                     InlineKeyboardButton::callback(
-                        "Intake log".to_string(),
+                        lang.get(Text::IntakeLog).to_string(),
                         "medication_log".to_string(),
                     ),
+                    InlineKeyboardButton::callback(
+                        lang.get(Text::Charts).to_string(),
+                        "charts".to_string(),
+                    ),
+                ],
+                vec![
+                    InlineKeyboardButton::callback(
+                        lang.get(Text::LogMeasurement).to_string(),
+                        "log_measurement".to_string(),
+                    ),
+                    InlineKeyboardButton::callback(
+                        lang.get(Text::Timeline).to_string(),
+                        "timeline".to_string(),
+                    ),
+                    InlineKeyboardButton::callback(
+                        lang.get(Text::Alerts).to_string(),
+                        "thresholds".to_string(),
+                    ),
+                ],
+                vec![
+                    InlineKeyboardButton::callback(
+                        lang.get(Text::Report).to_string(),
+                        "report".to_string(),
+                    ),
+                    InlineKeyboardButton::callback(
+                        lang.get(Text::Timezone).to_string(),
+                        "timezone".to_string(),
+                    ),
+                ],
+                vec![
+                    InlineKeyboardButton::callback(
+                        lang.get(Text::Stock).to_string(),
+                        "stock".to_string(),
+                    ),
+                    InlineKeyboardButton::callback(
+                        lang.get(Text::Weight).to_string(),
+                        "weight".to_string(),
+                    ),
+                    InlineKeyboardButton::callback(
+                        lang.get(Text::WakingHours).to_string(),
+                        "waking_hours".to_string(),
+                    ),
                 ],
                 vec![
                     InlineKeyboardButton::callback(
-                        "Share".to_string(),
+                        lang.get(Text::Share).to_string(),
                         "share_patient".to_string(),
                     ),
                     InlineKeyboardButton::callback(
-                        "Delete".to_string(),
+                        lang.get(Text::InviteLink).to_string(),
+                        "invite_patient".to_string(),
+                    ),
+                    InlineKeyboardButton::callback(
+                        lang.get(Text::Delete).to_string(),
                         "delete_patient".to_string(),
                     ),
                 ],
-                vec![InlineKeyboardButton::callback(
-                    "Cancel".to_string(),
-                    "cancel".to_string(),
-                )],
             ];
 
             let sharing = patient.get_shared_with();
-            let shared_msg = if sharing.len() > 0 {
-                format!(
-                    "Patient shared with accounts: {}\\.\n\n",
-                    sharing
-                        .iter()
-                        .fold(String::new(), |acc, id| { acc + &id.to_string() })
+
+            if patient.is_owner(&message.chat.id.to_string()) && !sharing.is_empty() {
+                keyboard.push(vec![InlineKeyboardButton::callback(
+                    lang.get(Text::TransferOwnership).to_string(),
+                    "transfer_patient".to_string(),
+                )]);
+            }
+
+            keyboard.push(vec![InlineKeyboardButton::callback(
+                lang.get(Text::Cancel).to_string(),
+                "cancel".to_string(),
+            )]);
+
+            let shared_msg = if !sharing.is_empty() {
+                lang.fill(
+                    Text::SharedWith,
+                    &[(
+                        "accounts",
+                        &sharing
+                            .iter()
+                            .fold(String::new(), |acc, id| acc + &id.to_string()),
+                    )],
                 )
             } else {
                 "".to_string()
@@ -104,7 +167,10 @@ pub async fn select_patient_callback_handler(
             bot.edit_message_text(
                 message.chat.id,
                 message.id,
-                format!("{}Select optzionne for {}:", shared_msg, patient.name),
+                lang.fill(
+                    Text::SelectOption,
+                    &[("shared", &shared_msg), ("patient", &patient.name)],
+                ),
             )
             .reply_markup(InlineKeyboardMarkup::new(keyboard))
             .parse_mode(ParseMode::MarkdownV2)
@@ -126,6 +192,7 @@ pub async fn patient_ops_callback_handler(
     bot: Bot,
     dialogue: MyDialogue,
     patient_id: String,
+    lang: Language,
     q: CallbackQuery,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
     let message = q.regular_message().unwrap();
@@ -136,26 +203,38 @@ pub async fn patient_ops_callback_handler(
         let patient = Patient::get_by_id(&patient_id, con.clone()).unwrap();
 
         if op == "cancel" {
-            cancel_with_edit(bot, dialogue, message.to_owned()).await?;
+            cancel_with_edit(bot, dialogue, lang, message.to_owned()).await?;
+        } else if op != "list_medication"
+            && op != "medication_log"
+            && op != "charts"
+            && op != "report"
+            && op != "timeline"
+            && !patient.can_edit(&message.chat.id.to_string())
+        {
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                lang.fill(Text::ViewOnly, &[("patient", &patient.name)]),
+            )
+            .await?;
+            dialogue.exit().await?;
         } else if op == "take" {
-            let new_keyb = Medication::generate_medication_keyboard(&patient_id, con.clone());
+            let new_keyb = Medication::generate_medication_keyboard(&patient_id, con.clone(), lang);
 
             bot.edit_message_text(
                 message.chat.id,
                 message.id,
-                "Great, now the name of the medicine?",
+                lang.get(Text::AskMedicineTaken),
             )
             .reply_markup(InlineKeyboardMarkup::new(new_keyb))
             .parse_mode(ParseMode::MarkdownV2)
             .await?;
 
             dialogue
-                .update(State::TakeMedicineFinal {
-                    patient_id: patient_id.into(),
-                })
+                .update(State::TakeMedicineFinal { patient_id })
                 .await?;
         } else if op == "share_patient" {
-            let btn = KeyboardButton::new("Select user");
+            let btn = KeyboardButton::new(lang.get(Text::SelectUser));
             let btnrequest = btn.request(ButtonRequest::RequestUsers(
                 KeyboardButtonRequestUsers::new(teloxide::types::RequestId(1)),
             ));
@@ -163,17 +242,77 @@ pub async fn patient_ops_callback_handler(
 
             bot.send_message(
                 message.chat.id,
-                format!(
-                    "Great. Now please select the Telegram user you'll be sharing {} with or /cancel.",
-                    patient.name
-                ),
+                lang.fill(Text::AskShareUser, &[("patient", &patient.name)]),
             )
             .reply_markup(keyb.one_time_keyboard())
             .await?;
             dialogue
-                .update(State::ReceiveTelegramUserForSharePatient {
-                    patient_id: patient_id.into(),
-                })
+                .update(State::ReceiveTelegramUserForSharePatient { patient_id })
+                .await?;
+        } else if op == "invite_patient" {
+            let keyboard = vec![
+                vec![
+                    InlineKeyboardButton::callback(
+                        lang.get(Text::Caregiver).to_string(),
+                        Role::Caregiver.to_string(),
+                    ),
+                    InlineKeyboardButton::callback(
+                        lang.get(Text::Viewer).to_string(),
+                        Role::Viewer.to_string(),
+                    ),
+                ],
+                vec![InlineKeyboardButton::callback(
+                    lang.get(Text::Cancel).to_string(),
+                    "cancel".to_string(),
+                )],
+            ];
+
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                lang.fill(Text::AskInviteRole, &[("patient", &patient.name)]),
+            )
+            .reply_markup(InlineKeyboardMarkup::new(keyboard))
+            .await?;
+
+            dialogue
+                .update(State::SelectInviteRole { patient_id })
+                .await?;
+        } else if (op == "delete_patient" || op == "transfer_patient")
+            && !patient.is_owner(&message.chat.id.to_string())
+        {
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                lang.fill(Text::OnlyOwner, &[("patient", &patient.name)]),
+            )
+            .await?;
+            dialogue.exit().await?;
+        } else if op == "transfer_patient" {
+            let mut keyboard: Vec<Vec<InlineKeyboardButton>> = vec![];
+
+            for user_id in patient.get_shared_with() {
+                keyboard.push(vec![InlineKeyboardButton::callback(
+                    get_user_display_name(&bot, user_id).await,
+                    user_id.to_string(),
+                )]);
+            }
+
+            keyboard.push(vec![InlineKeyboardButton::callback(
+                lang.get(Text::Cancel).to_string(),
+                "cancel".to_string(),
+            )]);
+
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                lang.fill(Text::AskNewOwner, &[("patient", &patient.name)]),
+            )
+            .reply_markup(InlineKeyboardMarkup::new(keyboard))
+            .await?;
+
+            dialogue
+                .update(State::SelectTransferOwner { patient_id })
                 .await?;
         } else if op == "delete_patient" {
             let patient = Patient::get_by_id(&patient_id, con.clone()).expect("Patient not found");
@@ -181,7 +320,7 @@ pub async fn patient_ops_callback_handler(
             bot.edit_message_text(
                 message.chat.id,
                 message.id,
-                format!("Patient {} deleted.", patient.name),
+                lang.fill(Text::PatientDeleted, &[("patient", &patient.name)]),
             )
             .await?;
             dialogue.exit().await?;
@@ -193,55 +332,513 @@ pub async fn patient_ops_callback_handler(
             medicines.reverse();
 
             let msg = match medicines.len() {
-                0 => format!(
-                    "No medications added yet for {}, add one with /addmedication.",
-                    patient.name
-                ),
+                0 => lang.fill(Text::NoMedicationsFor, &[("patient", &patient.name)]),
                 _ => {
                     let tz = get_user_timezone(con.clone(), &message.chat.id.to_string());
 
                     let msg = medicines
                         .iter()
-                        .map(|m| m.print_in_list(&tz) + "\n")
+                        .map(|m| m.print_in_list(&tz, lang) + "\n")
                         .collect::<String>();
 
-                    format!("{}\nRegister a taken dosage by running /take.", msg)
+                    let interactions = Medication::print_all_interactions(&medicines, lang);
+                    let interactions = if interactions.is_empty() {
+                        interactions
+                    } else {
+                        format!("\n{}:\n{}", lang.get(Text::Interactions), interactions)
+                    };
+
+                    format!(
+                        "{}{}\n{}",
+                        msg,
+                        interactions,
+                        lang.get(Text::RegisterWithTake)
+                    )
                 }
             };
             bot.edit_message_text(message.chat.id, message.id, msg)
                 .await?;
 
             dialogue.exit().await?;
-        } else if op == "medication_log" {
-            let new_keyb = Medication::generate_medication_keyboard(&patient_id, con.clone());
+        } else if op == "weight" {
+            let current = match patient.weight_kg {
+                Some(kg) => lang.fill(Text::CurrentWeight, &[("kg", &kg)]),
+                None => "".to_string(),
+            };
+
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                lang.fill(
+                    Text::AskWeight,
+                    &[("patient", &patient.name), ("current", &current)],
+                ),
+            )
+            .await?;
 
+            dialogue
+                .update(State::ReceivePatientWeight { patient_id })
+                .await?;
+        } else if op == "waking_hours" {
             bot.edit_message_text(
                 message.chat.id,
                 message.id,
-                "Great, getting the log for which medicine?",
+                lang.fill(
+                    Text::AskWakingHours,
+                    &[
+                        ("patient", &patient.name),
+                        ("waking_hours", &patient.get_waking_hours()),
+                    ],
+                ),
+            )
+            .await?;
+
+            dialogue
+                .update(State::ReceiveWakingHours { patient_id })
+                .await?;
+        } else if op == "timezone" {
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                lang.fill(
+                    Text::AskPatientTimezone,
+                    &[
+                        ("patient", &patient.name),
+                        ("timezone", &patient.get_timezone(con.clone()).name()),
+                    ],
+                ),
+            )
+            .await?;
+
+            dialogue
+                .update(State::ReceivePatientTimezone { patient_id })
+                .await?;
+        } else if op == "stock" {
+            let new_keyb = Medication::generate_medication_keyboard(&patient_id, con.clone(), lang);
+
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                lang.get(Text::AskStockMedicine),
             )
             .reply_markup(InlineKeyboardMarkup::new(new_keyb))
-            .parse_mode(ParseMode::MarkdownV2)
             .await?;
 
             dialogue
-                .update(State::MedicineLog {
-                    patient_id: patient_id.into(),
-                })
+                .update(State::SelectStockMedication { patient_id })
+                .await?;
+        } else if op == "medication_log" {
+            let new_keyb = Medication::generate_medication_keyboard(&patient_id, con.clone(), lang);
+
+            bot.edit_message_text(message.chat.id, message.id, lang.get(Text::AskLogMedicine))
+                .reply_markup(InlineKeyboardMarkup::new(new_keyb))
+                .parse_mode(ParseMode::MarkdownV2)
+                .await?;
+
+            dialogue.update(State::MedicineLog { patient_id }).await?;
+        } else if op == "charts" {
+            let medicines = Medication::get_all_by_patient_id(&patient_id, con.clone());
+
+            let tz = get_user_timezone(con.clone(), &message.chat.id.to_string());
+            let timezone: Tz = tz.parse().unwrap();
+            let today = Utc::now().with_timezone(&timezone).date_naive();
+
+            let mut charts = vec![];
+            for medication in medicines {
+                let intakes = medication.get_intake_history(con.clone())?;
+
+                if let Some(doses) = medication.get_schedule(&intakes, CHART_DAYS) {
+                    let png_bytes = render_intake_chart(&intakes, &doses, &timezone, today)?;
+                    charts.push((medication, png_bytes));
+                }
+            }
+
+            let msg = match charts.len() {
+                0 => lang.fill(Text::NothingTaken, &[("patient", &patient.name)]),
+                _ => lang.fill(
+                    Text::ChartsIntro,
+                    &[("patient", &patient.name), ("days", &CHART_DAYS)],
+                ),
+            };
+
+            bot.edit_message_text(message.chat.id, message.id, msg)
+                .await?;
+
+            for (medication, png_bytes) in charts {
+                bot.send_photo(
+                    message.chat.id,
+                    InputFile::memory(png_bytes).file_name(format!("{}.png", medication.medicine)),
+                )
+                .caption(format!(
+                    "{} ({}, {})",
+                    medication.medicine,
+                    medication.dosage,
+                    medication.get_frequency().print(lang)
+                ))
+                .await?;
+            }
+
+            dialogue.exit().await?;
+        } else if op == "log_measurement" {
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                lang.fill(Text::AskMeasurementKind, &[("patient", &patient.name)]),
+            )
+            .reply_markup(InlineKeyboardMarkup::new(generate_measurement_keyboard(
+                MeasurementKind::ALL.into_iter(),
+                lang,
+            )))
+            .await?;
+
+            dialogue
+                .update(State::SelectMeasurementKind { patient_id })
+                .await?;
+        } else if op == "thresholds" {
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                lang.fill(Text::AskThresholdKind, &[("patient", &patient.name)]),
+            )
+            .reply_markup(InlineKeyboardMarkup::new(generate_measurement_keyboard(
+                MeasurementKind::ALL
+                    .into_iter()
+                    .filter(|kind| kind.has_threshold()),
+                lang,
+            )))
+            .await?;
+
+            dialogue
+                .update(State::SelectThresholdKind { patient_id })
+                .await?;
+        } else if op == "timeline" {
+            let tz = get_user_timezone(con.clone(), &message.chat.id.to_string());
+            let timezone: Tz = tz.parse().unwrap();
+
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                print_timeline(&patient, con.clone(), &timezone, lang),
+            )
+            .await?;
+
+            dialogue.exit().await?;
+        } else if op == "report" {
+            let keyboard = vec![
+                REPORT_PERIODS
+                    .iter()
+                    .map(|days| {
+                        InlineKeyboardButton::callback(
+                            lang.fill(Text::Days, &[("days", days)]),
+                            days.to_string(),
+                        )
+                    })
+                    .collect(),
+                vec![InlineKeyboardButton::callback(
+                    lang.get(Text::Cancel).to_string(),
+                    "cancel".to_string(),
+                )],
+            ];
+
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                lang.fill(Text::AskReportPeriod, &[("patient", &patient.name)]),
+            )
+            .reply_markup(InlineKeyboardMarkup::new(keyboard))
+            .await?;
+
+            dialogue
+                .update(State::SelectReportPeriod { patient_id })
                 .await?;
         } else {
-            bot.edit_message_text(message.chat.id, message.id, "Didn't quite get that, sorry.")
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                lang.get(Text::DidntQuiteGetThat),
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+pub async fn report_period_callback_handler(
+    cfg: ConfigParameters,
+    bot: Bot,
+    dialogue: MyDialogue,
+    patient_id: String,
+    lang: Language,
+    q: CallbackQuery,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let message = q.regular_message().unwrap();
+    bot.answer_callback_query(&q.id).await?;
+
+    if let Some(ref data) = q.data {
+        if data == "cancel" {
+            cancel_with_edit(bot, dialogue, lang, message.to_owned()).await?;
+            return Ok(());
+        }
+
+        let days = data.parse::<i64>().unwrap_or(REPORT_PERIODS[0]);
+
+        let con = cfg.redis_connection;
+        let patient = Patient::get_by_id(&patient_id, con.clone()).unwrap();
+        let medications = Medication::get_all_by_patient_id(&patient_id, con.clone());
+        let intakes = medications
+            .iter()
+            .map(|m| m.get_intake_history(con.clone()))
+            .collect::<Result<Vec<Vec<i64>>, _>>()?;
+
+        let tz = get_user_timezone(con.clone(), &message.chat.id.to_string());
+        let timezone: Tz = tz.parse().unwrap();
+
+        let html = render_report(&patient, &medications, &intakes, days, &timezone);
+
+        bot.edit_message_text(
+            message.chat.id,
+            message.id,
+            lang.fill(
+                Text::ReportReady,
+                &[("patient", &patient.name), ("days", &days)],
+            ),
+        )
+        .await?;
+
+        bot.send_document(
+            message.chat.id,
+            InputFile::memory(html.into_bytes()).file_name(format!("{}.html", patient.name)),
+        )
+        .await?;
+
+        dialogue.exit().await?;
+    }
+
+    Ok(())
+}
+
+pub async fn invite_role_callback_handler(
+    cfg: ConfigParameters,
+    bot: Bot,
+    dialogue: MyDialogue,
+    patient_id: String,
+    lang: Language,
+    q: CallbackQuery,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let message = q.regular_message().unwrap();
+    bot.answer_callback_query(&q.id).await?;
+
+    if let Some(ref role) = q.data {
+        if role == "cancel" {
+            cancel_with_edit(bot, dialogue, lang, message.to_owned()).await?;
+        } else if let Some(role) = Role::parse(role).filter(|role| *role != Role::Owner) {
+            let con = cfg.redis_connection;
+            let patient = Patient::get_by_id(&patient_id, con.clone()).unwrap();
+
+            let invite = Invite::new(patient_id, role, message.chat.id.to_string());
+            invite.save(con.clone())?;
+
+            let me = bot.get_me().await?;
+            let tz = get_user_timezone(con.clone(), &message.chat.id.to_string());
+
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                lang.fill(
+                    Text::InviteCreated,
+                    &[
+                        ("patient", &patient.name),
+                        ("role", &role.print(lang)),
+                        ("expiry", &invite.print_expiry(&tz)),
+                        ("link", &invite.get_link(me.username())),
+                    ],
+                ),
+            )
+            .await?;
+
+            dialogue.exit().await?;
+        } else {
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                lang.get(Text::DidntQuiteGetThat),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn transfer_owner_callback_handler(
+    cfg: ConfigParameters,
+    bot: Bot,
+    dialogue: MyDialogue,
+    patient_id: String,
+    lang: Language,
+    q: CallbackQuery,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let message = q.regular_message().unwrap();
+    bot.answer_callback_query(&q.id).await?;
+
+    if let Some(ref user_id) = q.data {
+        if user_id == "cancel" {
+            cancel_with_edit(bot, dialogue, lang, message.to_owned()).await?;
+            return Ok(());
+        }
+
+        let con = cfg.redis_connection;
+        let mut patient = Patient::get_by_id(&patient_id, con.clone()).unwrap();
+
+        if !patient.is_owner(&q.from.id.to_string()) || !patient.request_transfer(user_id) {
+            bot.edit_message_text(
+                message.chat.id,
+                message.id,
+                lang.get(Text::DidntQuiteGetThat),
+            )
+            .await?;
+            dialogue.exit().await?;
+            return Ok(());
+        }
+
+        patient
+            .save(con.clone())
+            .expect("Error saving patient after requesting transfer");
+
+        let new_owner_lang = get_user_language(con.clone(), user_id);
+
+        let keyboard = vec![vec![
+            InlineKeyboardButton::callback(
+                new_owner_lang.get(Text::Accept).to_string(),
+                format!("transfer_accept:{}", patient.id),
+            ),
+            InlineKeyboardButton::callback(
+                new_owner_lang.get(Text::Decline).to_string(),
+                format!("transfer_decline:{}", patient.id),
+            ),
+        ]];
+
+        bot.send_message(
+            user_id.clone(),
+            new_owner_lang.fill(
+                Text::TransferRequest,
+                &[("name", &q.from.full_name()), ("patient", &patient.name)],
+            ),
+        )
+        .reply_markup(InlineKeyboardMarkup::new(keyboard))
+        .await?;
+
+        bot.edit_message_text(
+            message.chat.id,
+            message.id,
+            lang.fill(
+                Text::TransferRequested,
+                &[
+                    ("name", &get_user_display_name(&bot, user_id).await),
+                    ("patient", &patient.name),
+                ],
+            ),
+        )
+        .await?;
+
+        dialogue.exit().await?;
+    }
+
+    Ok(())
+}
+
+// only the answers, the "transfer_patient" menu op goes through the dialogue
+pub fn is_transfer_response(q: CallbackQuery) -> bool {
+    q.data.is_some_and(|data| {
+        data.starts_with("transfer_accept:") || data.starts_with("transfer_decline:")
+    })
+}
+
+// works regardless of the dialogue state, the request can arrive at any point
+pub async fn transfer_response_callback_handler(
+    cfg: ConfigParameters,
+    bot: Bot,
+    lang: Language,
+    q: CallbackQuery,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let message = q.regular_message().unwrap();
+    bot.answer_callback_query(&q.id).await?;
+
+    let data = q.data.clone().unwrap_or_default();
+    let (action, patient_id) = data.split_once(':').unwrap_or((&data, ""));
+    let user_id = q.from.id.to_string();
+
+    let con = cfg.redis_connection;
+    let mut patient = match Patient::get_by_id(patient_id, con.clone()) {
+        Ok(patient) if patient.get_pending_owner() == Some(&user_id) => patient,
+        _ => {
+            bot.edit_message_text(message.chat.id, message.id, lang.get(Text::TransferInvalid))
                 .await?;
+            return Ok(());
         }
+    };
+
+    let previous_owner = patient.get_owner().to_string();
+    let owner_lang = get_user_language(con.clone(), &previous_owner);
+    let name = q.from.full_name();
+
+    let (reply, notification) = if action == "transfer_accept" {
+        patient.accept_transfer(&user_id, con.clone())?;
+        (
+            lang.fill(Text::TransferAccepted, &[("patient", &patient.name)]),
+            owner_lang.fill(
+                Text::TransferAcceptedNotification,
+                &[("name", &name), ("patient", &patient.name)],
+            ),
+        )
+    } else {
+        patient.cancel_transfer();
+        (
+            lang.fill(Text::TransferDeclined, &[("patient", &patient.name)]),
+            owner_lang.fill(
+                Text::TransferDeclinedNotification,
+                &[("name", &name), ("patient", &patient.name)],
+            ),
+        )
+    };
+
+    patient
+        .save(con.clone())
+        .expect("Error saving patient after ownership transfer");
+
+    bot.edit_message_text(message.chat.id, message.id, reply)
+        .await?;
+
+    if let Err(e) = bot.send_message(previous_owner.clone(), notification).await {
+        log::warn!(
+            "Failed to notify owner of transfer response: telegram user id {}. Error {}",
+            &previous_owner,
+            e
+        )
     }
+
     Ok(())
 }
 
+async fn get_user_display_name(bot: &Bot, user_id: &str) -> String {
+    match user_id.parse::<i64>() {
+        Ok(id) => match bot.get_chat(ChatId(id)).await {
+            Ok(chat) => chat
+                .first_name()
+                .map(|name| name.to_string())
+                .unwrap_or(user_id.to_string()),
+            Err(_) => user_id.to_string(),
+        },
+        Err(_) => user_id.to_string(),
+    }
+}
+
 pub async fn receive_telegram_user_name(
     cfg: ConfigParameters,
     bot: Bot,
     dialogue: MyDialogue,
     patient_id: String,
+    lang: Language,
     msg: Message,
 ) -> HandlerResult {
     match msg.shared_users() {
@@ -260,14 +857,17 @@ pub async fn receive_telegram_user_name(
                 .save(con.clone())
                 .expect("Error saving patient after sharing");
 
-            bot.send_message(msg.chat.id, format!("Patient {} shared.", patient.name))
-                .await?;
+            bot.send_message(
+                msg.chat.id,
+                lang.fill(Text::PatientShared, &[("patient", &patient.name)]),
+            )
+            .await?;
 
             dialogue.exit().await?;
         }
         None => match msg.text() {
-            Some(text) if text == "/cancel" => {
-                bot.send_message(msg.chat.id, "Cancelling the current operation.")
+            Some("/cancel") => {
+                bot.send_message(msg.chat.id, lang.get(Text::Cancelling))
                     .reply_markup(KeyboardRemove::new())
                     .await?;
                 dialogue.exit().await?;
@@ -285,15 +885,18 @@ pub async fn receive_telegram_user_name(
                     .save(con.clone())
                     .expect("Error saving patient after sharing");
 
-                bot.send_message(msg.chat.id, "Patient shared.")
-                    .reply_markup(KeyboardRemove::new())
-                    .await?;
+                bot.send_message(
+                    msg.chat.id,
+                    lang.fill(Text::PatientShared, &[("patient", &patient.name)]),
+                )
+                .reply_markup(KeyboardRemove::new())
+                .await?;
 
                 dialogue.exit().await?;
             }
             _ => {
-                bot.send_message(msg.chat.id, "That doesn't look like a Telegram User ID (should be a number). Please try again or /cancel.")
-                        .await?;
+                bot.send_message(msg.chat.id, lang.get(Text::NotAUserId))
+                    .await?;
             }
         },
     }
@@ -305,6 +908,7 @@ pub async fn receive_new_patient_name(
     cfg: ConfigParameters,
     bot: Bot,
     dialogue: MyDialogue,
+    lang: Language,
     msg: Message,
 ) -> HandlerResult {
     match msg.text() {
@@ -313,9 +917,101 @@ pub async fn receive_new_patient_name(
             patient.save(cfg.redis_connection).unwrap();
             bot.send_message(
                 msg.chat.id,
-                format!(
-                    "Added patient {}. You might want to /addmedication next.",
-                    text
+                lang.fill(Text::PatientAdded, &[("patient", &text)]),
+            )
+            .await?;
+
+            dialogue.exit().await?;
+        }
+        None => {
+            bot.send_message(msg.chat.id, lang.get(Text::DidntGetThat))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn receive_patient_weight(
+    cfg: ConfigParameters,
+    bot: Bot,
+    dialogue: MyDialogue,
+    patient_id: String,
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    match msg.text().and_then(Patient::parse_weight) {
+        Some(weight_kg) => {
+            let con = cfg.redis_connection;
+            let mut patient = Patient::get_by_id(&patient_id, con.clone()).unwrap();
+
+            patient.weight_kg = Some(weight_kg);
+            patient
+                .save(con.clone())
+                .expect("Error saving patient after setting weight");
+
+            let mut report = lang.fill(
+                Text::WeightSet,
+                &[("patient", &patient.name), ("kg", &weight_kg)],
+            ) + "\n";
+
+            for mut medication in Medication::get_all_by_patient_id(&patient_id, con.clone()) {
+                if medication.recalculate_dosage(weight_kg) {
+                    medication.save(con.clone())?;
+
+                    report += &format!(
+                        "\n{}",
+                        lang.fill(
+                            Text::DosageRecalculated,
+                            &[
+                                ("medicine", &medication.medicine),
+                                ("dosage", &medication.dosage),
+                            ],
+                        )
+                    );
+
+                    if let Some(warning) = medication.print_max_dose_warning(weight_kg, lang) {
+                        report += &format!(" {}", warning);
+                    }
+                }
+            }
+
+            bot.send_message(msg.chat.id, report).await?;
+
+            dialogue.exit().await?;
+        }
+        None => {
+            bot.send_message(msg.chat.id, lang.get(Text::NotAWeight))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn receive_waking_hours(
+    cfg: ConfigParameters,
+    bot: Bot,
+    dialogue: MyDialogue,
+    patient_id: String,
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    match msg.text().and_then(WakingHours::parse) {
+        Some(waking_hours) => {
+            let con = cfg.redis_connection;
+            let mut patient = Patient::get_by_id(&patient_id, con.clone()).unwrap();
+
+            patient.set_waking_hours(waking_hours);
+            patient
+                .save(con.clone())
+                .expect("Error saving patient after setting waking hours");
+
+            bot.send_message(
+                msg.chat.id,
+                lang.fill(
+                    Text::WakingHoursSet,
+                    &[("patient", &patient.name), ("waking_hours", &waking_hours)],
                 ),
             )
             .await?;
@@ -323,7 +1019,7 @@ pub async fn receive_new_patient_name(
             dialogue.exit().await?;
         }
         None => {
-            bot.send_message(msg.chat.id, "Didn't get that, please try again or /cancel.")
+            bot.send_message(msg.chat.id, lang.get(Text::NotWakingHours))
                 .await?;
         }
     }
@@ -331,11 +1027,62 @@ pub async fn receive_new_patient_name(
     Ok(())
 }
 
+pub async fn receive_patient_timezone(
+    cfg: ConfigParameters,
+    bot: Bot,
+    dialogue: MyDialogue,
+    patient_id: String,
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    let text = msg.text().unwrap_or_default().trim();
+
+    // skipping goes back to the owner's timezone
+    let timezone = match msg.location() {
+        Some(location) => find_timezone(location.latitude, location.longitude).map(Some),
+        None if is_nothing(text) => Some(None),
+        None => parse_timezone(text).map(Some),
+    };
+
+    let Some(timezone) = timezone else {
+        let reply = match text.is_empty() {
+            true => lang.get(Text::DidntGetThat).to_string(),
+            false => lang.fill(Text::NotATimezone, &[("timezone", &text)]),
+        };
+
+        bot.send_message(msg.chat.id, reply).await?;
+        return Ok(());
+    };
+
+    let con = cfg.redis_connection;
+    let mut patient = Patient::get_by_id(&patient_id, con.clone()).unwrap();
+
+    patient.set_timezone(timezone);
+    patient
+        .save(con.clone())
+        .expect("Error saving patient after setting the timezone");
+
+    let reply = match timezone {
+        Some(timezone) => lang.fill(
+            Text::PatientTimezoneSet,
+            &[("patient", &patient.name), ("timezone", &timezone.name())],
+        ),
+        None => lang.fill(Text::PatientTimezoneOff, &[("patient", &patient.name)]),
+    };
+
+    bot.send_message(msg.chat.id, reply).await?;
+
+    dialogue.exit().await?;
+
+    Ok(())
+}
+
 pub async fn medicine_log_callback_handler(
     cfg: ConfigParameters,
     bot: Bot,
     dialogue: MyDialogue,
     patient_id: String,
+    lang: Language,
     q: CallbackQuery,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
     let message = q.regular_message().unwrap();
@@ -343,26 +1090,27 @@ pub async fn medicine_log_callback_handler(
 
     if let Some(ref medication_id) = q.data {
         if medication_id == "cancel" {
-            cancel_with_edit(bot, dialogue, message.to_owned()).await?;
+            cancel_with_edit(bot, dialogue, lang, message.to_owned()).await?;
         } else {
             let con = cfg.redis_connection;
             let patient = Patient::get_by_id(&patient_id, con.clone()).unwrap();
             let medication = Medication::get_by_id(medication_id, con.clone()).unwrap();
             let log = medication.get_medication_log(con.clone()).unwrap();
 
-            let header = format!(
-                "Log for {} administration of {} ({}):\n",
-                patient.name, medication.medicine, medication.dosage
-            );
+            let header = lang.fill(
+                Text::LogHeader,
+                &[
+                    ("patient", &patient.name),
+                    ("medicine", &medication.medicine),
+                    ("dosage", &medication.dosage),
+                ],
+            ) + "\n";
 
-            if log.len() == 0 {
+            if log.is_empty() {
                 bot.edit_message_text(
                     message.chat.id,
                     message.id,
-                    format!(
-                        "{}\n{}",
-                        header, " - Patient hasn't taken this medicine yet."
-                    ),
+                    format!("{}\n{}", header, lang.get(Text::LogEmpty)),
                 )
                 .await?;
             } else {
@@ -380,7 +1128,6 @@ pub async fn medicine_log_callback_handler(
                                 DateTime::from_timestamp(ts, 0)
                                     .unwrap()
                                     .with_timezone(&timezone)
-                                    .to_string()
                             ))
                             .collect::<String>()
                     ),
@@ -393,3 +1140,31 @@ pub async fn medicine_log_callback_handler(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn callback_query(data: &str) -> CallbackQuery {
+        serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "from": {"id": 1234, "is_bot": false, "first_name": "Anna"},
+            "chat_instance": "1",
+            "data": data,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_is_transfer_response() {
+        assert!(is_transfer_response(callback_query(
+            "transfer_accept:patient"
+        )));
+        assert!(is_transfer_response(callback_query(
+            "transfer_decline:patient"
+        )));
+
+        // the menu op is left to patient_ops_callback_handler
+        assert!(!is_transfer_response(callback_query("transfer_patient")));
+    }
+}
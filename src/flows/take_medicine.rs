@@ -1,15 +1,24 @@
 use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use redis::Connection;
 
 use medibot::State;
 
 use crate::commands::cancel_with_edit;
+use crate::flows::inventory::notify_low_stock;
+use crate::free_text::{IntakeCandidate, IntakeText};
+use crate::i18n::{Language, Text};
 use crate::medication::Medication;
-use crate::user::get_user_timezone;
+use crate::timezone::print_patient_time;
+use crate::user::{get_user_language, get_user_timezone};
 use crate::{patient::Patient, ConfigParameters, HandlerResult, MyDialogue};
 
 use teloxide::{
     prelude::*,
-    types::{InlineKeyboardMarkup, Message, ParseMode},
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, Message, ParseMode},
     Bot,
 };
 
@@ -17,11 +26,12 @@ pub async fn take_medicine_callback_handler(
     cfg: ConfigParameters,
     bot: Bot,
     dialogue: MyDialogue,
+    lang: Language,
     q: CallbackQuery,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
     if let Some(ref patient_id) = q.data {
         if patient_id == "cancel" {
-            cancel_with_edit(bot, dialogue, q.regular_message().unwrap().to_owned()).await?;
+            cancel_with_edit(bot, dialogue, lang, q.regular_message().unwrap().to_owned()).await?;
         } else {
             log::info!("You chose: {patient_id}");
             let con = cfg.redis_connection;
@@ -32,22 +42,32 @@ pub async fn take_medicine_callback_handler(
             let medication = Medication::get_all_by_patient_id(patient_id, con.clone());
             let patient = Patient::get_by_id(patient_id, con.clone()).unwrap();
 
-            if medication.len() == 0 {
+            if !patient.can_edit(&q.from.id.to_string()) {
                 bot.edit_message_text(
                     message.chat.id,
                     message.id,
-                    format!("Sorry you haven't added any medication plans for {} yet, try /addmedication.", patient.name)
+                    lang.fill(Text::ViewOnly, &[("patient", &patient.name)]),
+                )
+                .await?;
+
+                dialogue.exit().await?;
+            } else if medication.is_empty() {
+                bot.edit_message_text(
+                    message.chat.id,
+                    message.id,
+                    lang.fill(Text::NoPlans, &[("patient", &patient.name)]),
                 )
                 .await?;
 
                 dialogue.exit().await?;
             } else {
-                let new_keyb = Medication::generate_medication_keyboard(patient_id, con.clone());
+                let new_keyb =
+                    Medication::generate_medication_keyboard(patient_id, con.clone(), lang);
 
                 bot.edit_message_text(
                     message.chat.id,
                     message.id,
-                    "Great, now the name of the medicine?",
+                    lang.get(Text::AskMedicineTaken),
                 )
                 .reply_markup(InlineKeyboardMarkup::new(new_keyb))
                 .parse_mode(ParseMode::MarkdownV2)
@@ -69,67 +89,31 @@ pub async fn take_medicine_second_callback_handler(
     cfg: ConfigParameters,
     bot: Bot,
     dialogue: MyDialogue,
-    patient_id: String,
+    lang: Language,
     q: CallbackQuery,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
     if let Some(ref medicine_id) = q.data {
         if medicine_id == "cancel" {
-            cancel_with_edit(bot, dialogue, q.regular_message().unwrap().to_owned()).await?;
+            cancel_with_edit(bot, dialogue, lang, q.regular_message().unwrap().to_owned()).await?;
         } else {
             log::info!("You chose: {medicine_id}");
 
             bot.answer_callback_query(&q.id).await?;
 
             if let Some(message) = q.regular_message() {
-                let con = cfg.redis_connection;
-                let mut medicine = Medication::get_by_id(medicine_id, con.clone()).unwrap();
-                medicine.set_taken_now(con.clone())?;
-
-                let patient = Patient::get_by_id(&patient_id, con.clone()).unwrap();
-
-                let tz = get_user_timezone(con.clone(), &message.chat.id.to_string());
-
-                bot.edit_message_text(
-                    message.chat.id,
-                    message.id,
-                    format!(
-                        "{} has just taken {} ({}). Next dosage {}. All the best for them.",
-                        patient.name,
-                        medicine.medicine,
-                        medicine.dosage,
-                        medicine.print_can_take_next(&tz)
-                    ),
+                let reply = register_intake(
+                    &bot,
+                    cfg.redis_connection,
+                    medicine_id,
+                    Utc::now().timestamp(),
+                    &q.from.id.to_string(),
+                    &message.chat.id.to_string(),
+                    lang,
                 )
                 .await?;
 
-                let patient = Patient::get_by_id(&patient_id, con.clone()).unwrap();
-                for telegram_user in patient.get_all_shared_users() {
-                    if telegram_user == q.from.id.to_string() {
-                        continue;
-                    }
-
-                    match bot
-                        .send_message(
-                            telegram_user.clone(),
-                            format!(
-                                "{} just taken {} ({}). Next dosage {}. FYI!",
-                                patient.name,
-                                medicine.medicine,
-                                medicine.dosage,
-                                medicine.print_can_take_next(&tz)
-                            ),
-                        )
-                        .await
-                    {
-                        Err(e) => {
-                            log::warn!(
-                                "Failed to notify shared user of intake: telegram user id {}. Error {}",
-                                &telegram_user, e
-                            )
-                        }
-                        _ => {}
-                    }
-                }
+                bot.edit_message_text(message.chat.id, message.id, reply)
+                    .await?;
 
                 dialogue.exit().await?;
             }
@@ -143,21 +127,247 @@ pub async fn take_medicine_command(
     cfg: ConfigParameters,
     bot: Bot,
     dialogue: MyDialogue,
+    lang: Language,
     msg: Message,
 ) -> HandlerResult {
     let con = cfg.redis_connection;
 
-    let keyboard = Patient::generate_patient_keyboard(con.clone(), msg.chat.id.to_string(), false);
+    let keyboard =
+        Patient::generate_patient_keyboard(con.clone(), msg.chat.id.to_string(), false, lang);
 
-    bot.send_message(
-        msg.chat.id,
-        "💉*Time to take some meds\\!* 🤒 \n\n Please start by selecting the patient\\:",
-    )
-    .reply_markup(InlineKeyboardMarkup::new(keyboard))
-    .parse_mode(ParseMode::MarkdownV2)
-    .await?;
+    bot.send_message(msg.chat.id, lang.get(Text::StartTakeMedicine))
+        .reply_markup(InlineKeyboardMarkup::new(keyboard))
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
 
     dialogue.update(State::TakeMedicine).await?;
 
     Ok(())
 }
+
+/// Registers an intake, warns about low stock and lets everyone else with access to the patient
+/// and the linked groups know. Returns the confirmation for the chat it was registered from,
+/// the user's own or a group.
+pub async fn register_intake(
+    bot: &Bot,
+    con: Arc<Mutex<Connection>>,
+    medication_id: &str,
+    taken_at: i64,
+    user_id: &str,
+    chat_id: &str,
+    lang: Language,
+) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let mut medicine = Medication::get_by_id(medication_id, con.clone())?;
+    medicine.set_taken_at(taken_at, con.clone())?;
+
+    let patient = Patient::get_by_id(&medicine.patient_id, con.clone())?;
+
+    let tz = get_user_timezone(con.clone(), chat_id);
+    let patient_tz = patient.get_timezone(con.clone());
+
+    if medicine.should_alert_low_stock() {
+        medicine.save(con.clone())?;
+        notify_low_stock(
+            bot,
+            con.clone(),
+            &medicine,
+            patient.get_notified_chats(),
+            &tz,
+        )
+        .await;
+    }
+
+    let just_now = Utc::now().timestamp() - taken_at < 60;
+
+    // everyone gets the time of the intake where the patient is, and their own
+    let print = |lang: Language, tz: &str, notification: bool| {
+        let time = print_patient_time(
+            DateTime::from_timestamp(taken_at, 0).unwrap(),
+            "%H:%M",
+            &patient_tz,
+            &tz.parse().unwrap_or(Tz::UTC),
+            lang,
+        );
+
+        let text = match (just_now, notification) {
+            (true, false) => Text::JustTaken,
+            (true, true) => Text::JustTakenNotification,
+            (false, false) => Text::TakenAt,
+            (false, true) => Text::TakenAtNotification,
+        };
+
+        lang.fill(
+            text,
+            &[
+                ("patient", &patient.name),
+                ("medicine", &medicine.medicine),
+                ("dosage", &medicine.dosage),
+                ("time", &time),
+                ("next", &medicine.print_can_take_next(tz, lang)),
+            ],
+        )
+    };
+
+    for telegram_user in patient.get_notified_chats() {
+        if telegram_user == user_id || telegram_user == chat_id {
+            continue;
+        }
+
+        if let Err(e) = bot
+            .send_message(
+                telegram_user.clone(),
+                print(
+                    get_user_language(con.clone(), &telegram_user),
+                    &get_user_timezone(con.clone(), &telegram_user),
+                    true,
+                ),
+            )
+            .await
+        {
+            log::warn!(
+                "Failed to notify shared user of intake: telegram user id {}. Error {}",
+                &telegram_user,
+                e
+            )
+        }
+    }
+
+    Ok(print(lang, &tz, false))
+}
+
+fn get_intake_candidates(con: Arc<Mutex<Connection>>, user_id: &str) -> Vec<IntakeCandidate> {
+    Patient::get_my_patients(user_id, con.clone())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|patient| patient.can_edit(user_id))
+        .flat_map(|patient| {
+            Medication::get_all_by_patient_id(&patient.id, con.clone())
+                .into_iter()
+                .map(move |medication| IntakeCandidate {
+                    patient_id: patient.id.clone(),
+                    patient_name: patient.name.clone(),
+                    medication_id: medication.id,
+                    medicine: medication.medicine,
+                })
+        })
+        .collect()
+}
+
+/// Registers intakes written as text (ie, "gave xavi nurofen 20 min ago"), asking first when it
+/// isn't clear which medication it was. Returns false when the text isn't about an intake.
+pub async fn free_text_intake(
+    cfg: ConfigParameters,
+    bot: Bot,
+    lang: Language,
+    msg: Message,
+) -> Result<bool, Box<dyn Error + Send + Sync>> {
+    let Some(text) = msg.text() else {
+        return Ok(false);
+    };
+
+    let con = cfg.redis_connection;
+    let user_id = msg.chat.id.to_string();
+    let candidates = get_intake_candidates(con.clone(), &user_id);
+
+    let Some(intake) = IntakeText::parse(text, &candidates) else {
+        return Ok(false);
+    };
+
+    let taken_at = Utc::now().timestamp() - intake.minutes_ago * 60;
+
+    if intake.is_unambiguous() {
+        let reply = register_intake(
+            &bot,
+            con,
+            &intake.matches[0].medication_id,
+            taken_at,
+            &user_id,
+            &user_id,
+            lang,
+        )
+        .await?;
+
+        bot.send_message(msg.chat.id, reply).await?;
+    } else {
+        let mut keyboard: Vec<Vec<InlineKeyboardButton>> = intake
+            .matches
+            .iter()
+            .map(|candidate| {
+                vec![InlineKeyboardButton::callback(
+                    lang.fill(
+                        Text::IntakeCandidate,
+                        &[
+                            ("medicine", &candidate.medicine),
+                            ("patient", &candidate.patient_name),
+                        ],
+                    ),
+                    format!("intake:{}:{}", candidate.medication_id, taken_at),
+                )]
+            })
+            .collect();
+
+        keyboard.push(vec![InlineKeyboardButton::callback(
+            lang.get(Text::Cancel).to_string(),
+            "intake:cancel".to_string(),
+        )]);
+
+        bot.send_message(msg.chat.id, lang.get(Text::WhichIntake))
+            .reply_markup(InlineKeyboardMarkup::new(keyboard))
+            .await?;
+    }
+
+    Ok(true)
+}
+
+pub fn is_intake_confirmation(q: CallbackQuery) -> bool {
+    q.data.is_some_and(|data| data.starts_with("intake:"))
+}
+
+// works regardless of the dialogue state, like the message it answers
+pub async fn intake_confirmation_callback_handler(
+    cfg: ConfigParameters,
+    bot: Bot,
+    lang: Language,
+    q: CallbackQuery,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let message = q.regular_message().unwrap();
+    bot.answer_callback_query(&q.id).await?;
+
+    let data = q.data.clone().unwrap_or_default();
+    let confirmed = data
+        .strip_prefix("intake:")
+        .and_then(|data| data.split_once(':'))
+        .and_then(|(medication_id, ts)| Some((medication_id, ts.parse::<i64>().ok()?)));
+
+    let con = cfg.redis_connection;
+    let user_id = q.from.id.to_string();
+
+    // access could have changed since the keyboard was sent
+    let patient = confirmed
+        .and_then(|(medication_id, _)| Medication::get_by_id(medication_id, con.clone()).ok())
+        .and_then(|medication| Patient::get_by_id(&medication.patient_id, con.clone()).ok());
+
+    let reply = match (confirmed, patient) {
+        (Some(_), Some(patient)) if !patient.can_edit(&user_id) => {
+            lang.fill(Text::ViewOnly, &[("patient", &patient.name)])
+        }
+        (Some((medication_id, taken_at)), Some(_)) => {
+            register_intake(
+                &bot,
+                con,
+                medication_id,
+                taken_at,
+                &user_id,
+                &message.chat.id.to_string(),
+                lang,
+            )
+            .await?
+        }
+        _ => lang.get(Text::NothingRegistered).to_string(),
+    };
+
+    bot.edit_message_text(message.chat.id, message.id, reply)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use std::error::Error;
+
+use chrono_tz::Tz;
+
+use crate::commands::cancel;
+use crate::i18n::{Language, Text};
+use crate::timezone::{
+    find_timezone, get_region_timezones, get_regions, parse_timezone, print_city,
+};
+use crate::user::set_user_timezone;
+use crate::{ConfigParameters, HandlerResult, MyDialogue};
+use teloxide::prelude::*;
+use teloxide::types::{
+    ButtonRequest, InlineKeyboardButton, InlineKeyboardMarkup, KeyboardButton, KeyboardMarkup,
+    KeyboardRemove, Message,
+};
+use teloxide::Bot;
+
+fn generate_region_keyboard(lang: Language) -> Vec<Vec<InlineKeyboardButton>> {
+    let buttons: Vec<InlineKeyboardButton> = get_regions()
+        .into_iter()
+        .map(|region| InlineKeyboardButton::callback(region, format!("region:{}", region)))
+        .collect();
+
+    let mut keyboard: Vec<Vec<InlineKeyboardButton>> =
+        buttons.chunks(2).map(|row| row.to_vec()).collect();
+
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        lang.get(Text::Cancel).to_string(),
+        "cancel".to_string(),
+    )]);
+
+    keyboard
+}
+
+fn generate_city_keyboard(region: &str, lang: Language) -> Vec<Vec<InlineKeyboardButton>> {
+    let buttons: Vec<InlineKeyboardButton> = get_region_timezones(region)
+        .iter()
+        .map(|timezone| {
+            InlineKeyboardButton::callback(print_city(timezone), format!("tz:{}", timezone.name()))
+        })
+        .collect();
+
+    let mut keyboard: Vec<Vec<InlineKeyboardButton>> =
+        buttons.chunks(3).map(|row| row.to_vec()).collect();
+
+    keyboard.push(vec![
+        InlineKeyboardButton::callback(lang.get(Text::Back).to_string(), "regions".to_string()),
+        InlineKeyboardButton::callback(lang.get(Text::Cancel).to_string(), "cancel".to_string()),
+    ]);
+
+    keyboard
+}
+
+/// Asks for the timezone with a button to share the location, and the list of regions to pick
+/// it from. The answer is handled in the SelectTimezone state.
+pub async fn ask_timezone(bot: &Bot, chat_id: ChatId, lang: Language) -> HandlerResult {
+    let location = KeyboardMarkup::new(vec![vec![KeyboardButton::new(
+        lang.get(Text::ShareLocation),
+    )
+    .request(ButtonRequest::Location)]])
+    .resize_keyboard()
+    .one_time_keyboard();
+
+    bot.send_message(chat_id, lang.get(Text::AskTimezone))
+        .reply_markup(location)
+        .await?;
+
+    bot.send_message(chat_id, lang.get(Text::AskTimezoneRegion))
+        .reply_markup(InlineKeyboardMarkup::new(generate_region_keyboard(lang)))
+        .await?;
+
+    Ok(())
+}
+
+async fn save_timezone(
+    cfg: ConfigParameters,
+    bot: &Bot,
+    dialogue: MyDialogue,
+    chat_id: ChatId,
+    timezone: Tz,
+    lang: Language,
+) -> HandlerResult {
+    set_user_timezone(cfg.redis_connection, &chat_id.to_string(), &timezone)?;
+
+    bot.send_message(
+        chat_id,
+        lang.fill(Text::TimezoneSet, &[("timezone", &timezone.name())]),
+    )
+    .reply_markup(KeyboardRemove::new())
+    .await?;
+
+    dialogue.exit().await?;
+
+    Ok(())
+}
+
+pub async fn select_timezone_callback_handler(
+    cfg: ConfigParameters,
+    bot: Bot,
+    dialogue: MyDialogue,
+    lang: Language,
+    q: CallbackQuery,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let message = q.regular_message().unwrap();
+    bot.answer_callback_query(&q.id).await?;
+
+    let data = q.data.clone().unwrap_or_default();
+
+    if let Some(region) = data.strip_prefix("region:") {
+        bot.edit_message_text(
+            message.chat.id,
+            message.id,
+            lang.fill(Text::AskTimezoneCity, &[("region", &region)]),
+        )
+        .reply_markup(InlineKeyboardMarkup::new(generate_city_keyboard(
+            region, lang,
+        )))
+        .await?;
+    } else if data == "regions" {
+        bot.edit_message_text(
+            message.chat.id,
+            message.id,
+            lang.get(Text::AskTimezoneRegion),
+        )
+        .reply_markup(InlineKeyboardMarkup::new(generate_region_keyboard(lang)))
+        .await?;
+    } else {
+        // the location button lives in a different message, so both get cleaned up
+        bot.delete_message(message.chat.id, message.id).await?;
+
+        match data.strip_prefix("tz:").and_then(parse_timezone) {
+            Some(timezone) => {
+                save_timezone(cfg, &bot, dialogue, message.chat.id, timezone, lang).await?;
+            }
+            None => {
+                cancel(bot, dialogue, lang, message.to_owned()).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn receive_timezone(
+    cfg: ConfigParameters,
+    bot: Bot,
+    dialogue: MyDialogue,
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    let text = msg.text().unwrap_or_default().trim();
+
+    let timezone = match msg.location() {
+        Some(location) => find_timezone(location.latitude, location.longitude),
+        None => parse_timezone(text),
+    };
+
+    match timezone {
+        Some(timezone) => {
+            save_timezone(cfg, &bot, dialogue, msg.chat.id, timezone, lang).await?;
+        }
+        None if text.is_empty() => {
+            bot.send_message(msg.chat.id, lang.get(Text::DidntGetThat))
+                .await?;
+        }
+        None => {
+            bot.send_message(
+                msg.chat.id,
+                lang.fill(Text::NotATimezone, &[("timezone", &text)]),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,348 @@
+// intakes logged this long after the fact are most likely a typo
+pub const MAX_MINUTES_AGO: i64 = 48 * 60;
+
+/// A medication the user can register intakes for.
+#[derive(Debug, PartialEq, Clone)]
+pub struct IntakeCandidate {
+    pub patient_id: String,
+    pub patient_name: String,
+    pub medication_id: String,
+    pub medicine: String,
+}
+
+// past tense only, "can xavi take calpol" isn't an intake
+#[rustfmt::skip]
+const INTAKE_VERBS: &[&str] = &[
+    "gave", "given", "took", "taken", "had", // en
+    "di", "dio", "dimos", "dado", "tomó", "tomé", "tomado", // es, pt too
+    "donat", "donada", "pres", "presa", // ca
+    "dei", "deu", "demos", "tomou", "tomei", // pt
+];
+
+#[derive(Debug, PartialEq)]
+pub struct IntakeText<'a> {
+    pub matches: Vec<&'a IntakeCandidate>,
+    // only when both the patient and the medicine were named exactly
+    pub exact: bool,
+    // it says the medicine was given, and isn't a question about it
+    pub stated: bool,
+    // it doesn't say when, or what it says was understood
+    pub clear_time: bool,
+    pub minutes_ago: i64,
+}
+
+impl IntakeText<'_> {
+    // gave xavi nurofen
+    // xavi took 5ml calpol 20 min ago
+    // calpol half an hour ago
+    // le di nurofen a xavi hace 20 min
+    pub fn parse<'a>(text: &str, candidates: &'a [IntakeCandidate]) -> Option<IntakeText<'a>> {
+        let words = tokenize(text);
+        let minutes_ago = parse_minutes_ago(&words)?;
+
+        let single_patient = candidates
+            .iter()
+            .all(|c| c.patient_id == candidates[0].patient_id);
+
+        let scored: Vec<(u32, &IntakeCandidate)> = candidates
+            .iter()
+            .filter_map(|candidate| {
+                let medicine = score_name(&candidate.medicine, &words);
+                let patient = match single_patient {
+                    true => EXACT,
+                    false => score_name(&candidate.patient_name, &words),
+                };
+
+                // without the patient's name it could be any of them, to be confirmed
+                match medicine > 0 {
+                    true => Some((medicine + patient, candidate)),
+                    false => None,
+                }
+            })
+            .collect();
+
+        let best = scored.iter().map(|(score, _)| *score).max()?;
+        let matches: Vec<&IntakeCandidate> = scored
+            .into_iter()
+            .filter(|(score, _)| *score == best)
+            .map(|(_, candidate)| candidate)
+            .collect();
+
+        Some(IntakeText {
+            exact: best == 2 * EXACT,
+            stated: !text.contains('?')
+                && words
+                    .iter()
+                    .any(|word| INTAKE_VERBS.contains(&word.as_str())),
+            matches,
+            clear_time: minutes_ago.is_some(),
+            minutes_ago: minutes_ago.unwrap_or(0),
+        })
+    }
+
+    /// Whether the intake can be registered without asking first.
+    pub fn is_unambiguous(&self) -> bool {
+        self.exact && self.stated && self.clear_time && self.matches.len() == 1
+    }
+}
+
+const EXACT: u32 = 2;
+const FUZZY: u32 = 1;
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_string())
+        .collect()
+}
+
+/// How well any of the words of `name` matches any of the words in the text.
+fn score_name(name: &str, words: &[String]) -> u32 {
+    tokenize(name)
+        .iter()
+        .filter(|part| part.chars().count() >= 3)
+        .flat_map(|part| words.iter().map(move |word| score_word(part, word)))
+        .max()
+        .unwrap_or(0)
+}
+
+fn score_word(name: &str, word: &str) -> u32 {
+    if name == word {
+        return EXACT;
+    }
+
+    // one typo every four letters or so
+    let allowed = name.chars().count() / 4;
+
+    match allowed > 0 && levenshtein(name, word) <= allowed {
+        true => FUZZY,
+        false => 0,
+    }
+}
+
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+const MINUTE_UNITS: &[&str] = &[
+    "m", "min", "mins", "minute", "minutes", "minuto", "minutos", "minut", "minuts",
+];
+const HOUR_UNITS: &[&str] = &["h", "hr", "hrs", "hour", "hours", "hora", "horas", "hores"];
+const DAY_UNITS: &[&str] = &["d", "day", "days", "día", "días", "dia", "dias", "dies"];
+
+// 20 min ago, 2 horas atrás
+const AGO_AFTER: &[&str] = &["ago", "atrás"];
+// hace 20 min, fa mitja hora, há 2 horas
+const AGO_BEFORE: &[&str] = &["hace", "fa", "há"];
+
+// 20 min, 20m, an hour, half an hour, media hora
+fn parse_duration(words: &[&str]) -> Option<f64> {
+    let (amount, unit) = match words {
+        ["half", "a" | "an", unit] => (0.5, *unit),
+        [amount, unit] => {
+            let amount = match *amount {
+                "a" | "an" | "un" | "una" | "um" | "uma" => 1.0,
+                "half" | "media" | "mitja" | "meia" => 0.5,
+                amount => amount.parse().ok()?,
+            };
+            (amount, *unit)
+        }
+        [combined] => {
+            let split = combined.find(|c: char| !c.is_ascii_digit())?;
+            let (amount, unit) = combined.split_at(split);
+            (amount.parse().ok()?, unit)
+        }
+        _ => return None,
+    };
+
+    if MINUTE_UNITS.contains(&unit) {
+        Some(amount)
+    } else if HOUR_UNITS.contains(&unit) {
+        Some(amount * 60.0)
+    } else if DAY_UNITS.contains(&unit) {
+        Some(amount * 24.0 * 60.0)
+    } else {
+        None
+    }
+}
+
+/// The words that could hold the duration of the "ago" at `i`, longest first.
+fn get_duration_spans<'a>(words: &'a [&'a str], i: usize) -> Vec<&'a [&'a str]> {
+    if AGO_AFTER.contains(&words[i]) {
+        (1..=3)
+            .rev()
+            .filter_map(|n| words.get(i.checked_sub(n)?..i))
+            .collect()
+    } else if AGO_BEFORE.contains(&words[i]) {
+        (1..=3)
+            .rev()
+            .filter_map(|n| words.get(i + 1..i + 1 + n))
+            .collect()
+    } else {
+        vec![]
+    }
+}
+
+// Some(None) when it says when, but it wasn't understood, and None when it's too long ago to be
+// right
+fn parse_minutes_ago(words: &[String]) -> Option<Option<i64>> {
+    let words: Vec<&str> = words.iter().map(|word| word.as_str()).collect();
+
+    let spans: Vec<Vec<&[&str]>> = (0..words.len())
+        .map(|i| get_duration_spans(&words, i))
+        .filter(|spans| !spans.is_empty())
+        .collect();
+
+    let minutes = match spans.is_empty() {
+        // "20 min" alone could be "in 20 min" or "20 min before dinner"
+        true if words
+            .windows(1)
+            .chain(words.windows(2))
+            .any(|span| parse_duration(span).is_some()) =>
+        {
+            return Some(None);
+        }
+        true => 0,
+        false => match spans.iter().flatten().find_map(|span| parse_duration(span)) {
+            Some(minutes) => minutes as i64,
+            None => return Some(None),
+        },
+    };
+
+    match (0..=MAX_MINUTES_AGO).contains(&minutes) {
+        true => Some(Some(minutes)),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(patient: &str, medicine: &str) -> IntakeCandidate {
+        IntakeCandidate {
+            patient_id: patient.to_lowercase(),
+            patient_name: patient.to_string(),
+            medication_id: format!("{}-{}", patient, medicine).to_lowercase(),
+            medicine: medicine.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_intake_text() {
+        let candidates = [
+            candidate("Xavi", "Nurofen"),
+            candidate("Xavi", "Calpol"),
+            candidate("Anna", "Calpol"),
+        ];
+
+        let parsed = IntakeText::parse("gave xavi nurofen", &candidates).unwrap();
+        assert!(parsed.is_unambiguous());
+        assert_eq!(parsed.matches, vec![&candidates[0]]);
+        assert_eq!(parsed.minutes_ago, 0);
+
+        let parsed = IntakeText::parse("Xavi took 5ml calpol 20 min ago", &candidates).unwrap();
+        assert!(parsed.is_unambiguous());
+        assert_eq!(parsed.matches, vec![&candidates[1]]);
+        assert_eq!(parsed.minutes_ago, 20);
+
+        // no patient, so it could be either
+        let parsed = IntakeText::parse("calpol half an hour ago", &candidates).unwrap();
+        assert!(!parsed.is_unambiguous());
+        assert_eq!(parsed.matches, vec![&candidates[1], &candidates[2]]);
+        assert_eq!(parsed.minutes_ago, 30);
+
+        let parsed = IntakeText::parse("gave xabi calpl 2h ago", &candidates).unwrap();
+        assert!(!parsed.is_unambiguous());
+        assert_eq!(parsed.matches, vec![&candidates[1]]);
+        assert_eq!(parsed.minutes_ago, 120);
+
+        assert_eq!(IntakeText::parse("hello there", &candidates), None);
+    }
+
+    #[test]
+    fn test_intake_needs_a_verb() {
+        let candidates = [candidate("Xavi", "Calpol"), candidate("Anna", "Calpol")];
+
+        // questions and mentions are only registered once confirmed
+        for text in [
+            "how much calpol can xavi have?",
+            "can xavi take calpol",
+            "xavi calpol",
+            "has xavi had calpol?",
+        ] {
+            let parsed = IntakeText::parse(text, &candidates).unwrap();
+            assert!(!parsed.is_unambiguous(), "{}", text);
+            assert_eq!(parsed.matches, vec![&candidates[0]]);
+        }
+
+        for text in [
+            "xavi had calpol",
+            "li he donat calpol a la xavi",
+            "tomó calpol xavi",
+        ] {
+            let parsed = IntakeText::parse(text, &candidates).unwrap();
+            assert!(parsed.is_unambiguous(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_single_patient_needs_no_name() {
+        let candidates = [candidate("Xavi", "Nurofen"), candidate("Xavi", "Calpol")];
+
+        let parsed = IntakeText::parse("took calpol an hour ago", &candidates).unwrap();
+        assert!(parsed.is_unambiguous());
+        assert_eq!(parsed.matches, vec![&candidates[1]]);
+        assert_eq!(parsed.minutes_ago, 60);
+
+        assert_eq!(IntakeText::parse("calpol 3 days ago", &candidates), None);
+    }
+
+    #[test]
+    fn test_localized_minutes_ago() {
+        let candidates = [candidate("Xavi", "Nurofen"), candidate("Anna", "Calpol")];
+
+        for (text, minutes) in [
+            ("le di nurofen a xavi hace 20 min", 20),
+            ("fa mitja hora li he donat nurofen a la xavi", 30),
+            ("xavi tomou nurofen há 2 horas", 120),
+            ("xavi tomou nurofen 2 horas atrás", 120),
+            ("hace una hora le di nurofen a xavi", 60),
+        ] {
+            let parsed = IntakeText::parse(text, &candidates).unwrap();
+            assert!(parsed.is_unambiguous(), "{}", text);
+            assert_eq!(parsed.minutes_ago, minutes, "{}", text);
+        }
+
+        assert_eq!(
+            IntakeText::parse("le di nurofen a xavi hace 3 días", &candidates),
+            None
+        );
+
+        // when it says when but it wasn't understood, it's better to ask than register it now
+        for text in [
+            "gave xavi nurofen 20 min before dinner",
+            "gave xavi nurofen 2 weeks ago",
+            "le di nurofen a xavi hace un rato",
+        ] {
+            let parsed = IntakeText::parse(text, &candidates).unwrap();
+            assert!(!parsed.is_unambiguous(), "{}", text);
+            assert_eq!(parsed.matches, vec![&candidates[0]]);
+        }
+    }
+}
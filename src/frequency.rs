@@ -1,105 +1,609 @@
 use std::fmt::Display;
 
+use chrono::TimeDelta;
+
 use serde::{Deserialize, Serialize};
 
+use crate::free_text::levenshtein;
+use crate::i18n::{Language, Text};
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(from = "StoredFrequency")]
 pub struct Frequency {
-    hours: i64,
+    minutes: i64,
     start_time: Option<i64>,
+    // upper bound of ranges like "every 4-6 hours", `minutes` being the lower one
+    max_minutes: Option<i64>,
+    // for plans like "3 times a day", spread over the patient's waking hours
+    times_a_day: Option<i64>,
 }
 
-impl Frequency {
-    pub fn new(hours: i64) -> Frequency {
+// plans saved before intervals were in minutes only have hours
+#[derive(Deserialize)]
+struct StoredFrequency {
+    minutes: Option<i64>,
+    hours: Option<i64>,
+    start_time: Option<i64>,
+    #[serde(default)]
+    max_minutes: Option<i64>,
+    #[serde(default)]
+    max_hours: Option<i64>,
+    #[serde(default)]
+    times_a_day: Option<i64>,
+}
+
+impl From<StoredFrequency> for Frequency {
+    fn from(stored: StoredFrequency) -> Self {
         Frequency {
-            hours,
-            start_time: None,
+            minutes: stored
+                .minutes
+                .or(stored.hours.map(|hours| hours * 60))
+                .unwrap_or_default(),
+            start_time: stored.start_time,
+            max_minutes: stored
+                .max_minutes
+                .or(stored.max_hours.map(|hours| hours * 60)),
+            times_a_day: stored.times_a_day,
         }
     }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FrequencyError {
+    Empty,
+    MissingNumber,
+    Zero,
+    TooLong,
+    InvalidRange(f64, f64),
+    UnknownUnit(String),
+    UnknownWord(String),
+    TrailingWords(String),
+}
+
+// the words of every language are understood, whatever the language of the user
+const UNITS: [(&str, i64); 31] = [
+    ("m", 1),
+    ("min", 1),
+    ("mins", 1),
+    ("minute", 1),
+    ("minutes", 1),
+    ("minuto", 1),
+    ("minutos", 1),
+    ("minut", 1),
+    ("minuts", 1),
+    ("h", 60),
+    ("hr", 60),
+    ("hrs", 60),
+    ("hour", 60),
+    ("hours", 60),
+    ("hora", 60),
+    ("horas", 60),
+    ("hores", 60),
+    ("d", 24 * 60),
+    ("day", 24 * 60),
+    ("days", 24 * 60),
+    ("dia", 24 * 60),
+    ("día", 24 * 60),
+    ("dias", 24 * 60),
+    ("días", 24 * 60),
+    ("dies", 24 * 60),
+    ("week", 7 * 24 * 60),
+    ("weeks", 7 * 24 * 60),
+    ("semana", 7 * 24 * 60),
+    ("semanas", 7 * 24 * 60),
+    ("setmana", 7 * 24 * 60),
+    ("setmanes", 7 * 24 * 60),
+];
+
+// medical abbreviations, from the latin "quaque die", "bis in die", "ter in die",...
+const ABBREVIATIONS: [(&str, f64); 9] = [
+    ("qd", 1.0),
+    ("od", 1.0),
+    ("qhs", 1.0),
+    ("bid", 2.0),
+    ("bd", 2.0),
+    ("tid", 3.0),
+    ("tds", 3.0),
+    ("qid", 4.0),
+    ("qds", 4.0),
+];
+
+const MULTIPLIERS: [(&str, f64); 36] = [
+    ("once", 1.0),
+    ("twice", 2.0),
+    ("thrice", 3.0),
+    ("one", 1.0),
+    ("two", 2.0),
+    ("three", 3.0),
+    ("four", 4.0),
+    ("five", 5.0),
+    ("six", 6.0),
+    ("eight", 8.0),
+    ("ten", 10.0),
+    ("twelve", 12.0),
+    ("un", 1.0),
+    ("una", 1.0),
+    ("uma", 1.0),
+    ("dos", 2.0),
+    ("dues", 2.0),
+    ("dois", 2.0),
+    ("duas", 2.0),
+    ("tres", 3.0),
+    ("três", 3.0),
+    ("cuatro", 4.0),
+    ("quatre", 4.0),
+    ("quatro", 4.0),
+    ("cinco", 5.0),
+    ("cinc", 5.0),
+    ("seis", 6.0),
+    ("sis", 6.0),
+    ("ocho", 8.0),
+    ("vuit", 8.0),
+    ("oito", 8.0),
+    ("diez", 10.0),
+    ("deu", 10.0),
+    ("dez", 10.0),
+    ("doce", 12.0),
+    ("dotze", 12.0),
+];
+
+// "every day" and "a day" but also "daily" and "hourly"
+const ADVERBS: [(&str, i64); 9] = [
+    ("hourly", 60),
+    ("daily", 24 * 60),
+    ("weekly", 7 * 24 * 60),
+    ("diario", 24 * 60),
+    ("diariamente", 24 * 60),
+    ("diàriament", 24 * 60),
+    ("semanal", 7 * 24 * 60),
+    ("semanalmente", 7 * 24 * 60),
+    ("setmanalment", 7 * 24 * 60),
+];
+
+const EVERY: [&str; 3] = ["every", "each", "cada"];
+
+const TIMES: [&str; 10] = [
+    "times", "time", "x", "veces", "vez", "vegades", "vegada", "cops", "cop", "vezes",
+];
+
+const ARTICLES: [&str; 9] = ["a", "an", "per", "each", "every", "al", "ao", "por", "cada"];
+
+// longer plans aren't something to be reminded of, and would overflow the schedules
+const MAX_MINUTES: f64 = 365.0 * 24.0 * 60.0;
+
+// "4 to 6 hours", "4 a 6 horas"
+const RANGE: [&str; 2] = ["to", "a"];
+
+impl FrequencyError {
+    pub fn print(&self, lang: Language) -> String {
+        let examples = lang.get(Text::FrequencyTryExamples);
+        let example = lang.get(Text::FrequencyTryExample);
 
-    pub fn get_hours(&self) -> i64 {
-        return self.hours;
-    }
-
-    // every 6 hours
-    // 4 times a day
-    pub fn parse(frequency: &str) -> Option<Self> {
-        let lower = frequency.to_lowercase();
-        let mut split = lower.split(" ");
-        match split.next() {
-            Some(token) if token == "every" => {
-                let first_token = split.next();
-                let second_token = split.next();
-
-                match first_token {
-                    Some(token) => {
-                        if let Ok(number) = token.parse::<i64>() {
-                            // every 5
-                            match second_token {
-                                Some(token)
-                                    if token == "hour" || token == "hours" || token == "h" =>
-                                {
-                                    Some(Frequency {
-                                        hours: number,
-                                        start_time: None,
-                                    })
-                                }
-                                Some(token) if token == "day" || token == "days" => {
-                                    Some(Frequency {
-                                        hours: number * 24,
-                                        start_time: None,
-                                    })
-                                }
-                                Some(_) => None,
-                                None => None,
-                            }
-                        } else if token == "day" {
-                            Some(Frequency {
-                                hours: 24,
-                                start_time: None,
-                            })
-                        } else if token == "hour" {
-                            Some(Frequency {
-                                hours: 1,
-                                start_time: None,
-                            })
-                        } else if token.ends_with("h") {
-                            let len = token.len();
-                            match &token[..len - 1].to_string().parse::<i64>() {
-                                Err(_e) => None,
-                                Ok(value) => Some(Frequency {
-                                    hours: *value,
-                                    start_time: None,
-                                }),
-                            }
-                        } else {
-                            None
-                        }
-                    }
-                    None => None,
+        match self {
+            FrequencyError::Empty => lang.fill(Text::FrequencyEmpty, &[("examples", &examples)]),
+            FrequencyError::MissingNumber => {
+                lang.fill(Text::FrequencyMissingNumber, &[("example", &example)])
+            }
+            FrequencyError::Zero => lang.fill(Text::FrequencyZero, &[("example", &example)]),
+            FrequencyError::TooLong => lang.fill(Text::FrequencyTooLong, &[("example", &example)]),
+            FrequencyError::InvalidRange(min, max) => {
+                let range = lang.fill(Text::EveryHoursRange, &[("min", max), ("max", min)]);
+                lang.fill(Text::FrequencyInvalidRange, &[("example", &range)])
+            }
+            FrequencyError::UnknownUnit(unit) => match suggest(unit, UNITS.map(|(u, _)| u)) {
+                Some(suggestion) => lang.fill(
+                    Text::FrequencyUnknownUnitSuggestion,
+                    &[("unit", unit), ("suggestion", &suggestion)],
+                ),
+                None => lang.fill(Text::FrequencyUnknownUnit, &[("unit", unit)]),
+            },
+            FrequencyError::UnknownWord(word) => {
+                let known = ABBREVIATIONS
+                    .map(|(a, _)| a)
+                    .into_iter()
+                    .chain(MULTIPLIERS.map(|(m, _)| m))
+                    .chain(ADVERBS.map(|(a, _)| a))
+                    .chain(EVERY)
+                    .chain(TIMES);
+
+                match suggest(word, known) {
+                    Some(suggestion) => lang.fill(
+                        Text::FrequencyUnknownWordSuggestion,
+                        &[("word", word), ("suggestion", &suggestion)],
+                    ),
+                    None => lang.fill(
+                        Text::FrequencyUnknownWord,
+                        &[("word", word), ("examples", &examples)],
+                    ),
                 }
             }
-            Some(token) if token.parse::<usize>().is_ok() => {
-                let number = token.parse::<usize>().unwrap();
-                let rest_tokens: Vec<&str> = split.collect();
-
-                if rest_tokens.first() == Some(&"times") && rest_tokens.last() == Some(&"day") {
-                    return Some(Frequency {
-                        hours: (24f64 / number as f64).floor() as i64,
-                        start_time: None,
-                    });
+            FrequencyError::TrailingWords(words) => lang.fill(
+                Text::FrequencyTrailingWords,
+                &[("words", words), ("examples", &examples)],
+            ),
+        }
+    }
+}
+
+impl Display for FrequencyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "{}", self.print(Language::English))
+    }
+}
+
+/// The closest known word, when it's close enough to be a typo.
+fn suggest<'a>(word: &str, known: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    known
+        .into_iter()
+        .map(|k| (levenshtein(word, k), k))
+        .filter(|(distance, k)| *distance > 0 && *distance <= (k.len() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, k)| k)
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Number(f64),
+    Word(String),
+    Dash,
+    Slash,
+}
+
+fn tokenize(frequency: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut chars = frequency.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_ascii_digit() || c == '.' || c == ',' {
+            let mut number = String::new();
+            while let Some(&d) = chars
+                .peek()
+                .filter(|d| d.is_ascii_digit() || **d == '.' || **d == ',')
+            {
+                number.push(if d == ',' { '.' } else { d });
+                chars.next();
+            }
+            match number.parse::<f64>() {
+                Ok(n) => tokens.push(Token::Number(n)),
+                Err(_) => tokens.push(Token::Word(number)),
+            }
+        } else if c.is_alphabetic() {
+            let mut word = String::new();
+            while let Some(&l) = chars.peek().filter(|l| l.is_alphabetic()) {
+                word.extend(l.to_lowercase());
+                chars.next();
+            }
+            tokens.push(Token::Word(word));
+        } else {
+            match c {
+                '-' => tokens.push(Token::Dash),
+                '/' => tokens.push(Token::Slash),
+                _ => {}
+            }
+            chars.next();
+        }
+    }
+
+    tokens
+}
+
+/// Every how many minutes, with the upper bound of ranges and how many times a day.
+#[derive(Debug, PartialEq)]
+struct Interval {
+    minutes: f64,
+    max_minutes: Option<f64>,
+    times_a_day: Option<f64>,
+}
+
+// frequency    := abbreviation | "every" interval | count "times"? per period | adverb
+// abbreviation := "bid" | "tid" | ... | "q" amount unit | "qod"
+// interval     := "other"? amount? unit
+// amount       := number | number ("-" | "to") number
+// count        := number "x"? | "once" | "twice" | ...
+// per          := "a" | "an" | "per" | "each" | "every" | "/" | nothing before an adverb
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek_word(&self) -> Option<&str> {
+        match self.peek() {
+            Some(Token::Word(word)) => Some(word),
+            _ => None,
+        }
+    }
+
+    fn accept_word(&mut self, words: &[&str]) -> bool {
+        match self.peek_word() {
+            Some(word) if words.contains(&word) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn parse(&mut self) -> Result<Interval, FrequencyError> {
+        // "a cada 6 horas"
+        if self.peek_word() == Some("a")
+            && matches!(self.tokens.get(self.position + 1), Some(Token::Word(word)) if word == "cada")
+        {
+            self.next();
+        }
+
+        let interval = match self.peek().cloned() {
+            None => return Err(FrequencyError::Empty),
+            Some(Token::Word(word)) if EVERY.contains(&word.as_str()) => {
+                self.next();
+                self.parse_interval()?
+            }
+            Some(Token::Word(word)) if word == "q" => {
+                self.next();
+                self.parse_interval()?
+            }
+            Some(Token::Word(word)) if word == "qod" => {
+                self.next();
+                self.per_minutes(1.0, 2 * 24 * 60)
+            }
+            Some(Token::Word(word)) => {
+                if let Some((_, count)) = ABBREVIATIONS.iter().find(|(a, _)| *a == word) {
+                    self.next();
+                    self.per_minutes(*count, 24 * 60)
+                } else if let Some((_, minutes)) = ADVERBS.iter().find(|(a, _)| *a == word) {
+                    self.next();
+                    self.per_minutes(1.0, *minutes)
+                } else if let Some((_, count)) = MULTIPLIERS.iter().find(|(m, _)| *m == word) {
+                    self.next();
+                    self.parse_times(*count)?
+                } else {
+                    return Err(FrequencyError::UnknownWord(word));
                 }
+            }
+            Some(Token::Number(count)) => {
+                self.next();
+                self.parse_times(count)?
+            }
+            Some(Token::Dash) | Some(Token::Slash) => return Err(FrequencyError::MissingNumber),
+        };
+
+        if self.position < self.tokens.len() {
+            let rest = self.tokens[self.position..]
+                .iter()
+                .map(|token| match token {
+                    Token::Number(n) => n.to_string(),
+                    Token::Word(word) => word.clone(),
+                    Token::Dash => "-".to_string(),
+                    Token::Slash => "/".to_string(),
+                })
+                .collect::<Vec<String>>()
+                .join(" ");
+
+            return Err(FrequencyError::TrailingWords(rest));
+        }
+
+        // anything under a minute is too small to schedule
+        if interval.minutes < 0.5 {
+            return Err(FrequencyError::Zero);
+        }
+
+        if interval.max_minutes.unwrap_or(interval.minutes) > MAX_MINUTES {
+            return Err(FrequencyError::TooLong);
+        }
+
+        Ok(interval)
+    }
+
+    fn per_minutes(&self, count: f64, period: i64) -> Interval {
+        Interval {
+            minutes: period as f64 / count,
+            max_minutes: None,
+            times_a_day: match period == 24 * 60 {
+                true => Some(count),
+                false => None,
+            },
+        }
+    }
+
+    // "other day", "6 hours", "4-6 hours", "4 to 6 h", "hour", "two days"
+    fn parse_interval(&mut self) -> Result<Interval, FrequencyError> {
+        let other = self.accept_word(&["other"]);
 
-                None
+        let number_word = self
+            .peek_word()
+            .and_then(|word| MULTIPLIERS.iter().find(|(m, _)| *m == word))
+            .map(|(_, count)| *count);
+
+        let (amount, max_amount) = match (self.peek(), number_word) {
+            (Some(Token::Number(_)), _) => self.parse_amount()?,
+            (_, Some(count)) => {
+                self.next();
+                (count, None)
             }
-            Some(_) => None,
-            None => None,
+            _ => (if other { 2.0 } else { 1.0 }, None),
+        };
+
+        let unit = self.parse_unit()?;
+
+        Ok(Interval {
+            minutes: amount * unit as f64,
+            max_minutes: max_amount.map(|max| max * unit as f64),
+            times_a_day: None,
+        })
+    }
+
+    fn parse_amount(&mut self) -> Result<(f64, Option<f64>), FrequencyError> {
+        let Some(Token::Number(min)) = self.next() else {
+            return Err(FrequencyError::MissingNumber);
+        };
+
+        let is_range = match self.peek() {
+            Some(Token::Dash) => true,
+            Some(Token::Word(word)) => RANGE.contains(&word.as_str()),
+            _ => false,
+        };
+
+        if !is_range {
+            return Ok((min, None));
+        }
+
+        self.next();
+
+        match self.next() {
+            Some(Token::Number(max)) if max > min => Ok((min, Some(max))),
+            Some(Token::Number(max)) => Err(FrequencyError::InvalidRange(min, max)),
+            _ => Err(FrequencyError::MissingNumber),
+        }
+    }
+
+    fn parse_unit(&mut self) -> Result<i64, FrequencyError> {
+        match self.next() {
+            Some(Token::Word(word)) => UNITS
+                .iter()
+                .find(|(unit, _)| *unit == word)
+                .map(|(_, minutes)| *minutes)
+                .ok_or(FrequencyError::UnknownUnit(word)),
+            Some(_) => Err(FrequencyError::MissingNumber),
+            None => Err(FrequencyError::UnknownUnit("".to_string())),
+        }
+    }
+
+    // after the count: "times a day", "x/day", "x daily", "a day", "per week"
+    fn parse_times(&mut self, count: f64) -> Result<Interval, FrequencyError> {
+        self.accept_word(&TIMES);
+
+        if let Some(Token::Slash) = self.peek() {
+            self.next();
+        } else if let Some((_, minutes)) = self
+            .peek_word()
+            .and_then(|word| ADVERBS.iter().find(|(a, _)| *a == word))
+        {
+            let minutes = *minutes;
+            self.next();
+            return Ok(self.per_minutes(count, minutes));
+        } else if !self.accept_word(&ARTICLES) {
+            return match self.peek_word() {
+                Some(word) => Err(FrequencyError::UnknownWord(word.to_string())),
+                None => Err(FrequencyError::MissingNumber),
+            };
+        }
+
+        let period = self.parse_unit()?;
+        Ok(self.per_minutes(count, period))
+    }
+}
+
+impl Frequency {
+    #[allow(dead_code)]
+    pub fn new(minutes: i64) -> Frequency {
+        Frequency {
+            minutes,
+            start_time: None,
+            max_minutes: None,
+            times_a_day: None,
+        }
+    }
+
+    pub fn get_minutes(&self) -> i64 {
+        self.minutes
+    }
+
+    pub fn get_interval(&self) -> TimeDelta {
+        TimeDelta::minutes(self.minutes)
+    }
+
+    /// Plans every whole number of days (ie, every day, every other day, weekly), which keep
+    /// the time of the day rather than the hours between doses.
+    pub fn keeps_time_of_day(&self) -> bool {
+        self.minutes > 0 && self.minutes % (24 * 60) == 0
+    }
+
+    /// How many times a day for plans spread over the waking hours, None for plain intervals
+    /// and once a day.
+    pub fn get_times_a_day(&self) -> Option<i64> {
+        self.times_a_day
+    }
+
+    // every 6 hours, every 4-6 hours, every 30 minutes, every other day
+    // 3 times a day, twice daily, 1x/day
+    // bid, tid, qid, q6h, q4-6h
+    pub fn parse(frequency: &str) -> Result<Self, FrequencyError> {
+        let interval = Parser {
+            tokens: tokenize(frequency),
+            position: 0,
+        }
+        .parse()?;
+
+        Ok(Frequency {
+            minutes: interval.minutes.round() as i64,
+            start_time: None,
+            max_minutes: interval.max_minutes.map(|max| max.round() as i64),
+            times_a_day: interval
+                .times_a_day
+                .filter(|times| *times >= 2.0 && times.fract() == 0.0)
+                .map(|times| times as i64),
+        })
+    }
+}
+
+// 6 hours, 30 minutes, 4 hours 48 minutes
+fn print_duration(minutes: i64, lang: Language) -> String {
+    let print_minutes = |minutes: i64| match minutes {
+        1 => lang.get(Text::OneMinute).to_string(),
+        minutes => lang.fill(Text::Minutes, &[("minutes", &minutes)]),
+    };
+    let print_hours = |hours: i64| match hours {
+        1 => lang.get(Text::OneHour).to_string(),
+        hours => lang.fill(Text::Hours, &[("hours", &hours)]),
+    };
+
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => print_minutes(minutes),
+        (hours, 0) => print_hours(hours),
+        (hours, minutes) => format!("{} {}", print_hours(hours), print_minutes(minutes)),
+    }
+}
+
+impl Frequency {
+    pub fn print(&self, lang: Language) -> String {
+        if let Some(times) = self.times_a_day {
+            return lang.fill(Text::TimesADay, &[("times", &times)]);
+        }
+
+        match self.max_minutes {
+            Some(max) if self.minutes % 60 == 0 && max % 60 == 0 => lang.fill(
+                Text::EveryHoursRange,
+                &[("min", &(self.minutes / 60)), ("max", &(max / 60))],
+            ),
+            Some(max) => lang.fill(
+                Text::EveryRange,
+                &[
+                    ("min", &print_duration(self.minutes, lang)),
+                    ("max", &print_duration(max, lang)),
+                ],
+            ),
+            None if self.minutes == 60 => lang.get(Text::EveryHour).to_string(),
+            None => lang.fill(
+                Text::Every,
+                &[("duration", &print_duration(self.minutes, lang))],
+            ),
         }
     }
 }
 
 impl Display for Frequency {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
-        write!(f, "every {} hours", self.hours)
+        write!(f, "{}", self.print(Language::English))
     }
 }
 
@@ -107,41 +611,182 @@ impl Display for Frequency {
 mod tests {
     use super::*;
 
+    fn every(hours: i64) -> Frequency {
+        Frequency::new(hours * 60)
+    }
+
+    fn every_minutes(minutes: i64) -> Frequency {
+        Frequency::new(minutes)
+    }
+
+    fn range(hours: i64, max_hours: i64) -> Frequency {
+        Frequency {
+            minutes: hours * 60,
+            start_time: None,
+            max_minutes: Some(max_hours * 60),
+            times_a_day: None,
+        }
+    }
+
+    fn times_a_day(times: i64) -> Frequency {
+        Frequency {
+            minutes: 24 * 60 / times,
+            start_time: None,
+            max_minutes: None,
+            times_a_day: Some(times),
+        }
+    }
+
     #[test]
-    fn test_parse_frequency_every() {
-        assert_eq!(
-            Frequency::parse("every 6 hours"),
-            Some(Frequency {
-                hours: 6,
-                start_time: None
-            })
-        );
+    fn test_parse_frequency() {
+        let cases = [
+            ("every 6 hours", every(6)),
+            ("Every 6 Hours", every(6)),
+            ("every 6h", every(6)),
+            ("every 6 hrs", every(6)),
+            ("every 1.5 days", every(36)),
+            ("every hour", every(1)),
+            ("every day", every(24)),
+            ("every 2 days", every(48)),
+            ("every other day", every(48)),
+            ("every week", every(168)),
+            ("every 120 minutes", every(2)),
+            ("every 4-6 hours", range(4, 6)),
+            ("every 4 - 6 hours", range(4, 6)),
+            ("every 4 to 6 hours", range(4, 6)),
+            ("3 times a day", times_a_day(3)),
+            ("3 times per day", times_a_day(3)),
+            ("3 times daily", times_a_day(3)),
+            ("5 times a day", times_a_day(5)),
+            ("every 30 minutes", every_minutes(30)),
+            ("every 90 mins", every_minutes(90)),
+            ("every 1.5 hours", every_minutes(90)),
+            (
+                "every 15-30 min",
+                Frequency {
+                    minutes: 15,
+                    start_time: None,
+                    max_minutes: Some(30),
+                    times_a_day: None,
+                },
+            ),
+            ("q30min", every_minutes(30)),
+            ("4 times an hour", every_minutes(15)),
+            ("2 times a week", every(84)),
+            ("1x/day", every(24)),
+            ("3x/day", times_a_day(3)),
+            ("3x a day", times_a_day(3)),
+            ("3 x daily", times_a_day(3)),
+            ("once a day", every(24)),
+            ("once daily", every(24)),
+            ("twice daily", times_a_day(2)),
+            ("twice a day", times_a_day(2)),
+            ("thrice a day", times_a_day(3)),
+            ("four times a day", times_a_day(4)),
+            ("daily", every(24)),
+            ("hourly", every(1)),
+            ("weekly", every(168)),
+            ("qd", every(24)),
+            ("od", every(24)),
+            ("bid", times_a_day(2)),
+            ("BID", times_a_day(2)),
+            ("bd", times_a_day(2)),
+            ("tid", times_a_day(3)),
+            ("tds", times_a_day(3)),
+            ("qid", times_a_day(4)),
+            ("qds", times_a_day(4)),
+            ("qod", every(48)),
+            ("q6h", every(6)),
+            ("q 6 h", every(6)),
+            ("q4-6h", range(4, 6)),
+            ("q12h", every(12)),
+            ("cada 6 horas", every(6)),
+            ("cada 4 a 6 horas", range(4, 6)),
+            ("a cada 8 horas", every(8)),
+            ("cada dos días", every(48)),
+            ("3 veces al día", times_a_day(3)),
+            ("dos veces al día", times_a_day(2)),
+            ("una vez al día", every(24)),
+            ("3 vegades al dia", times_a_day(3)),
+            ("3 vezes ao dia", times_a_day(3)),
+            ("diario", every(24)),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(Frequency::parse(input), Ok(expected), "parsing {}", input);
+        }
     }
 
     #[test]
-    fn test_parse_frequency_every_day() {
-        assert_eq!(
-            Frequency::parse("every day"),
-            Some(Frequency {
-                hours: 24,
-                start_time: None
-            })
-        );
+    fn test_parse_frequency_errors() {
+        let cases = [
+            ("", FrequencyError::Empty),
+            ("   ", FrequencyError::Empty),
+            ("lol no way", FrequencyError::UnknownWord("lol".to_string())),
+            ("bdi", FrequencyError::UnknownWord("bdi".to_string())),
+            (
+                "every 6 hors",
+                FrequencyError::UnknownUnit("hors".to_string()),
+            ),
+            ("every 6", FrequencyError::UnknownUnit("".to_string())),
+            ("every 0 hours", FrequencyError::Zero),
+            ("every 6-4 hours", FrequencyError::InvalidRange(6.0, 4.0)),
+            (
+                "every 6 hours please",
+                FrequencyError::TrailingWords("please".to_string()),
+            ),
+            ("3 times", FrequencyError::MissingNumber),
+            ("every 0.1 minutes", FrequencyError::Zero),
+            ("every 99999999999 weeks", FrequencyError::TooLong),
+            ("every 1-400 days", FrequencyError::TooLong),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(Frequency::parse(input), Err(expected), "parsing {}", input);
+        }
+    }
+
+    #[test]
+    fn test_frequency_error_suggestions() {
+        let message = |input: &str| Frequency::parse(input).unwrap_err().to_string();
+
+        assert!(message("bidd").contains("did you mean \"bid\""));
+        assert!(message("twise daily").contains("did you mean \"twice\""));
+        assert!(message("every 6 houres").contains("did you mean \"hours\""));
+        assert!(message("every 6-4 hours").contains("every 4-6 hours"));
     }
 
     #[test]
-    fn test_parse_frequency_times() {
+    fn test_display_frequency() {
+        assert_eq!(every(6).to_string(), "every 6 hours");
+        assert_eq!(every(1).to_string(), "every hour");
+        assert_eq!(every_minutes(30).to_string(), "every 30 minutes");
+        assert_eq!(every_minutes(288).to_string(), "every 4 hours 48 minutes");
+        assert_eq!(times_a_day(3).to_string(), "3 times a day");
+        assert_eq!(range(4, 6).to_string(), "every 4-6 hours");
+
+        assert_eq!(every(6).print(Language::Spanish), "cada 6 horas");
+        assert_eq!(times_a_day(3).print(Language::Catalan), "3 vegades al dia");
         assert_eq!(
-            Frequency::parse("3 times a day"),
-            Some(Frequency {
-                hours: 8,
-                start_time: None
-            })
+            every_minutes(90).print(Language::Portuguese),
+            "a cada 1 hora 30 minutos"
         );
     }
 
     #[test]
-    fn test_parse_frequency_malformed() {
-        assert_eq!(Frequency::parse("lol no way"), None);
+    fn test_deserialize_frequency_in_hours() {
+        let frequency: Frequency =
+            serde_json::from_str("{\"hours\":8,\"start_time\":null}").unwrap();
+        assert_eq!(frequency, every(8));
+
+        let frequency: Frequency =
+            serde_json::from_str("{\"hours\":4,\"start_time\":null,\"max_hours\":6}").unwrap();
+        assert_eq!(frequency, range(4, 6));
+
+        let saved = serde_json::to_string(&every_minutes(30)).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Frequency>(&saved).unwrap(),
+            every_minutes(30)
+        );
     }
 }
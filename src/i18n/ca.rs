@@ -0,0 +1,335 @@
+use super::Text;
+
+pub fn get(text: Text) -> &'static str {
+    match text {
+        // commands
+        Text::Welcome => concat!(
+            "*Et donem la benvinguda a 💊 MediBot\\! 💉*\nEt recordaré quan has de prendre els medicaments\\.\n\n",
+            "Afegeix pacients i pautes de medicació amb /addmedication\\. Registra una presa amb /take\\.\n\n",
+            "Cada matí a les 8:00 t'enviaré un resum del dia, canvia-ho amb /digest\\.\n\n",
+            "Escriu /help per veure totes les ordres disponibles\\."
+        ),
+        Text::HelpHeader => "Aquestes són les ordres disponibles:",
+        Text::HelpInline => "També pots escriure el meu nom d'usuari i el d'un pacient a qualsevol xat (p. ex., @medibot xavi) per compartir com van les seves medicacions, o registrar una presa des d'allà.",
+        Text::CommandStart => "començar a fer servir el bot.",
+        Text::CommandHelp => "mostrar aquest text.",
+        Text::CommandPatients => "gestionar els meus pacients",
+        Text::CommandAddMedication => "afegir una nova pauta de medicació.",
+        Text::CommandTake => "registrar la presa d'un medicament",
+        Text::CommandCancel => "cancel·lar l'operació actual.",
+        Text::CommandGetAll => "veure tots els pacients i medicaments als quals tens accés.",
+        Text::CommandSetTimezone => "canviar la teva zona horària, d'una llista o amb la teva ubicació (p. ex., /settimezone Madrid).",
+        Text::CommandStats => "veure com s'han seguit les pautes últimament.",
+        Text::CommandCalendar => {
+            "exportar les properes dosis a un calendari, de 7 dies o del nombre de dies indicat."
+        }
+        Text::CommandDigest => {
+            "canviar l'hora del resum diari (p. ex., /digest 08:00), o desactivar-lo amb /digest off."
+        }
+        Text::CommandLanguage => "parlar en un altre idioma (p. ex., /language en).",
+        Text::CommandLink => "vincular pacients a un grup familiar, per seguir-los junts allà.",
+        Text::CommandDashboard => "veure-ho tot en una pàgina web, per a una tauleta o l'ordinador (p. ex., /dashboard off per desactivar l'enllaç).",
+        Text::CommandApiToken => "obtenir un token per fer servir l'API des d'altres aplicacions, com la domòtica (p. ex., /apitoken off per revocar-lo).",
+        Text::Cancelling => "Cancel·lant l'operació actual.",
+        Text::Cancel => "Cancel·la",
+        Text::SendTextPlease => "Ho sento, no ho he entès - envia un missatge de text.",
+        Text::DidntGetThat => "No ho he entès, torna-ho a provar o /cancel.",
+        Text::DidntQuiteGetThat => "Ho sento, no ho he acabat d'entendre.",
+        Text::UnknownMessage => {
+            "No ho he acabat d'entendre. Prova amb /addmedication, /patients o /help!"
+        }
+        Text::PrivateOnly => "Ho sento, als grups només puc ajudar amb /take, /getall, /link i /digest. Parla amb mi en privat per a tota la resta! :)",
+        Text::LinkInGroup => "Afegeix-me al vostre grup familiar i envia /link allà per seguir els teus pacients junts.",
+        Text::AskLinkPatients => "Quins pacients ha de seguir aquest grup? Tothom aquí veurà el seu resum diari i les seves preses, i qui hi tingui accés podrà registrar dosis amb /take.",
+        Text::NoPatientsToLink => "Encara no tens pacients que puguis editar, afegeix-ne un amb /addmedication en un xat privat amb mi.",
+        Text::PatientLinked => "{patient} està vinculat a aquest grup. Registreu dosis amb /take i consulteu-ho tot amb /getall.",
+        Text::PatientUnlinked => "{patient} ja no està vinculat a aquest grup.",
+        Text::NoLinkedPatients => "Cap dels teus pacients està vinculat a aquest grup encara, vincula'ls amb /link.",
+        Text::AskGroupIntake => "Quin s'ha pres? Qualsevol amb accés al pacient el pot registrar.",
+        Text::IntakeBy => "{intake} (registrat per {name})",
+        Text::TryAgain => "Ho sento, {error}. Ho pots tornar a provar? (p. ex., {examples})",
+        Text::ViewOnly => "Ho sento, només pots veure {patient}.",
+        Text::OnlyOwner => "Ho sento, només qui gestiona {patient} pot fer això.",
+        Text::NoPlans => {
+            "Encara no has afegit cap pauta de medicació per a {patient}, prova amb /addmedication."
+        }
+        Text::NoMedications => "Encara no hi ha medicaments - prova amb /addmedication per començar.",
+        Text::NoMedicationsTaken => " - Encara no s'ha pres cap medicament.",
+        Text::StatsNoMedications => " - Encara no hi ha medicaments.",
+        Text::StatsNotTaken => "{medicine} - encara no s'ha pres.",
+        Text::Days => "{days} dies",
+        Text::OneDay => "1 dia",
+        Text::AlreadyHasAccess => "Ja tens accés a {patient}.",
+        Text::InviteRedeemed => {
+            "Ara tens accés a {patient} com a {role}. Prova /patients per veure els seus medicaments."
+        }
+        Text::InviteAccepted => "{name} ha acceptat la teva invitació a {patient} com a {role}.",
+        Text::InviteExpired => {
+            "Ho sento, aquest enllaç d'invitació ha caducat o ja s'ha fet servir. Demana'n un de nou!"
+        }
+        Text::DigestOn => "T'envio un resum diari a les {time}. Canvia-ho amb /digest seguit d'una hora (p. ex., /digest 07:30) o desactiva'l amb /digest off.",
+        Text::DigestIsOff => "El resum diari està desactivat. Activa'l amb /digest seguit d'una hora (p. ex., /digest 07:30).",
+        Text::DigestTurnedOff => "D'acord, prou resums diaris.",
+        Text::DigestSet => "Entesos, t'enviaré un resum cada dia a les {time} ({timezone}).",
+        Text::DigestUnknownTime => {
+            "Ho sento, no reconec l'hora: {time}. Prova alguna cosa com /digest 07:30."
+        }
+        Text::TimezoneSet => "Zona horària canviada a {timezone}",
+        Text::TimezoneUnknown => "Ho sento, no reconec la zona horària: {timezone}. Envia /settimezone sol per triar-la d'una llista.",
+        Text::AskTimezone => "🌍 Quina és la teva zona horària? La necessito per avisar-te a l'hora correcta. Comparteix la teva ubicació amb el botó de sota o escriu el nom de la teva ciutat (p. ex., Madrid).",
+        Text::AskTimezoneRegion => "O tria-la de la llista:",
+        Text::AskTimezoneCity => "Tria la ciutat més propera a {region}:",
+        Text::ShareLocation => "📍 Compartir la meva ubicació",
+        Text::Back => "« Enrere",
+        Text::NotATimezone => "Ho sento, no sé la zona horària de {timezone}. Prova amb la ciutat gran més propera, comparteix la teva ubicació o /cancel.",
+        Text::YourTime => "{time} ({own} la teva hora)",
+        Text::LanguageCurrent => "Et parlo en {language}. Canvia-ho amb /language seguit d'un d'aquests: {languages}.",
+        Text::LanguageSet => "D'acord, a partir d'ara et parlaré en català.",
+        Text::LanguageUnknown => {
+            "Ho sento, no conec l'idioma \"{language}\". Prova'n un d'aquests: {languages}."
+        }
+
+        // digest
+        Text::DigestGreeting => "☀️ Bon dia! Aquest és el pla d'avui:\n\n{digest}Canvia l'hora d'aquest missatge o desactiva'l amb /digest.",
+        Text::NothingDueToday => "res per avui",
+        Text::DueAt => "a les {times}",
+        Text::MissedOne => " ⚠️ 1 dosi oblidada ahir.",
+        Text::MissedMany => " ⚠️ {count} dosis oblidades ahir.",
+
+        // adding medications
+        Text::StartAddMedication => {
+            "💊 *Afegint una nova pauta de medicació\\.* 💊 \n\n Comença triant el pacient\\:"
+        }
+        Text::AddNewPatient => "Afegir pacient...",
+        Text::AskNewPatientName => "D'acord, digues-me el nom del nou pacient.",
+        Text::AskMedicine => "Genial. Ara, com es diu el medicament que prendrà {patient}?",
+        Text::MedicineContains => "{medicine} conté {ingredients}. I quina és la dosi?",
+        Text::AskIngredients => "No conec {medicine}. Quins són els seus principis actius? (p. ex., paracetamol, cafeïna - o omet)",
+        Text::AskDosage => "I quina és la dosi?",
+        Text::NeedWeight => "Per això necessito el pes de {patient}, el pots indicar a /patients. O envia una dosi fixa (p. ex., 5 ml, 2 tablets,...)",
+        Text::AskConcentration => {
+            "I quina és la concentració del medicament? (p. ex., 120 mg/5 ml, 40 mg/ml,...)"
+        }
+        Text::AskMaxDose => "Quina és la dosi màxima d'una presa? (p. ex., 500 mg, o cap)",
+        Text::AskFrequency => "Per acabar, cada quant es pren? \\(p\\. ex\\., `cada 6 hores`, o `3 vegades al dia`\\)",
+        Text::DosageExamples => "5 ml, 2 tablets, 400mg, 15 mg/kg,...",
+        Text::MaxDoseExamples => "500 mg, o cap",
+        Text::FrequencyExamples => "cada 6 hores, 3 vegades al dia, bid,...",
+        Text::AddAnyway => "Afegeix igualment",
+        Text::InteractionWarning => "Compte! {medicine} podria no anar bé amb altres medicaments d'aquest pacient:\n\n{interactions}\nConsulta-ho amb un metge o farmacèutic. El vols afegir igualment?",
+        Text::PlanAdded => "\nEntesos\\. Afegint `{medicine}` a la pauta de `{patient}`: `{dosage}`, `{frequency}`\\.\n\nQuan donis la primera dosi, fes servir /take\\.\n",
+
+        // medications
+        Text::MaxDoseWarning => "⚠️ {mg} de {medicine} supera la seva dosi màxima de {max} per presa!",
+        Text::SharedIngredients => "⚠️ {medicine} i {other} contenen {ingredients}: les seves dosis compten juntes per al temps entre preses i el màxim diari.",
+        Text::StockLeft => "{stock} (uns {days} dies, s'acaba el {date})",
+        Text::RightNow => "Ara mateix",
+        Text::InHoursAndMinutes => "d'aquí a {hours} hores i {minutes} minuts",
+        Text::InMinutes => "d'aquí a {minutes} minuts",
+        Text::NotYet => "Encara no",
+        Text::HoursAgo => "fa {hours} hores",
+        Text::MinutesAgo => "fa {minutes} minuts",
+        Text::JustNow => "Ara mateix",
+        Text::MedicationInList => "{medicine} ({dosage}) - {frequency}. Última presa: {last_taken}. Propera presa: {next} {emoji}.",
+        Text::MedicationStock => " Existències: {stock}.",
+        Text::MedicationSharing => " Comparteix principis actius amb {medicines}.",
+
+        // frequencies
+        Text::EveryHour => "cada hora",
+        Text::Every => "cada {duration}",
+        Text::EveryHoursRange => "cada {min}-{max} hores",
+        Text::EveryRange => "cada {min} a {max}",
+        Text::TimesADay => "{times} vegades al dia",
+        Text::OneMinute => "1 minut",
+        Text::Minutes => "{minutes} minuts",
+        Text::OneHour => "1 hora",
+        Text::Hours => "{hours} hores",
+        Text::FrequencyTryExamples => "\"cada 6 hores\", \"3 vegades al dia\" o \"bid\"",
+        Text::FrequencyTryExample => "\"cada 6 hores\"",
+        Text::FrequencyEmpty => "la freqüència està buida, prova {examples}",
+        Text::FrequencyMissingNumber => "no he trobat cada quant, prova {example}",
+        Text::FrequencyZero => "no pot ser zero, prova {example}",
+        Text::FrequencyTooLong => "no pot ser més d'un any, prova {example}",
+        Text::FrequencyInvalidRange => {
+            "l'interval ha d'anar del més curt al més llarg, prova \"{example}\""
+        }
+        Text::FrequencyUnknownUnit => {
+            "no conec la unitat \"{unit}\", prova minuts, hores, dies o setmanes"
+        }
+        Text::FrequencyUnknownUnitSuggestion => {
+            "no conec la unitat \"{unit}\", volies dir \"{suggestion}\"?"
+        }
+        Text::FrequencyUnknownWord => "no entenc \"{word}\", prova {examples}",
+        Text::FrequencyUnknownWordSuggestion => "no entenc \"{word}\", volies dir \"{suggestion}\"?",
+        Text::FrequencyTrailingWords => "no entenc \"{words}\" al final, prova {examples}",
+
+        // dosages
+        Text::DosageEmpty => "la dosi està buida",
+        Text::DosageMissingAmount => "no he trobat la quantitat",
+        Text::DosageInvalidAmount => "la quantitat ha de ser més gran que zero",
+        Text::DosageMissingUnit => "no he trobat la unitat",
+        Text::DosageUnknownUnit => "no conec la unitat \"{unit}\"",
+
+        // stats
+        Text::AdherenceTaken => "{taken}/{expected} preses",
+        Text::AdherenceLate => ", {late} tard (mitjana {minutes} min)",
+        Text::AdherenceMissed => ", {missed} oblidades",
+
+        // taking medicines
+        Text::StartTakeMedicine => {
+            "💉*Hora dels medicaments\\!* 🤒 \n\n Comença triant el pacient\\:"
+        }
+        Text::AskMedicineTaken => "Genial, i quin medicament?",
+        Text::JustTaken => "{patient} acaba de prendre {medicine} ({dosage}). Propera presa {next}. Que es millori!",
+        Text::TakenAt => "{patient} ha pres {medicine} ({dosage}) a les {time}. Propera presa {next}. Que es millori!",
+        Text::JustTakenNotification => {
+            "{patient} acaba de prendre {medicine} ({dosage}). Propera presa {next}. Perquè ho sàpigues!"
+        }
+        Text::TakenAtNotification => {
+            "{patient} ha pres {medicine} ({dosage}) a les {time}. Propera presa {next}. Perquè ho sàpigues!"
+        }
+        Text::WhichIntake => "Per assegurar-me, quin ha estat?",
+        Text::IntakeCandidate => "{medicine} per a {patient}",
+        Text::NothingRegistered => "D'acord, no he registrat res.",
+        Text::InlineStatus => "Última presa: {last_taken}. Propera presa: {next}",
+        Text::ConfirmInlineIntake => "Registro que {patient} ha pres {medicine} ({dosage}) ara mateix?",
+        Text::RegisterIt => "Sí, registra-ho",
+
+        // stock
+        Text::AskStock => "Quant {medicine} tens?{current} (p. ex., {examples})",
+        Text::CurrentStock => " Ara en queden {stock}.",
+        Text::StockExamples => "150 ml, 2 bottles of 100 ml, 3 x 20 tablets,...",
+        Text::AskStockAlert => "Entesos, {stock}. Quan aviso a tothom per comprar-ne més? Us avisaré quan en quedi menys de:",
+        Text::StockUpdated => "Existències de {medicine} actualitzades: {stock}. Avisaré a tothom quan en quedin menys de {days} dies.",
+        Text::LowStock => "⚠️ Queda poc {medicine} per a {patient}: {stock}. Cal comprar-ne més!",
+
+        // calendars
+        Text::AskCalendarPatient => {
+            "Faré un calendari amb les dosis dels propers {days} dies. De quin pacient?"
+        }
+        Text::CalendarReady => "Aquí tens les dosis de {patient} dels propers {days} dies, obre el fitxer per afegir-les al teu calendari. Si es registra una presa a una altra hora, torna'l a exportar per actualitzar-les.",
+
+        // dashboard
+        Text::DashboardLink => "Aquí tens el teu tauler, obre'l a qualsevol pantalla per veure com va tothom: {link}\n\nÉs només de lectura i s'actualitza sol. Qualsevol amb l'enllaç pot veure'l, així que si acaba en males mans desactiva'l amb /dashboard off.",
+        Text::DashboardRevoked => "Fet, aquest enllaç al tauler ja no funciona. Envia /dashboard per tenir-ne un de nou.",
+        Text::DashboardUnavailable => "Ho sento, el tauler no està disponible en aquest servidor.",
+        Text::DashboardNotFound => "Aquest enllaç al tauler ja no funciona, envia /dashboard al bot per tenir-ne un de nou.",
+        Text::DashboardTitle => "Medicacions",
+        Text::DashboardUpdated => "Actualitzat a les {time}, s'actualitza cada {minutes} minuts.",
+        Text::DashboardHistory => "Preses dels últims {days} dies",
+        Text::DashboardNoIntakes => "No hi ha preses en aquest període.",
+        Text::ColumnMedicine => "Medicament",
+        Text::ColumnDosage => "Dosi",
+        Text::ColumnFrequency => "Freqüència",
+        Text::ColumnLastTaken => "Última presa",
+        Text::ColumnNext => "Propera presa",
+        Text::ColumnStock => "Existències",
+
+        // api
+        Text::ApiToken => "Aquí tens el teu token de l'API, guarda'l en secret: {token}\n\nEnvia'l a la capçalera Authorization (Bearer {token}) als endpoints de /api/v1. Qualsevol que el tingui pot veure els teus pacients i registrar preses, així que si es filtra revoca'l amb /apitoken off i demana'n un de nou.",
+        Text::ApiTokenRevoked => "Fet, aquest token de l'API ja no funciona. Envia /apitoken per tenir-ne un de nou.",
+        Text::ApiUnavailable => "Ho sento, l'API no està disponible en aquest servidor.",
+
+        // patients
+        Text::PatientsIntro => "🤒 Aquests són els pacients als quals tens accés\\. 🤕\n\nTria'n un per veure més opcions o afegeix-ne un de nou:",
+        Text::RegisterIntake => "Registrar una presa",
+        Text::AllMedications => "Tots els medicaments",
+        Text::IntakeLog => "Historial de preses",
+        Text::Charts => "Gràfics",
+        Text::LogMeasurement => "Anotar símptoma o constants",
+        Text::Timeline => "Cronologia",
+        Text::Alerts => "Alertes",
+        Text::Report => "Informe per al metge",
+        Text::Stock => "Existències",
+        Text::Weight => "Pes",
+        Text::WakingHours => "Hores despert",
+        Text::Timezone => "Zona horària",
+        Text::Share => "Compartir",
+        Text::InviteLink => "Enllaç d'invitació",
+        Text::Delete => "Eliminar",
+        Text::TransferOwnership => "Transferir",
+        Text::SharedWith => "Pacient compartit amb els comptes: {accounts}\\.\n\n",
+        Text::SelectOption => "{shared}Tria una opció per a {patient}:",
+        Text::AskPatientName => "D'acord, afegint un pacient. Digues-me com es diu:",
+        Text::SelectUser => "Triar usuari",
+        Text::AskShareUser => {
+            "Genial. Ara tria l'usuari de Telegram amb qui compartiràs {patient} o /cancel."
+        }
+        Text::Caregiver => "Cuidador",
+        Text::Viewer => "Observador",
+        Text::RoleOwner => "responsable",
+        Text::RoleCaregiver => "cuidador",
+        Text::RoleViewer => "observador",
+        Text::AskInviteRole => "Creant un enllaç d'invitació per a {patient}. Els cuidadors poden registrar preses i afegir medicaments, els observadors només els poden veure. Quin rol tindrà la persona convidada?",
+        Text::AskNewOwner => "Qui serà el nou responsable de {patient}? Ho haurà d'acceptar, i tu continuaràs tenint accés com a cuidador.",
+        Text::PatientDeleted => "Pacient {patient} eliminat.",
+        Text::NoMedicationsFor => "Encara no hi ha medicaments per a {patient}, afegeix-ne un amb /addmedication.",
+        Text::Interactions => "Interaccions",
+        Text::RegisterWithTake => "Registra una presa amb /take.",
+        Text::AskWeight => "Quant pesa {patient} en kg?{current} Les dosis per pes es recalcularan.",
+        Text::CurrentWeight => " Ara pesa {kg} kg.",
+        Text::AskWakingHours => "Quan sol estar despert {patient}? Ara és {waking_hours}. Els medicaments que es prenen unes quantes vegades al dia es reparteixen en aquestes hores, per no despertar ningú per una dosi. (p. ex., 07:00-21:00)",
+        Text::AskPatientTimezone => "On viu {patient}? Ara faig servir {timezone} per als seus horaris, hores despert i resums. Escriu el nom de la seva ciutat (p. ex., Buenos Aires), comparteix la seva ubicació, o omet per fer servir la zona horària del responsable.",
+        Text::AskStockMedicine => "De quin medicament actualitzo les existències?",
+        Text::AskLogMedicine => "Genial, de quin medicament vols l'historial?",
+        Text::NothingTaken => "{patient} encara no ha pres cap medicament.",
+        Text::ChartsIntro => "Preses de {patient} dels últims {days} dies, una fila per dia de mitjanit a mitjanit amb línies cada 6 hores. Verd és a temps, taronja tard i vermell oblidada.",
+        Text::AskMeasurementKind => "Què vols anotar per a {patient}?",
+        Text::AskThresholdKind => "Tothom qui té accés a {patient} rep un avís quan una mesura està fora de rang. Quina vols configurar?",
+        Text::AskReportPeriod => "Quin període ha de cobrir l'informe de {patient}?",
+        Text::ReportReady => {
+            "Aquí tens l'informe de {patient} dels últims {days} dies, obre'l en un navegador per imprimir-lo."
+        }
+        Text::InviteCreated => "Aquí tens un enllaç d'invitació d'un sol ús a {patient} com a {role}, vàlid fins a {expiry}:\n\n{link}\n\nReenvia'l a la persona amb qui el vols compartir.",
+        Text::Accept => "Acceptar",
+        Text::Decline => "Rebutjar",
+        Text::TransferRequest => "{name} vol que siguis responsable de {patient}. Ho acceptes?",
+        Text::TransferRequested => "D'acord, he demanat a {name} que accepti ser responsable de {patient}. T'avisaré quan ho faci.",
+        Text::TransferInvalid => "Aquesta transferència ja no és vàlida.",
+        Text::TransferAccepted => "Ara ets responsable de {patient}.",
+        Text::TransferAcceptedNotification => {
+            "{name} ha acceptat ser responsable de {patient}. Continues tenint accés com a cuidador."
+        }
+        Text::TransferDeclined => "D'acord, {patient} es queda amb el seu responsable actual.",
+        Text::TransferDeclinedNotification => "{name} ha rebutjat ser responsable de {patient}.",
+        Text::PatientShared => "Pacient {patient} compartit.",
+        Text::NotAUserId => "Això no sembla un ID d'usuari de Telegram (hauria de ser un número). Torna-ho a provar o /cancel.",
+        Text::PatientAdded => "Pacient {patient} afegit. Potser ara vols fer servir /addmedication.",
+        Text::WeightSet => "Pes de {patient} canviat a {kg} kg.",
+        Text::DosageRecalculated => "{medicine} ara és {dosage}.",
+        Text::NotAWeight => "Això no sembla un pes (p. ex., 12.5 kg). Torna-ho a provar o /cancel.",
+        Text::WakingHoursSet => "Hores despert de {patient} canviades a {waking_hours}. Les dosis fora d'aquestes hores passen al matí següent.",
+        Text::NotWakingHours => "No ho he entès (p. ex., 07:00-21:00), torna-ho a provar o /cancel.",
+        Text::PatientTimezoneSet => "Zona horària de {patient} canviada a {timezone}. Les seves hores es mostren al costat de les teves quan són diferents.",
+        Text::PatientTimezoneOff => "{patient} ara fa servir la zona horària del responsable.",
+        Text::LogHeader => "Historial de preses de {medicine} ({dosage}) de {patient}:",
+        Text::LogEmpty => " - El pacient encara no ha pres aquest medicament.",
+
+        // measurements
+        Text::Temperature => "Temperatura",
+        Text::BloodPressure => "Tensió arterial",
+        Text::Glucose => "Glucosa",
+        Text::BodyWeight => "Pes",
+        Text::Symptom => "Símptoma",
+        Text::SymptomExample => "granets als braços",
+        Text::MeasurementEmpty => "està buit",
+        Text::MeasurementInvalidNumber => "no he trobat el número",
+        Text::MeasurementOutOfRange => "això no sembla correcte",
+        Text::AskReading => "{emoji} {kind}? (p. ex., {example})",
+        Text::MeasurementAlert => "⚠️ El valor de {kind} de {patient} és {reading} (avís quan està {threshold}). Anotat per {name}.",
+        Text::MeasurementLogged => "{emoji} Anotat per a {patient}: {kind} {reading}.",
+        Text::AskThreshold => "Quan aviso a tothom pel valor de {kind} de {patient}?{current} (p. ex., més de 38, menys de 70, 70-180 o off)",
+        Text::CurrentThreshold => " Ara aviso quan està {threshold}.",
+        Text::NotAThreshold => {
+            "No ho he entès (p. ex., més de 38, menys de 70, 70-180 o off), torna-ho a provar o /cancel."
+        }
+        Text::ThresholdSet => "Entesos, avisaré a tothom qui té accés a {patient} quan el seu valor de {kind} estigui {threshold}.",
+        Text::ThresholdOff => "D'acord, prou avisos pel valor de {kind} de {patient}.",
+        Text::Outside => "fora de {min}-{max}",
+        Text::Below => "per sota de {min}",
+        Text::Above => "per sobre de {max}",
+        Text::Never => "mai",
+        Text::TimelineEmpty => "No hi ha res anotat per a {patient} els últims {days} dies.",
+        Text::TimelineHeader => "Cronologia de {patient}:",
+    }
+}
@@ -0,0 +1,341 @@
+use super::Text;
+
+pub fn get(text: Text) -> &'static str {
+    match text {
+        // commands
+        Text::Welcome => concat!(
+            "*Welcome to 💊 MediBot\\! 💉*\nI'll remind you of when to take your meds\\.\n\n",
+            "Add patients and medication plans with /addmedication\\. Register an intake with /take\\.\n\n",
+            "Every morning at 8:00 I'll send you a summary of the day, change it with /digest\\.\n\n",
+            "Type /help to see all available commands\\."
+        ),
+        Text::HelpHeader => "These commands are supported:",
+        Text::HelpInline => "You can also type my username and a patient's name in any chat (ie, @medibot xavi) to share how their medications are going, or register a dose from there.",
+        Text::CommandStart => "start interacting with the bot.",
+        Text::CommandHelp => "display this text.",
+        Text::CommandPatients => "manage my patients",
+        Text::CommandAddMedication => "add a new medication plan.",
+        Text::CommandTake => "register a medicine being taken",
+        Text::CommandCancel => "cancel the current operation.",
+        Text::CommandGetAll => "gets all the patients and meds you have access to.",
+        Text::CommandSetTimezone => "set your timezone, from a list or your location (ie, /settimezone Madrid).",
+        Text::CommandStats => "see how well medication plans were followed lately.",
+        Text::CommandCalendar => {
+            "export the upcoming doses to a calendar file, for 7 days or the given number of days."
+        }
+        Text::CommandDigest => {
+            "set the time of the daily summary (ie, /digest 08:00), or turn it off with /digest off."
+        }
+        Text::CommandLanguage => "talk to me in another language (ie, /language es).",
+        Text::CommandLink => "link patients to a family group, to follow them together there.",
+        Text::CommandDashboard => "see everything on a web page, for a tablet or computer (ie, /dashboard off to stop the link).",
+        Text::CommandApiToken => "get a token to use the API from other apps, like home automation (ie, /apitoken off to revoke it).",
+        Text::Cancelling => "Cancelling the current operation.",
+        Text::Cancel => "Cancel",
+        Text::SendTextPlease => "Sorry, couldn't understand that - please send a text message.",
+        Text::DidntGetThat => "Didn't get that, please try again or /cancel.",
+        Text::DidntQuiteGetThat => "Didn't quite get that, sorry.",
+        Text::UnknownMessage => "Didn't quite get that. Try /addmedication, /patients or /help!",
+        Text::PrivateOnly => "Sorry, in groups I can only help with /take, /getall, /link and /digest. Come and have a private chat for everything else! :)",
+        Text::LinkInGroup => "Add me to your family group and send /link there to follow your patients together.",
+        Text::AskLinkPatients => "Which patients should this group follow? Everyone here will see their daily summary and intakes, and those with access can register doses with /take.",
+        Text::NoPatientsToLink => "You don't have any patients you can edit yet, add one with /addmedication in a private chat with me.",
+        Text::PatientLinked => "{patient} is now linked to this group. Register doses with /take and see everything with /getall.",
+        Text::PatientUnlinked => "{patient} is no longer linked to this group.",
+        Text::NoLinkedPatients => "None of your patients are linked to this group yet, link them with /link.",
+        Text::AskGroupIntake => "Which one was taken? Anyone with access to the patient can register it.",
+        Text::IntakeBy => "{intake} (registered by {name})",
+        Text::TryAgain => "Sorry, {error}. Can you try again? (ie, {examples})",
+        Text::ViewOnly => "Sorry, you only have view access to {patient}.",
+        Text::OnlyOwner => "Sorry, only the owner of {patient} can do that.",
+        Text::NoPlans => {
+            "Sorry you haven't added any medication plans for {patient} yet, try /addmedication."
+        }
+        Text::NoMedications => "No medications added yet - try /addmedication to start.",
+        Text::NoMedicationsTaken => " - No medications taken yet.",
+        Text::StatsNoMedications => " - No medications yet.",
+        Text::StatsNotTaken => "{medicine} - not taken yet.",
+        Text::Days => "{days} days",
+        Text::OneDay => "1 day",
+        Text::AlreadyHasAccess => "You already have access to {patient}.",
+        Text::InviteRedeemed => {
+            "You now have access to {patient} as {role}. Try /patients to see their medications."
+        }
+        Text::InviteAccepted => "{name} accepted your invite to {patient} as {role}.",
+        Text::InviteExpired => {
+            "Sorry, this invite link has expired or has already been used. Ask for a new one!"
+        }
+        Text::DigestOn => "I send you a daily summary at {time}. Change it with /digest followed by a time (ie, /digest 07:30) or turn it off with /digest off.",
+        Text::DigestIsOff => "The daily summary is off. Turn it on with /digest followed by a time (ie, /digest 07:30).",
+        Text::DigestTurnedOff => "Ok, no more daily summaries.",
+        Text::DigestSet => "Got it, I'll send you a summary every day at {time} ({timezone}).",
+        Text::DigestUnknownTime => {
+            "Sorry, I don't recognise the time: {time}. Try something like /digest 07:30."
+        }
+        Text::TimezoneSet => "Timezone set for {timezone}",
+        Text::TimezoneUnknown => "Sorry, I don't recognise the timezone: {timezone}. Send /settimezone alone to pick it from a list.",
+        Text::AskTimezone => "🌍 What's your timezone? I need it to remind you at the right time. Share your location with the button below or type the name of your city (ie, Madrid).",
+        Text::AskTimezoneRegion => "Or pick it from the list:",
+        Text::AskTimezoneCity => "Pick the city closest to you in {region}:",
+        Text::ShareLocation => "📍 Share my location",
+        Text::Back => "« Back",
+        Text::NotATimezone => "Sorry, I don't know the timezone of {timezone}. Try the closest big city, share your location or /cancel.",
+        Text::YourTime => "{time} ({own} your time)",
+        Text::LanguageCurrent => "I'm talking to you in {language}. Change it with /language followed by one of: {languages}.",
+        Text::LanguageSet => "Ok, from now on I'll talk to you in English.",
+        Text::LanguageUnknown => {
+            "Sorry, I don't know the language \"{language}\". Try one of: {languages}."
+        }
+
+        // digest
+        Text::DigestGreeting => "☀️ Good morning! Here's today's plan:\n\n{digest}Change the time of this message or turn it off with /digest.",
+        Text::NothingDueToday => "nothing due today",
+        Text::DueAt => "due at {times}",
+        Text::MissedOne => " ⚠️ 1 dose missed yesterday.",
+        Text::MissedMany => " ⚠️ {count} doses missed yesterday.",
+
+        // adding medications
+        Text::StartAddMedication => {
+            "💊 *Adding a new medication plan\\.* 💊 \n\n Please start by selecting the patient\\:"
+        }
+        Text::AddNewPatient => "Add new patient...",
+        Text::AskNewPatientName => "Ok, tell me what's the new patient's name.",
+        Text::AskMedicine => {
+            "Great. Now what's the name of the medicine {patient} is going to be taking?"
+        }
+        Text::MedicineContains => "{medicine} contains {ingredients}. And what's the dosage?",
+        Text::AskIngredients => "I don't know {medicine}. What are its active ingredients? (ie, paracetamol, caffeine - or skip)",
+        Text::AskDosage => "And what's the dosage?",
+        Text::NeedWeight => "I need {patient}'s weight for that, you can set it in /patients. Or send a fixed dosage instead (ie, 5 ml, 2 tablets,...)",
+        Text::AskConcentration => {
+            "And what's the concentration of the medicine? (ie, 120 mg/5 ml, 40 mg/ml,...)"
+        }
+        Text::AskMaxDose => "What's the maximum single dose of the medicine? (ie, 500 mg, or none)",
+        Text::AskFrequency => "Finally, what's the medication frequency? \\(e\\.g\\., `every 6 hours`, or `3 times a day`\\)",
+        Text::DosageExamples => "5 ml, 2 tablets, 400mg, 15 mg/kg,...",
+        Text::MaxDoseExamples => "500 mg, or none",
+        Text::FrequencyExamples => "every 6 hours, 3 times a day, bid,...",
+        Text::AddAnyway => "Add anyway",
+        Text::InteractionWarning => "Careful! {medicine} might not go well with other medications of this patient:\n\n{interactions}\nPlease check with a doctor or pharmacist. Do you want to add it anyway?",
+        Text::PlanAdded => "\nGot it\\. Adding a new plan of `{medicine}` to `{patient}`'s plan: `{dosage}`, `{frequency}`\\.\n\nWhen giving the first dose, run /take\\.\n",
+
+        // medications
+        Text::MaxDoseWarning => "⚠️ {mg} of {medicine} is above its maximum single dose of {max}!",
+        Text::SharedIngredients => "⚠️ {medicine} + {other} both contain {ingredients}: their doses count together for the time between doses and the daily maximum.",
+        Text::StockLeft => "{stock} (about {days} days, runs out on {date})",
+        Text::RightNow => "Right now",
+        Text::InHoursAndMinutes => "in {hours} hours and {minutes} minutes",
+        Text::InMinutes => "in {minutes} minutes",
+        Text::NotYet => "Not yet",
+        Text::HoursAgo => "{hours} hours ago",
+        Text::MinutesAgo => "{minutes} minutes ago",
+        Text::JustNow => "Just now",
+        Text::MedicationInList => "{medicine} ({dosage}) - {frequency}. Last taken: {last_taken}. Can take next: {next} {emoji}.",
+        Text::MedicationStock => " Stock: {stock}.",
+        Text::MedicationSharing => " Shares ingredients with {medicines}.",
+
+        // frequencies
+        Text::EveryHour => "every hour",
+        Text::Every => "every {duration}",
+        Text::EveryHoursRange => "every {min}-{max} hours",
+        Text::EveryRange => "every {min} to {max}",
+        Text::TimesADay => "{times} times a day",
+        Text::OneMinute => "1 minute",
+        Text::Minutes => "{minutes} minutes",
+        Text::OneHour => "1 hour",
+        Text::Hours => "{hours} hours",
+        Text::FrequencyTryExamples => "\"every 6 hours\", \"3 times a day\" or \"bid\"",
+        Text::FrequencyTryExample => "\"every 6 hours\"",
+        Text::FrequencyEmpty => "the frequency is empty, try {examples}",
+        Text::FrequencyMissingNumber => "I couldn't find how often, try {example}",
+        Text::FrequencyZero => "it can't be zero, try {example}",
+        Text::FrequencyTooLong => "it can't be longer than a year, try {example}",
+        Text::FrequencyInvalidRange => {
+            "the range should go from the shortest to the longest, try \"{example}\""
+        }
+        Text::FrequencyUnknownUnit => "I don't know the unit \"{unit}\", try minutes, hours, days or weeks",
+        Text::FrequencyUnknownUnitSuggestion => {
+            "I don't know the unit \"{unit}\", did you mean \"{suggestion}\"?"
+        }
+        Text::FrequencyUnknownWord => "I don't understand \"{word}\", try {examples}",
+        Text::FrequencyUnknownWordSuggestion => {
+            "I don't understand \"{word}\", did you mean \"{suggestion}\"?"
+        }
+        Text::FrequencyTrailingWords => "I don't understand \"{words}\" at the end, try {examples}",
+
+        // dosages
+        Text::DosageEmpty => "the dosage is empty",
+        Text::DosageMissingAmount => "I couldn't find the amount",
+        Text::DosageInvalidAmount => "the amount should be more than zero",
+        Text::DosageMissingUnit => "I couldn't find the unit",
+        Text::DosageUnknownUnit => "I don't know the unit \"{unit}\"",
+
+        // stats
+        Text::AdherenceTaken => "{taken}/{expected} taken",
+        Text::AdherenceLate => ", {late} late (avg {minutes} min)",
+        Text::AdherenceMissed => ", {missed} missed",
+
+        // taking medicines
+        Text::StartTakeMedicine => {
+            "💉*Time to take some meds\\!* 🤒 \n\n Please start by selecting the patient\\:"
+        }
+        Text::AskMedicineTaken => "Great, now the name of the medicine?",
+        Text::JustTaken => "{patient} has just taken {medicine} ({dosage}). Next dosage {next}. All the best for them.",
+        Text::TakenAt => "{patient} took {medicine} ({dosage}) at {time}. Next dosage {next}. All the best for them.",
+        Text::JustTakenNotification => {
+            "{patient} has just taken {medicine} ({dosage}). Next dosage {next}. FYI!"
+        }
+        Text::TakenAtNotification => {
+            "{patient} took {medicine} ({dosage}) at {time}. Next dosage {next}. FYI!"
+        }
+        Text::WhichIntake => "Just to be sure, which one was it?",
+        Text::IntakeCandidate => "{medicine} for {patient}",
+        Text::NothingRegistered => "Ok, nothing registered.",
+        Text::InlineStatus => "Last taken: {last_taken}. Can take next: {next}",
+        Text::ConfirmInlineIntake => "Register {medicine} ({dosage}) for {patient} as taken just now?",
+        Text::RegisterIt => "Yes, register it",
+
+        // stock
+        Text::AskStock => "How much {medicine} do you have?{current} (ie, {examples})",
+        Text::CurrentStock => " There's {stock} left right now.",
+        Text::StockExamples => "150 ml, 2 bottles of 100 ml, 3 x 20 tablets,...",
+        Text::AskStockAlert => "Got it, {stock}. When should I warn everyone to buy more? I'll let you know when there's less than:",
+        Text::StockUpdated => "Stock of {medicine} updated: {stock}. I'll warn everyone when there's less than {days} days left.",
+        Text::LowStock => "⚠️ Running low on {medicine} for {patient}: {stock}. Time to buy more!",
+
+        // calendars
+        Text::AskCalendarPatient => {
+            "I'll make a calendar with the doses of the next {days} days. Which patient?"
+        }
+        Text::CalendarReady => "Here are {patient}'s doses for the next {days} days, open the file to add them to your calendar. If an intake is registered at another time, export it again to update them.",
+
+        // dashboard
+        Text::DashboardLink => "Here's your dashboard, open it on any screen to see how everyone's doing: {link}\n\nIt's read-only and refreshes by itself. Anyone with the link can see it, so if it ends up in the wrong hands turn it off with /dashboard off.",
+        Text::DashboardRevoked => "Done, that dashboard link doesn't work anymore. Send /dashboard for a new one.",
+        Text::DashboardUnavailable => "Sorry, the dashboard isn't available on this server.",
+        Text::DashboardNotFound => "This dashboard link doesn't work anymore, send /dashboard to the bot for a new one.",
+        Text::DashboardTitle => "Medications",
+        Text::DashboardUpdated => "Updated at {time}, refreshes every {minutes} minutes.",
+        Text::DashboardHistory => "Intakes of the last {days} days",
+        Text::DashboardNoIntakes => "No intakes in this period.",
+        Text::ColumnMedicine => "Medicine",
+        Text::ColumnDosage => "Dosage",
+        Text::ColumnFrequency => "Frequency",
+        Text::ColumnLastTaken => "Last taken",
+        Text::ColumnNext => "Can take next",
+        Text::ColumnStock => "Stock",
+
+        // api
+        Text::ApiToken => "Here's your API token, keep it secret: {token}\n\nSend it in the Authorization header (Bearer {token}) to the /api/v1 endpoints. Anyone with it can see your patients and register intakes, so if it leaks revoke it with /apitoken off and ask for a new one.",
+        Text::ApiTokenRevoked => "Done, that API token doesn't work anymore. Send /apitoken for a new one.",
+        Text::ApiUnavailable => "Sorry, the API isn't available on this server.",
+
+        // patients
+        Text::PatientsIntro => "🤒 Here are the patients you have access to\\. 🤕\n\nSelect one for more options or add a new one below:",
+        Text::RegisterIntake => "Register medicine intake",
+        Text::AllMedications => "All medications",
+        Text::IntakeLog => "Intake log",
+        Text::Charts => "Charts",
+        Text::LogMeasurement => "Log symptom or vitals",
+        Text::Timeline => "Timeline",
+        Text::Alerts => "Alerts",
+        Text::Report => "Report for the doctor",
+        Text::Stock => "Stock",
+        Text::Weight => "Weight",
+        Text::WakingHours => "Waking hours",
+        Text::Timezone => "Timezone",
+        Text::Share => "Share",
+        Text::InviteLink => "Invite link",
+        Text::Delete => "Delete",
+        Text::TransferOwnership => "Transfer ownership",
+        Text::SharedWith => "Patient shared with accounts: {accounts}\\.\n\n",
+        Text::SelectOption => "{shared}Select an option for {patient}:",
+        Text::AskPatientName => "Ok, adding a new patient. Please tell me what's their name:",
+        Text::SelectUser => "Select user",
+        Text::AskShareUser => {
+            "Great. Now please select the Telegram user you'll be sharing {patient} with or /cancel."
+        }
+        Text::Caregiver => "Caregiver",
+        Text::Viewer => "Viewer",
+        Text::RoleOwner => "owner",
+        Text::RoleCaregiver => "caregiver",
+        Text::RoleViewer => "viewer",
+        Text::AskInviteRole => "Creating an invite link for {patient}. Caregivers can register intakes and add medications, viewers can only see them. Which role should the invited person have?",
+        Text::AskNewOwner => "Who should become the new owner of {patient}? They'll need to accept it, and you'll keep access as a caregiver.",
+        Text::PatientDeleted => "Patient {patient} deleted.",
+        Text::NoMedicationsFor => "No medications added yet for {patient}, add one with /addmedication.",
+        Text::Interactions => "Interactions",
+        Text::RegisterWithTake => "Register a taken dosage by running /take.",
+        Text::AskWeight => "What's {patient}'s weight in kg?{current} Weight based dosages will be recalculated.",
+        Text::CurrentWeight => " Right now it's {kg} kg.",
+        Text::AskWakingHours => "When is {patient} usually awake? Right now it's {waking_hours}. Medicines taken a number of times a day are spread over these hours, so nobody is woken up for a dose. (ie, 07:00-21:00)",
+        Text::AskPatientTimezone => "Where does {patient} live? Right now I use {timezone} for their schedules, waking hours and digests. Type the name of their city (ie, Buenos Aires), share its location, or skip to use the owner's timezone.",
+        Text::AskStockMedicine => "Updating the stock of which medicine?",
+        Text::AskLogMedicine => "Great, getting the log for which medicine?",
+        Text::NothingTaken => "{patient} hasn't taken any medication yet.",
+        Text::ChartsIntro => "Intakes of {patient} over the last {days} days, a row per day from midnight to midnight with lines every 6 hours. Green is on time, orange late and red missed.",
+        Text::AskMeasurementKind => "What do you want to log for {patient}?",
+        Text::AskThresholdKind => "Everyone with access to {patient} gets a warning when a measurement is out of range. Which one do you want to set?",
+        Text::AskReportPeriod => "Which period should the report for {patient} cover?",
+        Text::ReportReady => {
+            "Here's the report for {patient} over the last {days} days, open it in a browser to print it."
+        }
+        Text::InviteCreated => "Here's a single-use invite link to {patient} as {role}, valid until {expiry}:\n\n{link}\n\nForward it to the person you want to share it with.",
+        Text::Accept => "Accept",
+        Text::Decline => "Decline",
+        Text::TransferRequest => "{name} wants to make you the owner of {patient}. Do you accept?",
+        Text::TransferRequested => "Ok, I've asked {name} to accept the ownership of {patient}. I'll let you know when they do.",
+        Text::TransferInvalid => "This ownership transfer is no longer valid.",
+        Text::TransferAccepted => "You're now the owner of {patient}.",
+        Text::TransferAcceptedNotification => {
+            "{name} accepted the ownership of {patient}. You still have access as a caregiver."
+        }
+        Text::TransferDeclined => "Ok, {patient} stays with its current owner.",
+        Text::TransferDeclinedNotification => "{name} declined the ownership of {patient}.",
+        Text::PatientShared => "Patient {patient} shared.",
+        Text::NotAUserId => "That doesn't look like a Telegram User ID (should be a number). Please try again or /cancel.",
+        Text::PatientAdded => "Added patient {patient}. You might want to /addmedication next.",
+        Text::WeightSet => "{patient}'s weight set to {kg} kg.",
+        Text::DosageRecalculated => "{medicine} is now {dosage}.",
+        Text::NotAWeight => {
+            "That doesn't look like a weight (ie, 12.5 kg). Please try again or /cancel."
+        }
+        Text::WakingHoursSet => "{patient}'s waking hours set to {waking_hours}. Doses due outside them move to the next morning.",
+        Text::NotWakingHours => "Didn't get that (ie, 07:00-21:00), please try again or /cancel.",
+        Text::PatientTimezoneSet => "{patient}'s timezone set to {timezone}. Their times are shown next to yours when they differ.",
+        Text::PatientTimezoneOff => "{patient} now uses the owner's timezone.",
+        Text::LogHeader => "Log for {patient} administration of {medicine} ({dosage}):",
+        Text::LogEmpty => " - Patient hasn't taken this medicine yet.",
+
+        // measurements
+        Text::Temperature => "Temperature",
+        Text::BloodPressure => "Blood pressure",
+        Text::Glucose => "Glucose",
+        Text::BodyWeight => "Weight",
+        Text::Symptom => "Symptom",
+        Text::SymptomExample => "rash on the arms",
+        Text::MeasurementEmpty => "it's empty",
+        Text::MeasurementInvalidNumber => "I couldn't find the number",
+        Text::MeasurementOutOfRange => "that doesn't look right",
+        Text::AskReading => "{emoji} {kind}? (ie, {example})",
+        Text::MeasurementAlert => {
+            "⚠️ {patient}'s {kind} is {reading} (warning when {threshold}). Logged by {name}."
+        }
+        Text::MeasurementLogged => "{emoji} {kind} of {patient} logged: {reading}.",
+        Text::AskThreshold => "When should I warn everyone about {patient}'s {kind}?{current} (ie, above 38, below 70, 70-180 or off)",
+        Text::CurrentThreshold => " Right now it's {threshold}.",
+        Text::NotAThreshold => {
+            "Didn't get that (ie, above 38, below 70, 70-180 or off), please try again or /cancel."
+        }
+        Text::ThresholdSet => {
+            "Got it, I'll warn everyone with access to {patient} when their {kind} is {threshold}."
+        }
+        Text::ThresholdOff => "Ok, no more warnings about {patient}'s {kind}.",
+        Text::Outside => "outside {min}-{max}",
+        Text::Below => "below {min}",
+        Text::Above => "above {max}",
+        Text::Never => "never",
+        Text::TimelineEmpty => "Nothing logged for {patient} in the last {days} days.",
+        Text::TimelineHeader => "Timeline of {patient}:",
+    }
+}
@@ -0,0 +1,339 @@
+use super::Text;
+
+pub fn get(text: Text) -> &'static str {
+    match text {
+        // commands
+        Text::Welcome => concat!(
+            "*Te damos la bienvenida a 💊 MediBot\\! 💉*\nTe recordaré cuándo tomar tus medicinas\\.\n\n",
+            "Añade pacientes y pautas de medicación con /addmedication\\. Registra una toma con /take\\.\n\n",
+            "Cada mañana a las 8:00 te enviaré un resumen del día, cámbialo con /digest\\.\n\n",
+            "Escribe /help para ver todos los comandos disponibles\\."
+        ),
+        Text::HelpHeader => "Estos son los comandos disponibles:",
+        Text::HelpInline => "También puedes escribir mi nombre de usuario y el de un paciente en cualquier chat (ej, @medibot xavi) para compartir cómo van sus medicaciones, o registrar una toma desde allí.",
+        Text::CommandStart => "empezar a usar el bot.",
+        Text::CommandHelp => "mostrar este texto.",
+        Text::CommandPatients => "gestionar mis pacientes",
+        Text::CommandAddMedication => "añadir una nueva pauta de medicación.",
+        Text::CommandTake => "registrar la toma de una medicina",
+        Text::CommandCancel => "cancelar la operación actual.",
+        Text::CommandGetAll => "ver todos los pacientes y medicinas a los que tienes acceso.",
+        Text::CommandSetTimezone => "cambiar tu zona horaria, de una lista o con tu ubicación (ej, /settimezone Madrid).",
+        Text::CommandStats => "ver cómo se han seguido las pautas últimamente.",
+        Text::CommandCalendar => {
+            "exportar las próximas dosis a un calendario, de 7 días o del número de días indicado."
+        }
+        Text::CommandDigest => {
+            "cambiar la hora del resumen diario (ej, /digest 08:00), o desactivarlo con /digest off."
+        }
+        Text::CommandLanguage => "hablar en otro idioma (ej, /language en).",
+        Text::CommandLink => "vincular pacientes a un grupo familiar, para seguirlos juntos allí.",
+        Text::CommandDashboard => "ver todo en una página web, para una tableta o el ordenador (ej, /dashboard off para desactivar el enlace).",
+        Text::CommandApiToken => "obtener un token para usar la API desde otras aplicaciones, como la domótica (ej, /apitoken off para revocarlo).",
+        Text::Cancelling => "Cancelando la operación actual.",
+        Text::Cancel => "Cancelar",
+        Text::SendTextPlease => "Lo siento, no lo he entendido - envía un mensaje de texto.",
+        Text::DidntGetThat => "No lo he entendido, inténtalo de nuevo o /cancel.",
+        Text::DidntQuiteGetThat => "Lo siento, no lo he acabado de entender.",
+        Text::UnknownMessage => {
+            "No lo he acabado de entender. ¡Prueba con /addmedication, /patients o /help!"
+        }
+        Text::PrivateOnly => "Lo siento, en los grupos solo puedo ayudar con /take, /getall, /link y /digest. ¡Háblame por privado para todo lo demás! :)",
+        Text::LinkInGroup => "Añádeme a vuestro grupo familiar y envía /link allí para seguir a tus pacientes juntos.",
+        Text::AskLinkPatients => "¿Qué pacientes debe seguir este grupo? Todos aquí verán su resumen diario y sus tomas, y quien tenga acceso podrá registrar dosis con /take.",
+        Text::NoPatientsToLink => "Aún no tienes pacientes que puedas editar, añade uno con /addmedication en un chat privado conmigo.",
+        Text::PatientLinked => "{patient} está vinculado a este grupo. Registrad dosis con /take y vedlo todo con /getall.",
+        Text::PatientUnlinked => "{patient} ya no está vinculado a este grupo.",
+        Text::NoLinkedPatients => "Ninguno de tus pacientes está vinculado a este grupo todavía, vincúlalos con /link.",
+        Text::AskGroupIntake => "¿Cuál se ha tomado? Cualquiera con acceso al paciente puede registrarlo.",
+        Text::IntakeBy => "{intake} (registrado por {name})",
+        Text::TryAgain => "Lo siento, {error}. ¿Puedes volver a intentarlo? (ej, {examples})",
+        Text::ViewOnly => "Lo siento, solo puedes ver a {patient}.",
+        Text::OnlyOwner => "Lo siento, solo quien gestiona a {patient} puede hacer eso.",
+        Text::NoPlans => {
+            "Aún no has añadido ninguna pauta de medicación para {patient}, prueba con /addmedication."
+        }
+        Text::NoMedications => "Aún no hay medicinas - prueba con /addmedication para empezar.",
+        Text::NoMedicationsTaken => " - Aún no se ha tomado ninguna medicina.",
+        Text::StatsNoMedications => " - Aún no hay medicinas.",
+        Text::StatsNotTaken => "{medicine} - aún no se ha tomado.",
+        Text::Days => "{days} días",
+        Text::OneDay => "1 día",
+        Text::AlreadyHasAccess => "Ya tienes acceso a {patient}.",
+        Text::InviteRedeemed => {
+            "Ahora tienes acceso a {patient} como {role}. Prueba /patients para ver sus medicinas."
+        }
+        Text::InviteAccepted => "{name} ha aceptado tu invitación a {patient} como {role}.",
+        Text::InviteExpired => {
+            "Lo siento, este enlace de invitación ha caducado o ya se ha usado. ¡Pide uno nuevo!"
+        }
+        Text::DigestOn => "Te envío un resumen diario a las {time}. Cámbialo con /digest seguido de una hora (ej, /digest 07:30) o desactívalo con /digest off.",
+        Text::DigestIsOff => "El resumen diario está desactivado. Actívalo con /digest seguido de una hora (ej, /digest 07:30).",
+        Text::DigestTurnedOff => "Vale, no más resúmenes diarios.",
+        Text::DigestSet => "Entendido, te enviaré un resumen cada día a las {time} ({timezone}).",
+        Text::DigestUnknownTime => {
+            "Lo siento, no reconozco la hora: {time}. Prueba algo como /digest 07:30."
+        }
+        Text::TimezoneSet => "Zona horaria cambiada a {timezone}",
+        Text::TimezoneUnknown => "Lo siento, no reconozco la zona horaria: {timezone}. Envía /settimezone solo para elegirla de una lista.",
+        Text::AskTimezone => "🌍 ¿Cuál es tu zona horaria? La necesito para avisarte a la hora correcta. Comparte tu ubicación con el botón de abajo o escribe el nombre de tu ciudad (ej, Madrid).",
+        Text::AskTimezoneRegion => "O elígela de la lista:",
+        Text::AskTimezoneCity => "Elige la ciudad más cercana en {region}:",
+        Text::ShareLocation => "📍 Compartir mi ubicación",
+        Text::Back => "« Atrás",
+        Text::NotATimezone => "Lo siento, no sé la zona horaria de {timezone}. Prueba con la ciudad grande más cercana, comparte tu ubicación o /cancel.",
+        Text::YourTime => "{time} ({own} tu hora)",
+        Text::LanguageCurrent => "Te hablo en {language}. Cámbialo con /language seguido de uno de estos: {languages}.",
+        Text::LanguageSet => "Vale, a partir de ahora te hablaré en español.",
+        Text::LanguageUnknown => {
+            "Lo siento, no conozco el idioma \"{language}\". Prueba uno de estos: {languages}."
+        }
+
+        // digest
+        Text::DigestGreeting => "☀️ ¡Buenos días! Este es el plan de hoy:\n\n{digest}Cambia la hora de este mensaje o desactívalo con /digest.",
+        Text::NothingDueToday => "nada para hoy",
+        Text::DueAt => "a las {times}",
+        Text::MissedOne => " ⚠️ 1 dosis olvidada ayer.",
+        Text::MissedMany => " ⚠️ {count} dosis olvidadas ayer.",
+
+        // adding medications
+        Text::StartAddMedication => {
+            "💊 *Añadiendo una nueva pauta de medicación\\.* 💊 \n\n Empieza eligiendo el paciente\\:"
+        }
+        Text::AddNewPatient => "Añadir paciente...",
+        Text::AskNewPatientName => "Vale, dime el nombre del nuevo paciente.",
+        Text::AskMedicine => "Genial. Ahora, ¿cómo se llama la medicina que va a tomar {patient}?",
+        Text::MedicineContains => "{medicine} contiene {ingredients}. ¿Y cuál es la dosis?",
+        Text::AskIngredients => "No conozco {medicine}. ¿Cuáles son sus principios activos? (ej, paracetamol, cafeína - o saltar)",
+        Text::AskDosage => "¿Y cuál es la dosis?",
+        Text::NeedWeight => "Para eso necesito el peso de {patient}, puedes indicarlo en /patients. O envía una dosis fija (ej, 5 ml, 2 tablets,...)",
+        Text::AskConcentration => {
+            "¿Y cuál es la concentración de la medicina? (ej, 120 mg/5 ml, 40 mg/ml,...)"
+        }
+        Text::AskMaxDose => "¿Cuál es la dosis máxima de una toma? (ej, 500 mg, o ninguna)",
+        Text::AskFrequency => "Por último, ¿cada cuánto se toma? \\(ej\\., `cada 6 horas`, o `3 veces al día`\\)",
+        Text::DosageExamples => "5 ml, 2 tablets, 400mg, 15 mg/kg,...",
+        Text::MaxDoseExamples => "500 mg, o ninguna",
+        Text::FrequencyExamples => "cada 6 horas, 3 veces al día, bid,...",
+        Text::AddAnyway => "Añadir igualmente",
+        Text::InteractionWarning => "¡Cuidado! {medicine} podría no ir bien con otras medicinas de este paciente:\n\n{interactions}\nConsúltalo con un médico o farmacéutico. ¿Quieres añadirla igualmente?",
+        Text::PlanAdded => "\nEntendido\\. Añadiendo `{medicine}` a la pauta de `{patient}`: `{dosage}`, `{frequency}`\\.\n\nCuando des la primera dosis, usa /take\\.\n",
+
+        // medications
+        Text::MaxDoseWarning => "⚠️ ¡{mg} de {medicine} supera su dosis máxima de {max} por toma!",
+        Text::SharedIngredients => "⚠️ {medicine} y {other} contienen {ingredients}: sus dosis cuentan juntas para el tiempo entre tomas y el máximo diario.",
+        Text::StockLeft => "{stock} (unos {days} días, se acaba el {date})",
+        Text::RightNow => "Ahora mismo",
+        Text::InHoursAndMinutes => "en {hours} horas y {minutes} minutos",
+        Text::InMinutes => "en {minutes} minutos",
+        Text::NotYet => "Aún no",
+        Text::HoursAgo => "hace {hours} horas",
+        Text::MinutesAgo => "hace {minutes} minutos",
+        Text::JustNow => "Ahora mismo",
+        Text::MedicationInList => "{medicine} ({dosage}) - {frequency}. Última toma: {last_taken}. Próxima toma: {next} {emoji}.",
+        Text::MedicationStock => " Existencias: {stock}.",
+        Text::MedicationSharing => " Comparte principios activos con {medicines}.",
+
+        // frequencies
+        Text::EveryHour => "cada hora",
+        Text::Every => "cada {duration}",
+        Text::EveryHoursRange => "cada {min}-{max} horas",
+        Text::EveryRange => "cada {min} a {max}",
+        Text::TimesADay => "{times} veces al día",
+        Text::OneMinute => "1 minuto",
+        Text::Minutes => "{minutes} minutos",
+        Text::OneHour => "1 hora",
+        Text::Hours => "{hours} horas",
+        Text::FrequencyTryExamples => "\"cada 6 horas\", \"3 veces al día\" o \"bid\"",
+        Text::FrequencyTryExample => "\"cada 6 horas\"",
+        Text::FrequencyEmpty => "la frecuencia está vacía, prueba {examples}",
+        Text::FrequencyMissingNumber => "no he encontrado cada cuánto, prueba {example}",
+        Text::FrequencyZero => "no puede ser cero, prueba {example}",
+        Text::FrequencyTooLong => "no puede ser más de un año, prueba {example}",
+        Text::FrequencyInvalidRange => {
+            "el intervalo debe ir del más corto al más largo, prueba \"{example}\""
+        }
+        Text::FrequencyUnknownUnit => {
+            "no conozco la unidad \"{unit}\", prueba minutos, horas, días o semanas"
+        }
+        Text::FrequencyUnknownUnitSuggestion => {
+            "no conozco la unidad \"{unit}\", ¿querías decir \"{suggestion}\"?"
+        }
+        Text::FrequencyUnknownWord => "no entiendo \"{word}\", prueba {examples}",
+        Text::FrequencyUnknownWordSuggestion => {
+            "no entiendo \"{word}\", ¿querías decir \"{suggestion}\"?"
+        }
+        Text::FrequencyTrailingWords => "no entiendo \"{words}\" al final, prueba {examples}",
+
+        // dosages
+        Text::DosageEmpty => "la dosis está vacía",
+        Text::DosageMissingAmount => "no he encontrado la cantidad",
+        Text::DosageInvalidAmount => "la cantidad debe ser mayor que cero",
+        Text::DosageMissingUnit => "no he encontrado la unidad",
+        Text::DosageUnknownUnit => "no conozco la unidad \"{unit}\"",
+
+        // stats
+        Text::AdherenceTaken => "{taken}/{expected} tomadas",
+        Text::AdherenceLate => ", {late} tarde (media {minutes} min)",
+        Text::AdherenceMissed => ", {missed} olvidadas",
+
+        // taking medicines
+        Text::StartTakeMedicine => {
+            "💉*¡Hora de las medicinas\\!* 🤒 \n\n Empieza eligiendo el paciente\\:"
+        }
+        Text::AskMedicineTaken => "Genial, ¿y qué medicina?",
+        Text::JustTaken => "{patient} acaba de tomar {medicine} ({dosage}). Próxima toma {next}. ¡Que se mejore!",
+        Text::TakenAt => "{patient} ha tomado {medicine} ({dosage}) a las {time}. Próxima toma {next}. ¡Que se mejore!",
+        Text::JustTakenNotification => {
+            "{patient} acaba de tomar {medicine} ({dosage}). Próxima toma {next}. ¡Para que lo sepas!"
+        }
+        Text::TakenAtNotification => {
+            "{patient} ha tomado {medicine} ({dosage}) a las {time}. Próxima toma {next}. ¡Para que lo sepas!"
+        }
+        Text::WhichIntake => "Para asegurarme, ¿cuál ha sido?",
+        Text::IntakeCandidate => "{medicine} para {patient}",
+        Text::NothingRegistered => "Vale, no he registrado nada.",
+        Text::InlineStatus => "Última toma: {last_taken}. Próxima toma: {next}",
+        Text::ConfirmInlineIntake => "¿Registro que {patient} ha tomado {medicine} ({dosage}) ahora mismo?",
+        Text::RegisterIt => "Sí, regístralo",
+
+        // stock
+        Text::AskStock => "¿Cuánto {medicine} tienes?{current} (ej, {examples})",
+        Text::CurrentStock => " Ahora quedan {stock}.",
+        Text::StockExamples => "150 ml, 2 bottles of 100 ml, 3 x 20 tablets,...",
+        Text::AskStockAlert => "Entendido, {stock}. ¿Cuándo aviso a todos para comprar más? Os avisaré cuando quede menos de:",
+        Text::StockUpdated => "Existencias de {medicine} actualizadas: {stock}. Avisaré a todos cuando queden menos de {days} días.",
+        Text::LowStock => "⚠️ Queda poco {medicine} para {patient}: {stock}. ¡Hay que comprar más!",
+
+        // calendars
+        Text::AskCalendarPatient => {
+            "Haré un calendario con las dosis de los próximos {days} días. ¿De qué paciente?"
+        }
+        Text::CalendarReady => "Aquí tienes las dosis de {patient} de los próximos {days} días, abre el archivo para añadirlas a tu calendario. Si se registra una toma a otra hora, expórtalo de nuevo para actualizarlas.",
+
+        // dashboard
+        Text::DashboardLink => "Aquí tienes tu panel, ábrelo en cualquier pantalla para ver cómo va todo el mundo: {link}\n\nEs de solo lectura y se actualiza solo. Cualquiera con el enlace puede verlo, así que si acaba en malas manos desactívalo con /dashboard off.",
+        Text::DashboardRevoked => "Hecho, ese enlace al panel ya no funciona. Envía /dashboard para tener uno nuevo.",
+        Text::DashboardUnavailable => "Lo siento, el panel no está disponible en este servidor.",
+        Text::DashboardNotFound => "Este enlace al panel ya no funciona, envía /dashboard al bot para tener uno nuevo.",
+        Text::DashboardTitle => "Medicaciones",
+        Text::DashboardUpdated => "Actualizado a las {time}, se actualiza cada {minutes} minutos.",
+        Text::DashboardHistory => "Tomas de los últimos {days} días",
+        Text::DashboardNoIntakes => "No hay tomas en este periodo.",
+        Text::ColumnMedicine => "Medicamento",
+        Text::ColumnDosage => "Dosis",
+        Text::ColumnFrequency => "Frecuencia",
+        Text::ColumnLastTaken => "Última toma",
+        Text::ColumnNext => "Próxima toma",
+        Text::ColumnStock => "Existencias",
+
+        // api
+        Text::ApiToken => "Aquí tienes tu token de la API, mantenlo en secreto: {token}\n\nEnvíalo en la cabecera Authorization (Bearer {token}) a los endpoints de /api/v1. Cualquiera que lo tenga puede ver tus pacientes y registrar tomas, así que si se filtra revócalo con /apitoken off y pide uno nuevo.",
+        Text::ApiTokenRevoked => "Hecho, ese token de la API ya no funciona. Envía /apitoken para tener uno nuevo.",
+        Text::ApiUnavailable => "Lo siento, la API no está disponible en este servidor.",
+
+        // patients
+        Text::PatientsIntro => "🤒 Estos son los pacientes a los que tienes acceso\\. 🤕\n\nElige uno para ver más opciones o añade uno nuevo:",
+        Text::RegisterIntake => "Registrar una toma",
+        Text::AllMedications => "Todas las medicinas",
+        Text::IntakeLog => "Historial de tomas",
+        Text::Charts => "Gráficos",
+        Text::LogMeasurement => "Anotar síntoma o constantes",
+        Text::Timeline => "Cronología",
+        Text::Alerts => "Alertas",
+        Text::Report => "Informe para el médico",
+        Text::Stock => "Existencias",
+        Text::Weight => "Peso",
+        Text::WakingHours => "Horas despierto",
+        Text::Timezone => "Zona horaria",
+        Text::Share => "Compartir",
+        Text::InviteLink => "Enlace de invitación",
+        Text::Delete => "Eliminar",
+        Text::TransferOwnership => "Transferir",
+        Text::SharedWith => "Paciente compartido con las cuentas: {accounts}\\.\n\n",
+        Text::SelectOption => "{shared}Elige una opción para {patient}:",
+        Text::AskPatientName => "Vale, añadiendo un paciente. Dime cómo se llama:",
+        Text::SelectUser => "Elegir usuario",
+        Text::AskShareUser => {
+            "Genial. Ahora elige el usuario de Telegram con quien compartirás a {patient} o /cancel."
+        }
+        Text::Caregiver => "Cuidador",
+        Text::Viewer => "Observador",
+        Text::RoleOwner => "responsable",
+        Text::RoleCaregiver => "cuidador",
+        Text::RoleViewer => "observador",
+        Text::AskInviteRole => "Creando un enlace de invitación para {patient}. Los cuidadores pueden registrar tomas y añadir medicinas, los observadores solo pueden verlas. ¿Qué rol tendrá la persona invitada?",
+        Text::AskNewOwner => "¿Quién será el nuevo responsable de {patient}? Tendrá que aceptarlo, y tú seguirás teniendo acceso como cuidador.",
+        Text::PatientDeleted => "Paciente {patient} eliminado.",
+        Text::NoMedicationsFor => "Aún no hay medicinas para {patient}, añade una con /addmedication.",
+        Text::Interactions => "Interacciones",
+        Text::RegisterWithTake => "Registra una toma con /take.",
+        Text::AskWeight => "¿Cuánto pesa {patient} en kg?{current} Las dosis por peso se recalcularán.",
+        Text::CurrentWeight => " Ahora pesa {kg} kg.",
+        Text::AskWakingHours => "¿Cuándo suele estar despierto {patient}? Ahora es {waking_hours}. Las medicinas que se toman varias veces al día se reparten en estas horas, para no despertar a nadie para una dosis. (ej, 07:00-21:00)",
+        Text::AskPatientTimezone => "¿Dónde vive {patient}? Ahora uso {timezone} para sus horarios, horas despierto y resúmenes. Escribe el nombre de su ciudad (ej, Buenos Aires), comparte su ubicación, o saltar para usar la zona horaria del responsable.",
+        Text::AskStockMedicine => "¿De qué medicina actualizo las existencias?",
+        Text::AskLogMedicine => "Genial, ¿de qué medicina quieres el historial?",
+        Text::NothingTaken => "{patient} aún no ha tomado ninguna medicina.",
+        Text::ChartsIntro => "Tomas de {patient} de los últimos {days} días, una fila por día de medianoche a medianoche con líneas cada 6 horas. Verde es a tiempo, naranja tarde y rojo olvidada.",
+        Text::AskMeasurementKind => "¿Qué quieres anotar para {patient}?",
+        Text::AskThresholdKind => "Todos los que tienen acceso a {patient} reciben un aviso cuando una medida está fuera de rango. ¿Cuál quieres configurar?",
+        Text::AskReportPeriod => "¿Qué periodo debe cubrir el informe de {patient}?",
+        Text::ReportReady => {
+            "Aquí tienes el informe de {patient} de los últimos {days} días, ábrelo en un navegador para imprimirlo."
+        }
+        Text::InviteCreated => "Aquí tienes un enlace de invitación de un solo uso a {patient} como {role}, válido hasta {expiry}:\n\n{link}\n\nReenvíalo a la persona con quien lo quieres compartir.",
+        Text::Accept => "Aceptar",
+        Text::Decline => "Rechazar",
+        Text::TransferRequest => "{name} quiere que seas responsable de {patient}. ¿Aceptas?",
+        Text::TransferRequested => "Vale, he pedido a {name} que acepte ser responsable de {patient}. Te avisaré cuando lo haga.",
+        Text::TransferInvalid => "Esta transferencia ya no es válida.",
+        Text::TransferAccepted => "Ahora eres responsable de {patient}.",
+        Text::TransferAcceptedNotification => {
+            "{name} ha aceptado ser responsable de {patient}. Sigues teniendo acceso como cuidador."
+        }
+        Text::TransferDeclined => "Vale, {patient} se queda con su responsable actual.",
+        Text::TransferDeclinedNotification => "{name} ha rechazado ser responsable de {patient}.",
+        Text::PatientShared => "Paciente {patient} compartido.",
+        Text::NotAUserId => "Eso no parece un ID de usuario de Telegram (debería ser un número). Inténtalo de nuevo o /cancel.",
+        Text::PatientAdded => "Paciente {patient} añadido. Quizás quieras usar /addmedication ahora.",
+        Text::WeightSet => "Peso de {patient} cambiado a {kg} kg.",
+        Text::DosageRecalculated => "{medicine} ahora es {dosage}.",
+        Text::NotAWeight => "Eso no parece un peso (ej, 12.5 kg). Inténtalo de nuevo o /cancel.",
+        Text::WakingHoursSet => "Horas despierto de {patient} cambiadas a {waking_hours}. Las dosis fuera de ellas pasan a la mañana siguiente.",
+        Text::NotWakingHours => "No lo he entendido (ej, 07:00-21:00), inténtalo de nuevo o /cancel.",
+        Text::PatientTimezoneSet => "Zona horaria de {patient} cambiada a {timezone}. Sus horas se muestran junto a las tuyas cuando son distintas.",
+        Text::PatientTimezoneOff => "{patient} ahora usa la zona horaria del responsable.",
+        Text::LogHeader => "Historial de tomas de {medicine} ({dosage}) de {patient}:",
+        Text::LogEmpty => " - El paciente aún no ha tomado esta medicina.",
+
+        // measurements
+        Text::Temperature => "Temperatura",
+        Text::BloodPressure => "Tensión arterial",
+        Text::Glucose => "Glucosa",
+        Text::BodyWeight => "Peso",
+        Text::Symptom => "Síntoma",
+        Text::SymptomExample => "sarpullido en los brazos",
+        Text::MeasurementEmpty => "está vacío",
+        Text::MeasurementInvalidNumber => "no he encontrado el número",
+        Text::MeasurementOutOfRange => "eso no parece correcto",
+        Text::AskReading => "{emoji} ¿{kind}? (ej, {example})",
+        Text::MeasurementAlert => "⚠️ El valor de {kind} de {patient} es {reading} (aviso cuando está {threshold}). Anotado por {name}.",
+        Text::MeasurementLogged => "{emoji} Anotado para {patient}: {kind} {reading}.",
+        Text::AskThreshold => "¿Cuándo aviso a todos por el valor de {kind} de {patient}?{current} (ej, más de 38, menos de 70, 70-180 o off)",
+        Text::CurrentThreshold => " Ahora aviso cuando está {threshold}.",
+        Text::NotAThreshold => {
+            "No lo he entendido (ej, más de 38, menos de 70, 70-180 o off), inténtalo de nuevo o /cancel."
+        }
+        Text::ThresholdSet => {
+            "Entendido, avisaré a todos los que tienen acceso a {patient} cuando su valor de {kind} esté {threshold}."
+        }
+        Text::ThresholdOff => "Vale, no más avisos por el valor de {kind} de {patient}.",
+        Text::Outside => "fuera de {min}-{max}",
+        Text::Below => "por debajo de {min}",
+        Text::Above => "por encima de {max}",
+        Text::Never => "nunca",
+        Text::TimelineEmpty => "No hay nada anotado para {patient} en los últimos {days} días.",
+        Text::TimelineHeader => "Cronología de {patient}:",
+    }
+}
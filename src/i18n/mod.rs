@@ -0,0 +1,441 @@
+use std::fmt::Display;
+
+mod ca;
+mod en;
+mod es;
+mod pt;
+
+/// Languages the bot talks, picked per user with /language and otherwise taken from their
+/// Telegram app.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+    Catalan,
+    Portuguese,
+}
+
+impl Language {
+    pub const ALL: [Language; 4] = [
+        Language::English,
+        Language::Spanish,
+        Language::Catalan,
+        Language::Portuguese,
+    ];
+
+    // es, pt-BR, ca_ES, catala, Español
+    pub fn parse(language: &str) -> Option<Self> {
+        let lower = language.trim().to_lowercase();
+        let code = lower.split(['-', '_']).next().unwrap_or_default();
+
+        match code {
+            "en" | "english" => Some(Language::English),
+            "es" | "spanish" | "español" | "espanol" | "castellano" => Some(Language::Spanish),
+            "ca" | "catalan" | "català" | "catala" => Some(Language::Catalan),
+            "pt" | "portuguese" | "português" | "portugues" => Some(Language::Portuguese),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+            Language::Catalan => "ca",
+            Language::Portuguese => "pt",
+        }
+    }
+
+    /// The name of the language in itself.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Spanish => "Español",
+            Language::Catalan => "Català",
+            Language::Portuguese => "Português",
+        }
+    }
+
+    pub fn get(&self, text: Text) -> &'static str {
+        match self {
+            Language::English => en::get(text),
+            Language::Spanish => es::get(text),
+            Language::Catalan => ca::get(text),
+            Language::Portuguese => pt::get(text),
+        }
+    }
+
+    /// The text with each `{name}` replaced by its value.
+    pub fn fill(&self, text: Text, values: &[(&str, &(dyn Display + Sync))]) -> String {
+        values
+            .iter()
+            .fold(self.get(text).to_string(), |filled, (name, value)| {
+                filled.replace(&format!("{{{}}}", name), &value.to_string())
+            })
+    }
+
+    /// Help listing every command, in the order they show up in the command menu.
+    pub fn print_help(&self) -> String {
+        let help = COMMANDS.iter().fold(
+            self.get(Text::HelpHeader).to_string(),
+            |help, (command, text)| format!("{}\n/{} — {}", help, command, self.get(*text)),
+        );
+
+        format!("{}\n\n{}", help, self.get(Text::HelpInline))
+    }
+}
+
+pub const COMMANDS: [(&str, Text); 15] = [
+    ("start", Text::CommandStart),
+    ("help", Text::CommandHelp),
+    ("patients", Text::CommandPatients),
+    ("addmedication", Text::CommandAddMedication),
+    ("take", Text::CommandTake),
+    ("cancel", Text::CommandCancel),
+    ("getall", Text::CommandGetAll),
+    ("settimezone", Text::CommandSetTimezone),
+    ("stats", Text::CommandStats),
+    ("calendar", Text::CommandCalendar),
+    ("digest", Text::CommandDigest),
+    ("language", Text::CommandLanguage),
+    ("link", Text::CommandLink),
+    ("dashboard", Text::CommandDashboard),
+    ("apitoken", Text::CommandApiToken),
+];
+
+// answers to skip a question or turn something off, in any language
+const NOTHING: [&str; 13] = [
+    "skip",
+    "none",
+    "no",
+    "off",
+    "saltar",
+    "ninguno",
+    "ninguna",
+    "desactivar",
+    "omet",
+    "cap",
+    "pular",
+    "nenhum",
+    "nenhuma",
+];
+
+pub fn is_nothing(answer: &str) -> bool {
+    NOTHING.contains(&answer.trim().to_lowercase().as_str())
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Text {
+    // commands
+    Welcome,
+    HelpHeader,
+    HelpInline,
+    CommandStart,
+    CommandHelp,
+    CommandPatients,
+    CommandAddMedication,
+    CommandTake,
+    CommandCancel,
+    CommandGetAll,
+    CommandSetTimezone,
+    CommandStats,
+    CommandCalendar,
+    CommandDigest,
+    CommandLanguage,
+    CommandLink,
+    CommandDashboard,
+    CommandApiToken,
+    Cancelling,
+    Cancel,
+    SendTextPlease,
+    DidntGetThat,
+    DidntQuiteGetThat,
+    UnknownMessage,
+    PrivateOnly,
+    LinkInGroup,
+    AskLinkPatients,
+    NoPatientsToLink,
+    PatientLinked,
+    PatientUnlinked,
+    NoLinkedPatients,
+    AskGroupIntake,
+    IntakeBy,
+    TryAgain,
+    ViewOnly,
+    OnlyOwner,
+    NoPlans,
+    NoMedications,
+    NoMedicationsTaken,
+    StatsNoMedications,
+    StatsNotTaken,
+    Days,
+    OneDay,
+    AlreadyHasAccess,
+    InviteRedeemed,
+    InviteAccepted,
+    InviteExpired,
+    DigestOn,
+    DigestIsOff,
+    DigestTurnedOff,
+    DigestSet,
+    DigestUnknownTime,
+    TimezoneSet,
+    TimezoneUnknown,
+    AskTimezone,
+    AskTimezoneRegion,
+    AskTimezoneCity,
+    ShareLocation,
+    Back,
+    NotATimezone,
+    YourTime,
+    LanguageCurrent,
+    LanguageSet,
+    LanguageUnknown,
+
+    // digest
+    DigestGreeting,
+    NothingDueToday,
+    DueAt,
+    MissedOne,
+    MissedMany,
+
+    // adding medications
+    StartAddMedication,
+    AddNewPatient,
+    AskNewPatientName,
+    AskMedicine,
+    MedicineContains,
+    AskIngredients,
+    AskDosage,
+    NeedWeight,
+    AskConcentration,
+    AskMaxDose,
+    AskFrequency,
+    DosageExamples,
+    MaxDoseExamples,
+    FrequencyExamples,
+    AddAnyway,
+    InteractionWarning,
+    PlanAdded,
+
+    // medications
+    MaxDoseWarning,
+    SharedIngredients,
+    StockLeft,
+    RightNow,
+    InHoursAndMinutes,
+    InMinutes,
+    NotYet,
+    HoursAgo,
+    MinutesAgo,
+    JustNow,
+    MedicationInList,
+    MedicationStock,
+    MedicationSharing,
+
+    // frequencies
+    EveryHour,
+    Every,
+    EveryHoursRange,
+    EveryRange,
+    TimesADay,
+    OneMinute,
+    Minutes,
+    OneHour,
+    Hours,
+    FrequencyTryExamples,
+    FrequencyTryExample,
+    FrequencyEmpty,
+    FrequencyMissingNumber,
+    FrequencyZero,
+    FrequencyTooLong,
+    FrequencyInvalidRange,
+    FrequencyUnknownUnit,
+    FrequencyUnknownUnitSuggestion,
+    FrequencyUnknownWord,
+    FrequencyUnknownWordSuggestion,
+    FrequencyTrailingWords,
+
+    // dosages
+    DosageEmpty,
+    DosageMissingAmount,
+    DosageInvalidAmount,
+    DosageMissingUnit,
+    DosageUnknownUnit,
+
+    // stats
+    AdherenceTaken,
+    AdherenceLate,
+    AdherenceMissed,
+
+    // taking medicines
+    StartTakeMedicine,
+    AskMedicineTaken,
+    JustTaken,
+    TakenAt,
+    JustTakenNotification,
+    TakenAtNotification,
+    WhichIntake,
+    IntakeCandidate,
+    NothingRegistered,
+    InlineStatus,
+    ConfirmInlineIntake,
+    RegisterIt,
+
+    // stock
+    AskStock,
+    CurrentStock,
+    StockExamples,
+    AskStockAlert,
+    StockUpdated,
+    LowStock,
+
+    // calendars
+    AskCalendarPatient,
+    CalendarReady,
+
+    // dashboard
+    DashboardLink,
+    DashboardRevoked,
+    DashboardUnavailable,
+    DashboardNotFound,
+    DashboardTitle,
+    DashboardUpdated,
+    DashboardHistory,
+    DashboardNoIntakes,
+    ColumnMedicine,
+    ColumnDosage,
+    ColumnFrequency,
+    ColumnLastTaken,
+    ColumnNext,
+    ColumnStock,
+
+    // api
+    ApiToken,
+    ApiTokenRevoked,
+    ApiUnavailable,
+
+    // patients
+    PatientsIntro,
+    RegisterIntake,
+    AllMedications,
+    IntakeLog,
+    Charts,
+    LogMeasurement,
+    Timeline,
+    Alerts,
+    Report,
+    Stock,
+    Weight,
+    WakingHours,
+    Timezone,
+    Share,
+    InviteLink,
+    Delete,
+    TransferOwnership,
+    SharedWith,
+    SelectOption,
+    AskPatientName,
+    SelectUser,
+    AskShareUser,
+    Caregiver,
+    Viewer,
+    RoleOwner,
+    RoleCaregiver,
+    RoleViewer,
+    AskInviteRole,
+    AskNewOwner,
+    PatientDeleted,
+    NoMedicationsFor,
+    Interactions,
+    RegisterWithTake,
+    AskWeight,
+    CurrentWeight,
+    AskWakingHours,
+    AskPatientTimezone,
+    AskStockMedicine,
+    AskLogMedicine,
+    NothingTaken,
+    ChartsIntro,
+    AskMeasurementKind,
+    AskThresholdKind,
+    AskReportPeriod,
+    ReportReady,
+    InviteCreated,
+    Accept,
+    Decline,
+    TransferRequest,
+    TransferRequested,
+    TransferInvalid,
+    TransferAccepted,
+    TransferAcceptedNotification,
+    TransferDeclined,
+    TransferDeclinedNotification,
+    PatientShared,
+    NotAUserId,
+    PatientAdded,
+    WeightSet,
+    DosageRecalculated,
+    NotAWeight,
+    WakingHoursSet,
+    NotWakingHours,
+    PatientTimezoneSet,
+    PatientTimezoneOff,
+    LogHeader,
+    LogEmpty,
+
+    // measurements
+    Temperature,
+    BloodPressure,
+    Glucose,
+    BodyWeight,
+    Symptom,
+    SymptomExample,
+    MeasurementEmpty,
+    MeasurementInvalidNumber,
+    MeasurementOutOfRange,
+    AskReading,
+    MeasurementAlert,
+    MeasurementLogged,
+    AskThreshold,
+    CurrentThreshold,
+    NotAThreshold,
+    ThresholdSet,
+    ThresholdOff,
+    Outside,
+    Below,
+    Above,
+    Never,
+    TimelineEmpty,
+    TimelineHeader,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_language() {
+        assert_eq!(Language::parse("es"), Some(Language::Spanish));
+        assert_eq!(Language::parse("pt-BR"), Some(Language::Portuguese));
+        assert_eq!(Language::parse("ca_ES"), Some(Language::Catalan));
+        assert_eq!(Language::parse("Català"), Some(Language::Catalan));
+        assert_eq!(Language::parse("en-GB"), Some(Language::English));
+        assert_eq!(Language::parse("ru"), None);
+        assert_eq!(Language::parse(""), None);
+    }
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(
+            Language::English.fill(Text::ViewOnly, &[("patient", &"Xavi")]),
+            "Sorry, you only have view access to Xavi."
+        );
+        assert_eq!(
+            Language::Spanish.fill(Text::Days, &[("days", &7)]),
+            "7 días"
+        );
+        assert!(Language::Catalan.print_help().contains("\n/language — "));
+    }
+}
@@ -0,0 +1,335 @@
+use super::Text;
+
+pub fn get(text: Text) -> &'static str {
+    match text {
+        // commands
+        Text::Welcome => concat!(
+            "*Boas\\-vindas ao 💊 MediBot\\! 💉*\nVou lembrar\\-te de quando tomar os teus remédios\\.\n\n",
+            "Adiciona pacientes e planos de medicação com /addmedication\\. Regista uma toma com /take\\.\n\n",
+            "Todas as manhãs às 8:00 envio\\-te um resumo do dia, muda\\-o com /digest\\.\n\n",
+            "Escreve /help para ver todos os comandos disponíveis\\."
+        ),
+        Text::HelpHeader => "Estes são os comandos disponíveis:",
+        Text::HelpInline => "Também podes escrever o meu nome de utilizador e o de um paciente em qualquer chat (ex., @medibot xavi) para partilhar como vão as suas medicações, ou registar uma toma a partir daí.",
+        Text::CommandStart => "começar a usar o bot.",
+        Text::CommandHelp => "mostrar este texto.",
+        Text::CommandPatients => "gerir os meus pacientes",
+        Text::CommandAddMedication => "adicionar um novo plano de medicação.",
+        Text::CommandTake => "registar a toma de um remédio",
+        Text::CommandCancel => "cancelar a operação atual.",
+        Text::CommandGetAll => "ver todos os pacientes e remédios a que tens acesso.",
+        Text::CommandSetTimezone => "mudar o teu fuso horário, de uma lista ou com a tua localização (ex., /settimezone Lisbon).",
+        Text::CommandStats => "ver como os planos foram seguidos ultimamente.",
+        Text::CommandCalendar => {
+            "exportar as próximas doses para um calendário, de 7 dias ou do número de dias indicado."
+        }
+        Text::CommandDigest => {
+            "mudar a hora do resumo diário (ex., /digest 08:00), ou desativá-lo com /digest off."
+        }
+        Text::CommandLanguage => "falar noutra língua (ex., /language en).",
+        Text::CommandLink => "ligar pacientes a um grupo familiar, para os acompanharem juntos lá.",
+        Text::CommandDashboard => "ver tudo numa página web, para um tablet ou o computador (ex., /dashboard off para desativar a ligação).",
+        Text::CommandApiToken => "obter um token para usar a API a partir de outras aplicações, como a domótica (ex., /apitoken off para o revogar).",
+        Text::Cancelling => "A cancelar a operação atual.",
+        Text::Cancel => "Cancelar",
+        Text::SendTextPlease => "Desculpa, não percebi - envia uma mensagem de texto.",
+        Text::DidntGetThat => "Não percebi, tenta de novo ou /cancel.",
+        Text::DidntQuiteGetThat => "Desculpa, não percebi bem.",
+        Text::UnknownMessage => "Não percebi bem. Experimenta /addmedication, /patients ou /help!",
+        Text::PrivateOnly => "Desculpa, nos grupos só posso ajudar com /take, /getall, /link e /digest. Fala comigo em privado para tudo o resto! :)",
+        Text::LinkInGroup => "Adiciona-me ao vosso grupo familiar e envia /link lá para acompanharem os teus pacientes juntos.",
+        Text::AskLinkPatients => "Que pacientes deve acompanhar este grupo? Todos aqui vão ver o seu resumo diário e as tomas, e quem tiver acesso pode registar doses com /take.",
+        Text::NoPatientsToLink => "Ainda não tens pacientes que possas editar, adiciona um com /addmedication numa conversa privada comigo.",
+        Text::PatientLinked => "{patient} está ligado a este grupo. Registem doses com /take e vejam tudo com /getall.",
+        Text::PatientUnlinked => "{patient} já não está ligado a este grupo.",
+        Text::NoLinkedPatients => "Nenhum dos teus pacientes está ligado a este grupo ainda, liga-os com /link.",
+        Text::AskGroupIntake => "Qual foi tomado? Qualquer pessoa com acesso ao paciente pode registá-lo.",
+        Text::IntakeBy => "{intake} (registado por {name})",
+        Text::TryAgain => "Desculpa, {error}. Podes tentar de novo? (ex., {examples})",
+        Text::ViewOnly => "Desculpa, só podes ver {patient}.",
+        Text::OnlyOwner => "Desculpa, só quem gere {patient} pode fazer isso.",
+        Text::NoPlans => {
+            "Ainda não adicionaste nenhum plano de medicação para {patient}, experimenta /addmedication."
+        }
+        Text::NoMedications => "Ainda não há remédios - experimenta /addmedication para começar.",
+        Text::NoMedicationsTaken => " - Ainda não foi tomado nenhum remédio.",
+        Text::StatsNoMedications => " - Ainda não há remédios.",
+        Text::StatsNotTaken => "{medicine} - ainda não foi tomado.",
+        Text::Days => "{days} dias",
+        Text::OneDay => "1 dia",
+        Text::AlreadyHasAccess => "Já tens acesso a {patient}.",
+        Text::InviteRedeemed => {
+            "Agora tens acesso a {patient} como {role}. Experimenta /patients para ver os seus remédios."
+        }
+        Text::InviteAccepted => "{name} aceitou o teu convite para {patient} como {role}.",
+        Text::InviteExpired => {
+            "Desculpa, este link de convite expirou ou já foi usado. Pede um novo!"
+        }
+        Text::DigestOn => "Envio-te um resumo diário às {time}. Muda-o com /digest seguido de uma hora (ex., /digest 07:30) ou desativa-o com /digest off.",
+        Text::DigestIsOff => "O resumo diário está desativado. Ativa-o com /digest seguido de uma hora (ex., /digest 07:30).",
+        Text::DigestTurnedOff => "Ok, chega de resumos diários.",
+        Text::DigestSet => "Entendido, vou enviar-te um resumo todos os dias às {time} ({timezone}).",
+        Text::DigestUnknownTime => {
+            "Desculpa, não reconheço a hora: {time}. Experimenta algo como /digest 07:30."
+        }
+        Text::TimezoneSet => "Fuso horário mudado para {timezone}",
+        Text::TimezoneUnknown => "Desculpa, não reconheço o fuso horário: {timezone}. Envia /settimezone sozinho para o escolher de uma lista.",
+        Text::AskTimezone => "🌍 Qual é o teu fuso horário? Preciso dele para te avisar à hora certa. Partilha a tua localização com o botão abaixo ou escreve o nome da tua cidade (ex., Lisbon).",
+        Text::AskTimezoneRegion => "Ou escolhe-o da lista:",
+        Text::AskTimezoneCity => "Escolhe a cidade mais próxima em {region}:",
+        Text::ShareLocation => "📍 Partilhar a minha localização",
+        Text::Back => "« Voltar",
+        Text::NotATimezone => "Desculpa, não sei o fuso horário de {timezone}. Tenta a cidade grande mais próxima, partilha a tua localização ou /cancel.",
+        Text::YourTime => "{time} ({own} a tua hora)",
+        Text::LanguageCurrent => "Estou a falar contigo em {language}. Muda-o com /language seguido de uma destas: {languages}.",
+        Text::LanguageSet => "Ok, a partir de agora vou falar contigo em português.",
+        Text::LanguageUnknown => {
+            "Desculpa, não conheço a língua \"{language}\". Experimenta uma destas: {languages}."
+        }
+
+        // digest
+        Text::DigestGreeting => "☀️ Bom dia! Este é o plano de hoje:\n\n{digest}Muda a hora desta mensagem ou desativa-a com /digest.",
+        Text::NothingDueToday => "nada para hoje",
+        Text::DueAt => "às {times}",
+        Text::MissedOne => " ⚠️ 1 dose esquecida ontem.",
+        Text::MissedMany => " ⚠️ {count} doses esquecidas ontem.",
+
+        // adding medications
+        Text::StartAddMedication => {
+            "💊 *A adicionar um novo plano de medicação\\.* 💊 \n\n Começa por escolher o paciente\\:"
+        }
+        Text::AddNewPatient => "Adicionar paciente...",
+        Text::AskNewPatientName => "Ok, diz-me o nome do novo paciente.",
+        Text::AskMedicine => "Ótimo. Agora, como se chama o remédio que {patient} vai tomar?",
+        Text::MedicineContains => "{medicine} contém {ingredients}. E qual é a dose?",
+        Text::AskIngredients => "Não conheço {medicine}. Quais são os seus princípios ativos? (ex., paracetamol, cafeína - ou pular)",
+        Text::AskDosage => "E qual é a dose?",
+        Text::NeedWeight => "Para isso preciso do peso de {patient}, podes indicá-lo em /patients. Ou envia uma dose fixa (ex., 5 ml, 2 tablets,...)",
+        Text::AskConcentration => {
+            "E qual é a concentração do remédio? (ex., 120 mg/5 ml, 40 mg/ml,...)"
+        }
+        Text::AskMaxDose => "Qual é a dose máxima de uma toma? (ex., 500 mg, ou nenhuma)",
+        Text::AskFrequency => "Por fim, de quanto em quanto tempo se toma? \\(ex\\., `a cada 6 horas`, ou `3 vezes ao dia`\\)",
+        Text::DosageExamples => "5 ml, 2 tablets, 400mg, 15 mg/kg,...",
+        Text::MaxDoseExamples => "500 mg, ou nenhuma",
+        Text::FrequencyExamples => "a cada 6 horas, 3 vezes ao dia, bid,...",
+        Text::AddAnyway => "Adicionar mesmo assim",
+        Text::InteractionWarning => "Cuidado! {medicine} pode não se dar bem com outros remédios deste paciente:\n\n{interactions}\nConfirma com um médico ou farmacêutico. Queres adicioná-lo mesmo assim?",
+        Text::PlanAdded => "\nEntendido\\. A adicionar `{medicine}` ao plano de `{patient}`: `{dosage}`, `{frequency}`\\.\n\nQuando deres a primeira dose, usa /take\\.\n",
+
+        // medications
+        Text::MaxDoseWarning => "⚠️ {mg} de {medicine} ultrapassa a sua dose máxima de {max} por toma!",
+        Text::SharedIngredients => "⚠️ {medicine} e {other} contêm {ingredients}: as suas doses contam juntas para o tempo entre tomas e o máximo diário.",
+        Text::StockLeft => "{stock} (cerca de {days} dias, acaba a {date})",
+        Text::RightNow => "Agora mesmo",
+        Text::InHoursAndMinutes => "daqui a {hours} horas e {minutes} minutos",
+        Text::InMinutes => "daqui a {minutes} minutos",
+        Text::NotYet => "Ainda não",
+        Text::HoursAgo => "há {hours} horas",
+        Text::MinutesAgo => "há {minutes} minutos",
+        Text::JustNow => "Agora mesmo",
+        Text::MedicationInList => "{medicine} ({dosage}) - {frequency}. Última toma: {last_taken}. Próxima toma: {next} {emoji}.",
+        Text::MedicationStock => " Stock: {stock}.",
+        Text::MedicationSharing => " Partilha princípios ativos com {medicines}.",
+
+        // frequencies
+        Text::EveryHour => "a cada hora",
+        Text::Every => "a cada {duration}",
+        Text::EveryHoursRange => "a cada {min}-{max} horas",
+        Text::EveryRange => "a cada {min} a {max}",
+        Text::TimesADay => "{times} vezes ao dia",
+        Text::OneMinute => "1 minuto",
+        Text::Minutes => "{minutes} minutos",
+        Text::OneHour => "1 hora",
+        Text::Hours => "{hours} horas",
+        Text::FrequencyTryExamples => "\"a cada 6 horas\", \"3 vezes ao dia\" ou \"bid\"",
+        Text::FrequencyTryExample => "\"a cada 6 horas\"",
+        Text::FrequencyEmpty => "a frequência está vazia, experimenta {examples}",
+        Text::FrequencyMissingNumber => "não encontrei de quanto em quanto tempo, experimenta {example}",
+        Text::FrequencyZero => "não pode ser zero, experimenta {example}",
+        Text::FrequencyTooLong => "não pode ser mais de um ano, experimenta {example}",
+        Text::FrequencyInvalidRange => {
+            "o intervalo deve ir do mais curto ao mais longo, experimenta \"{example}\""
+        }
+        Text::FrequencyUnknownUnit => {
+            "não conheço a unidade \"{unit}\", experimenta minutos, horas, dias ou semanas"
+        }
+        Text::FrequencyUnknownUnitSuggestion => {
+            "não conheço a unidade \"{unit}\", querias dizer \"{suggestion}\"?"
+        }
+        Text::FrequencyUnknownWord => "não percebo \"{word}\", experimenta {examples}",
+        Text::FrequencyUnknownWordSuggestion => {
+            "não percebo \"{word}\", querias dizer \"{suggestion}\"?"
+        }
+        Text::FrequencyTrailingWords => "não percebo \"{words}\" no fim, experimenta {examples}",
+
+        // dosages
+        Text::DosageEmpty => "a dose está vazia",
+        Text::DosageMissingAmount => "não encontrei a quantidade",
+        Text::DosageInvalidAmount => "a quantidade deve ser maior que zero",
+        Text::DosageMissingUnit => "não encontrei a unidade",
+        Text::DosageUnknownUnit => "não conheço a unidade \"{unit}\"",
+
+        // stats
+        Text::AdherenceTaken => "{taken}/{expected} tomadas",
+        Text::AdherenceLate => ", {late} atrasadas (média {minutes} min)",
+        Text::AdherenceMissed => ", {missed} esquecidas",
+
+        // taking medicines
+        Text::StartTakeMedicine => {
+            "💉*Hora dos remédios\\!* 🤒 \n\n Começa por escolher o paciente\\:"
+        }
+        Text::AskMedicineTaken => "Ótimo, e qual remédio?",
+        Text::JustTaken => "{patient} acabou de tomar {medicine} ({dosage}). Próxima toma {next}. As melhoras!",
+        Text::TakenAt => "{patient} tomou {medicine} ({dosage}) às {time}. Próxima toma {next}. As melhoras!",
+        Text::JustTakenNotification => {
+            "{patient} acabou de tomar {medicine} ({dosage}). Próxima toma {next}. Para saberes!"
+        }
+        Text::TakenAtNotification => {
+            "{patient} tomou {medicine} ({dosage}) às {time}. Próxima toma {next}. Para saberes!"
+        }
+        Text::WhichIntake => "Só para ter a certeza, qual foi?",
+        Text::IntakeCandidate => "{medicine} para {patient}",
+        Text::NothingRegistered => "Ok, não registei nada.",
+        Text::InlineStatus => "Última toma: {last_taken}. Próxima toma: {next}",
+        Text::ConfirmInlineIntake => "Registo que {patient} tomou {medicine} ({dosage}) agora mesmo?",
+        Text::RegisterIt => "Sim, regista",
+
+        // stock
+        Text::AskStock => "Quanto {medicine} tens?{current} (ex., {examples})",
+        Text::CurrentStock => " Agora restam {stock}.",
+        Text::StockExamples => "150 ml, 2 bottles of 100 ml, 3 x 20 tablets,...",
+        Text::AskStockAlert => "Entendido, {stock}. Quando aviso toda a gente para comprar mais? Aviso quando restar menos de:",
+        Text::StockUpdated => "Stock de {medicine} atualizado: {stock}. Aviso toda a gente quando restarem menos de {days} dias.",
+        Text::LowStock => "⚠️ Está a acabar {medicine} para {patient}: {stock}. Hora de comprar mais!",
+
+        // calendars
+        Text::AskCalendarPatient => {
+            "Vou fazer um calendário com as doses dos próximos {days} dias. De que paciente?"
+        }
+        Text::CalendarReady => "Aqui estão as doses de {patient} dos próximos {days} dias, abre o ficheiro para as adicionar ao teu calendário. Se uma toma for registada a outra hora, exporta-o de novo para as atualizar.",
+
+        // dashboard
+        Text::DashboardLink => "Aqui tens o teu painel, abre-o em qualquer ecrã para ver como estão todos: {link}\n\nÉ só de leitura e atualiza-se sozinho. Qualquer pessoa com a ligação pode vê-lo, por isso se acabar em más mãos desativa-o com /dashboard off.",
+        Text::DashboardRevoked => "Feito, essa ligação ao painel já não funciona. Envia /dashboard para teres uma nova.",
+        Text::DashboardUnavailable => "Desculpa, o painel não está disponível neste servidor.",
+        Text::DashboardNotFound => "Esta ligação ao painel já não funciona, envia /dashboard ao bot para teres uma nova.",
+        Text::DashboardTitle => "Medicações",
+        Text::DashboardUpdated => "Atualizado às {time}, atualiza-se a cada {minutes} minutos.",
+        Text::DashboardHistory => "Tomas dos últimos {days} dias",
+        Text::DashboardNoIntakes => "Não há tomas neste período.",
+        Text::ColumnMedicine => "Medicamento",
+        Text::ColumnDosage => "Dose",
+        Text::ColumnFrequency => "Frequência",
+        Text::ColumnLastTaken => "Última toma",
+        Text::ColumnNext => "Próxima toma",
+        Text::ColumnStock => "Stock",
+
+        // api
+        Text::ApiToken => "Aqui tens o teu token da API, mantém-no em segredo: {token}\n\nEnvia-o no cabeçalho Authorization (Bearer {token}) para os endpoints de /api/v1. Qualquer pessoa que o tenha pode ver os teus pacientes e registar tomas, por isso se for divulgado revoga-o com /apitoken off e pede um novo.",
+        Text::ApiTokenRevoked => "Feito, esse token da API já não funciona. Envia /apitoken para teres um novo.",
+        Text::ApiUnavailable => "Desculpa, a API não está disponível neste servidor.",
+
+        // patients
+        Text::PatientsIntro => "🤒 Estes são os pacientes a que tens acesso\\. 🤕\n\nEscolhe um para ver mais opções ou adiciona um novo:",
+        Text::RegisterIntake => "Registar uma toma",
+        Text::AllMedications => "Todos os remédios",
+        Text::IntakeLog => "Histórico de tomas",
+        Text::Charts => "Gráficos",
+        Text::LogMeasurement => "Anotar sintoma ou sinais vitais",
+        Text::Timeline => "Cronologia",
+        Text::Alerts => "Alertas",
+        Text::Report => "Relatório para o médico",
+        Text::Stock => "Stock",
+        Text::Weight => "Peso",
+        Text::WakingHours => "Horas acordado",
+        Text::Timezone => "Fuso horário",
+        Text::Share => "Partilhar",
+        Text::InviteLink => "Link de convite",
+        Text::Delete => "Apagar",
+        Text::TransferOwnership => "Transferir",
+        Text::SharedWith => "Paciente partilhado com as contas: {accounts}\\.\n\n",
+        Text::SelectOption => "{shared}Escolhe uma opção para {patient}:",
+        Text::AskPatientName => "Ok, a adicionar um paciente. Diz-me como se chama:",
+        Text::SelectUser => "Escolher utilizador",
+        Text::AskShareUser => {
+            "Ótimo. Agora escolhe o utilizador do Telegram com quem vais partilhar {patient} ou /cancel."
+        }
+        Text::Caregiver => "Cuidador",
+        Text::Viewer => "Observador",
+        Text::RoleOwner => "responsável",
+        Text::RoleCaregiver => "cuidador",
+        Text::RoleViewer => "observador",
+        Text::AskInviteRole => "A criar um link de convite para {patient}. Os cuidadores podem registar tomas e adicionar remédios, os observadores só os podem ver. Que papel terá a pessoa convidada?",
+        Text::AskNewOwner => "Quem vai ser o novo responsável de {patient}? Terá de aceitar, e tu continuas a ter acesso como cuidador.",
+        Text::PatientDeleted => "Paciente {patient} apagado.",
+        Text::NoMedicationsFor => "Ainda não há remédios para {patient}, adiciona um com /addmedication.",
+        Text::Interactions => "Interações",
+        Text::RegisterWithTake => "Regista uma toma com /take.",
+        Text::AskWeight => "Quanto pesa {patient} em kg?{current} As doses por peso vão ser recalculadas.",
+        Text::CurrentWeight => " Agora pesa {kg} kg.",
+        Text::AskWakingHours => "Quando costuma estar acordado {patient}? Agora é {waking_hours}. Os remédios tomados várias vezes ao dia são distribuídos por estas horas, para ninguém ser acordado para uma dose. (ex., 07:00-21:00)",
+        Text::AskPatientTimezone => "Onde vive {patient}? Agora uso {timezone} para os seus horários, horas acordado e resumos. Escreve o nome da sua cidade (ex., Buenos Aires), partilha a sua localização, ou pular para usar o fuso horário do responsável.",
+        Text::AskStockMedicine => "De que remédio atualizo o stock?",
+        Text::AskLogMedicine => "Ótimo, de que remédio queres o histórico?",
+        Text::NothingTaken => "{patient} ainda não tomou nenhum remédio.",
+        Text::ChartsIntro => "Tomas de {patient} dos últimos {days} dias, uma linha por dia da meia-noite à meia-noite com marcas a cada 6 horas. Verde é a tempo, laranja atrasada e vermelho esquecida.",
+        Text::AskMeasurementKind => "O que queres anotar para {patient}?",
+        Text::AskThresholdKind => "Todos os que têm acesso a {patient} recebem um aviso quando uma medida está fora do intervalo. Qual queres configurar?",
+        Text::AskReportPeriod => "Que período deve cobrir o relatório de {patient}?",
+        Text::ReportReady => {
+            "Aqui está o relatório de {patient} dos últimos {days} dias, abre-o num navegador para o imprimir."
+        }
+        Text::InviteCreated => "Aqui está um link de convite de uso único para {patient} como {role}, válido até {expiry}:\n\n{link}\n\nReencaminha-o para a pessoa com quem o queres partilhar.",
+        Text::Accept => "Aceitar",
+        Text::Decline => "Recusar",
+        Text::TransferRequest => "{name} quer que sejas responsável de {patient}. Aceitas?",
+        Text::TransferRequested => "Ok, pedi a {name} que aceite ser responsável de {patient}. Aviso-te quando o fizer.",
+        Text::TransferInvalid => "Esta transferência já não é válida.",
+        Text::TransferAccepted => "Agora és responsável de {patient}.",
+        Text::TransferAcceptedNotification => {
+            "{name} aceitou ser responsável de {patient}. Continuas a ter acesso como cuidador."
+        }
+        Text::TransferDeclined => "Ok, {patient} fica com o seu responsável atual.",
+        Text::TransferDeclinedNotification => "{name} recusou ser responsável de {patient}.",
+        Text::PatientShared => "Paciente {patient} partilhado.",
+        Text::NotAUserId => "Isso não parece um ID de utilizador do Telegram (deve ser um número). Tenta de novo ou /cancel.",
+        Text::PatientAdded => "Paciente {patient} adicionado. Talvez queiras usar /addmedication agora.",
+        Text::WeightSet => "Peso de {patient} mudado para {kg} kg.",
+        Text::DosageRecalculated => "{medicine} agora é {dosage}.",
+        Text::NotAWeight => "Isso não parece um peso (ex., 12.5 kg). Tenta de novo ou /cancel.",
+        Text::WakingHoursSet => "Horas acordado de {patient} mudadas para {waking_hours}. As doses fora delas passam para a manhã seguinte.",
+        Text::NotWakingHours => "Não percebi (ex., 07:00-21:00), tenta de novo ou /cancel.",
+        Text::PatientTimezoneSet => "Fuso horário de {patient} mudado para {timezone}. As suas horas aparecem junto às tuas quando são diferentes.",
+        Text::PatientTimezoneOff => "{patient} agora usa o fuso horário do responsável.",
+        Text::LogHeader => "Histórico de tomas de {medicine} ({dosage}) de {patient}:",
+        Text::LogEmpty => " - O paciente ainda não tomou este remédio.",
+
+        // measurements
+        Text::Temperature => "Temperatura",
+        Text::BloodPressure => "Tensão arterial",
+        Text::Glucose => "Glicose",
+        Text::BodyWeight => "Peso",
+        Text::Symptom => "Sintoma",
+        Text::SymptomExample => "manchas nos braços",
+        Text::MeasurementEmpty => "está vazio",
+        Text::MeasurementInvalidNumber => "não encontrei o número",
+        Text::MeasurementOutOfRange => "isso não parece certo",
+        Text::AskReading => "{emoji} {kind}? (ex., {example})",
+        Text::MeasurementAlert => "⚠️ O valor de {kind} de {patient} é {reading} (aviso quando está {threshold}). Anotado por {name}.",
+        Text::MeasurementLogged => "{emoji} Anotado para {patient}: {kind} {reading}.",
+        Text::AskThreshold => "Quando aviso toda a gente pelo valor de {kind} de {patient}?{current} (ex., mais de 38, menos de 70, 70-180 ou off)",
+        Text::CurrentThreshold => " Agora aviso quando está {threshold}.",
+        Text::NotAThreshold => {
+            "Não percebi (ex., mais de 38, menos de 70, 70-180 ou off), tenta de novo ou /cancel."
+        }
+        Text::ThresholdSet => "Entendido, aviso todos os que têm acesso a {patient} quando o seu valor de {kind} estiver {threshold}.",
+        Text::ThresholdOff => "Ok, chega de avisos pelo valor de {kind} de {patient}.",
+        Text::Outside => "fora de {min}-{max}",
+        Text::Below => "abaixo de {min}",
+        Text::Above => "acima de {max}",
+        Text::Never => "nunca",
+        Text::TimelineEmpty => "Nada anotado para {patient} nos últimos {days} dias.",
+        Text::TimelineHeader => "Cronologia de {patient}:",
+    }
+}
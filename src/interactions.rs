@@ -0,0 +1,146 @@
+use std::cmp::Reverse;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+// bundled so interaction checks work offline, one interaction per line
+const INTERACTIONS_CSV: &str = include_str!("data/interactions.csv");
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub enum Severity {
+    Minor,
+    Moderate,
+    Major,
+}
+
+impl Severity {
+    pub fn parse(severity: &str) -> Option<Self> {
+        match severity {
+            "minor" => Some(Severity::Minor),
+            "moderate" => Some(Severity::Moderate),
+            "major" => Some(Severity::Major),
+            _ => None,
+        }
+    }
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Severity::Minor => "ℹ️",
+            Severity::Moderate => "⚠️",
+            Severity::Major => "⛔",
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            Severity::Minor => write!(f, "minor"),
+            Severity::Moderate => write!(f, "moderate"),
+            Severity::Major => write!(f, "major"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Interaction {
+    pub ingredient_a: String,
+    pub ingredient_b: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Interaction {
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(4, ',');
+
+        Some(Interaction {
+            ingredient_a: fields.next()?.trim().to_string(),
+            ingredient_b: fields.next()?.trim().to_string(),
+            severity: Severity::parse(fields.next()?.trim())?,
+            message: fields.next()?.trim().to_string(),
+        })
+    }
+
+    pub fn print(&self, medicine_a: &str, medicine_b: &str) -> String {
+        format!(
+            "{} {} + {} ({} interaction): {}",
+            self.severity.emoji(),
+            medicine_a,
+            medicine_b,
+            self.severity,
+            self.message
+        )
+    }
+
+    fn matches(&self, names_a: &[String], names_b: &[String]) -> bool {
+        let contains = |names: &[String], ingredient: &str| {
+            names.iter().any(|name| contains_word(name, ingredient))
+        };
+
+        (contains(names_a, &self.ingredient_a) && contains(names_b, &self.ingredient_b))
+            || (contains(names_a, &self.ingredient_b) && contains(names_b, &self.ingredient_a))
+    }
+}
+
+fn contains_word(name: &str, word: &str) -> bool {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|token| token == word)
+}
+
+pub fn get_interactions() -> &'static Vec<Interaction> {
+    static INTERACTIONS: OnceLock<Vec<Interaction>> = OnceLock::new();
+
+    INTERACTIONS.get_or_init(|| {
+        INTERACTIONS_CSV
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let interaction = Interaction::parse(line);
+                if interaction.is_none() {
+                    log::warn!("Skipping malformed interaction: {}", line);
+                }
+                interaction
+            })
+            .collect()
+    })
+}
+
+/// Interactions between two medicines, given the names or ingredients of each, most severe first.
+pub fn find_interactions(names_a: &[String], names_b: &[String]) -> Vec<&'static Interaction> {
+    let mut interactions: Vec<&Interaction> = get_interactions()
+        .iter()
+        .filter(|interaction| interaction.matches(names_a, names_b))
+        .collect();
+
+    interactions.sort_by_key(|interaction| Reverse(interaction.severity));
+    interactions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_interactions_parse() {
+        let lines = INTERACTIONS_CSV
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+            .count();
+
+        assert_eq!(get_interactions().len(), lines);
+    }
+
+    #[test]
+    fn test_find_interactions() {
+        let found = find_interactions(&["Warfarin 5mg".to_string()], &["ibuprofen".to_string()]);
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].severity, Severity::Major);
+
+        assert!(find_interactions(&["calpol".to_string()], &["ibuprofen".to_string()]).is_empty());
+        assert!(
+            find_interactions(&["ibuprofenate".to_string()], &["warfarin".to_string()]).is_empty()
+        );
+    }
+}
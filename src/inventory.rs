@@ -0,0 +1,153 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dosage::{Dosage, DosageError, Unit};
+
+pub const DEFAULT_ALERT_DAYS: i64 = 3;
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Stock {
+    amount: f64,
+    unit: Unit,
+    pub alert_days: i64,
+    // so caregivers only get one alert until the medicine is restocked
+    alerted: bool,
+}
+
+impl Stock {
+    pub fn new(amount: f64, unit: Unit, alert_days: i64) -> Stock {
+        Stock {
+            amount,
+            unit,
+            alert_days,
+            alerted: false,
+        }
+    }
+
+    // 150ml
+    // 2 bottles of 100ml
+    // 3 x 20 tablets
+    pub fn parse(stock: &str) -> Result<Self, DosageError> {
+        let lower = stock.trim().to_lowercase();
+
+        let (count, quantity) = match lower.split_once(" of ") {
+            Some((count, quantity)) => (parse_count(count)?, quantity),
+            None => match lower.split_once('x') {
+                Some((count, quantity)) if count.trim().parse::<f64>().is_ok() => {
+                    (parse_count(count)?, quantity)
+                }
+                _ => (1.0, lower.as_str()),
+            },
+        };
+
+        match Dosage::parse(quantity)? {
+            Dosage::Quantity { amount, unit } => {
+                Ok(Stock::new(count * amount, unit, DEFAULT_ALERT_DAYS))
+            }
+            Dosage::Text(_) => Err(DosageError::MissingAmount),
+        }
+    }
+
+    /// Takes a dose out of the stock. Doses that can't be converted to the stock unit are ignored.
+    pub fn take(&mut self, dose: &Dosage) {
+        if let Some(amount) = dose.convert_to(self.unit) {
+            self.amount = (self.amount - amount).max(0.0);
+        }
+    }
+
+    /// How many days the stock lasts when giving `dose` every `minutes`.
+    pub fn days_left(&self, dose: &Dosage, minutes: i64) -> Option<f64> {
+        let amount = dose.convert_to(self.unit)?;
+
+        if amount <= 0.0 || minutes <= 0 {
+            return None;
+        }
+
+        let doses_per_day = 24.0 * 60.0 / minutes as f64;
+
+        Some(self.amount / amount / doses_per_day)
+    }
+
+    /// Returns true the first time the stock drops under the alert threshold.
+    pub fn should_alert(&mut self, dose: &Dosage, minutes: i64) -> bool {
+        match self.days_left(dose, minutes) {
+            Some(days) if days < self.alert_days as f64 && !self.alerted => {
+                self.alerted = true;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn parse_count(count: &str) -> Result<f64, DosageError> {
+    let number = count
+        .split_whitespace()
+        .next()
+        .ok_or(DosageError::MissingAmount)?;
+
+    match number.parse::<f64>() {
+        Ok(n) if n > 0.0 => Ok(n),
+        Ok(_) => Err(DosageError::InvalidAmount),
+        Err(_) => Err(DosageError::MissingAmount),
+    }
+}
+
+impl Display for Stock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let rounded = (self.amount * 100.0).round() / 100.0;
+        write!(f, "{} {}", rounded, self.unit.name(rounded != 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stock() {
+        let cases = [
+            ("150ml", Stock::new(150.0, Unit::Ml, DEFAULT_ALERT_DAYS)),
+            (
+                "2 bottles of 100 ml",
+                Stock::new(200.0, Unit::Ml, DEFAULT_ALERT_DAYS),
+            ),
+            (
+                "3 x 20 tabs",
+                Stock::new(60.0, Unit::Tablet, DEFAULT_ALERT_DAYS),
+            ),
+            ("2x10 mg", Stock::new(20.0, Unit::Mg, DEFAULT_ALERT_DAYS)),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(Stock::parse(input), Ok(expected), "parsing {}", input);
+        }
+
+        assert_eq!(
+            Stock::parse("some bottles of 100ml"),
+            Err(DosageError::MissingAmount)
+        );
+    }
+
+    #[test]
+    fn test_stock_days_left_and_alert() {
+        let dose = Dosage::new(5.0, Unit::Ml);
+        let mut stock = Stock::new(60.0, Unit::Ml, 3);
+
+        // 5ml every 6 hours is 20ml a day
+        assert_eq!(stock.days_left(&dose, 6 * 60), Some(3.0));
+        assert!(!stock.should_alert(&dose, 6 * 60));
+
+        stock.take(&dose);
+        stock.take(&dose);
+        stock.take(&dose);
+
+        assert_eq!(stock.to_string(), "45 ml");
+        assert!(stock.should_alert(&dose, 6 * 60));
+        assert!(!stock.should_alert(&dose, 6 * 60));
+
+        stock.take(&Dosage::new(1.0, Unit::Tablet));
+        assert_eq!(stock.to_string(), "45 ml");
+    }
+}
@@ -0,0 +1,110 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use redis::{Commands, Connection, RedisError};
+use redis_macros::{FromRedisValue, ToRedisArgs};
+use serde::{Deserialize, Serialize};
+
+use crate::patient::Role;
+
+// invites are single use and expire after a week
+const INVITE_TTL_SECONDS: u64 = 7 * 24 * 60 * 60;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
+pub struct Invite {
+    pub token: String,
+    pub patient_id: String,
+    pub role: Role,
+    pub created_by: String,
+    pub expires_at: i64,
+}
+
+impl Invite {
+    pub fn new(patient_id: String, role: Role, created_by: String) -> Invite {
+        Invite {
+            // deep link payloads only allow [A-Za-z0-9_-], up to 64 chars
+            token: uuid::Uuid::new_v4().to_string().replace("-", ""),
+            patient_id,
+            role,
+            created_by,
+            expires_at: Utc::now().timestamp() + INVITE_TTL_SECONDS as i64,
+        }
+    }
+
+    pub fn save(&self, connection: Arc<Mutex<Connection>>) -> Result<(), RedisError> {
+        log::info!("saving invite {:?}", self);
+
+        connection.lock().unwrap().set_ex::<String, &Invite, ()>(
+            format!("medi:invite:{}", self.token),
+            self,
+            INVITE_TTL_SECONDS,
+        )
+    }
+
+    pub fn get(token: &str, con: Arc<Mutex<Connection>>) -> Result<Option<Self>, RedisError> {
+        con.lock()
+            .unwrap()
+            .get::<String, Option<Invite>>(format!("medi:invite:{}", token))
+    }
+
+    /// Deletes the invite once redeemed. Only the call that actually deleted it gets true, so
+    /// the same link can't be redeemed twice.
+    pub fn consume(&self, con: Arc<Mutex<Connection>>) -> Result<bool, RedisError> {
+        con.lock()
+            .unwrap()
+            .del::<String, i64>(format!("medi:invite:{}", self.token))
+            .map(|deleted| deleted == 1)
+    }
+
+    pub fn get_link(&self, bot_username: &str) -> String {
+        format!("https://t.me/{}?start={}", bot_username, self.token)
+    }
+
+    pub fn print_expiry(&self, tz: &str) -> String {
+        let date = DateTime::from_timestamp(self.expires_at, 0).unwrap();
+        match tz.parse::<chrono_tz::Tz>() {
+            Err(_) => date.to_string(),
+            Ok(tz) => date.with_timezone(&tz).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_redis_connection() -> redis::Connection {
+        // creating a real connection actually
+        let client = redis::Client::open("redis://127.0.0.1/").expect("Could not connect to Redis");
+        let mut redis_connection = client
+            .get_connection()
+            .expect("Could not get a Redis connection");
+
+        redis::cmd("SELECT")
+            .arg(1) // selecting db 1 for tests to preserve data on the other one (default, 0)
+            .exec(&mut redis_connection)
+            .unwrap();
+        redis::cmd("FLUSHDB").exec(&mut redis_connection).unwrap();
+
+        redis_connection
+    }
+
+    #[test]
+    fn test_invite_single_use() {
+        let invite = Invite::new("patient".to_string(), Role::Viewer, "1234".to_string());
+
+        let redis_con = Arc::new(Mutex::new(create_redis_connection()));
+        invite.save(redis_con.clone()).unwrap();
+
+        // looking it up doesn't use it
+        let found = Invite::get(&invite.token, redis_con.clone()).unwrap();
+        assert_eq!(found.as_ref(), Some(&invite));
+        assert!(Invite::get(&invite.token, redis_con.clone())
+            .unwrap()
+            .is_some());
+
+        assert!(invite.consume(redis_con.clone()).unwrap());
+        assert!(!invite.consume(redis_con.clone()).unwrap());
+        assert_eq!(Invite::get(&invite.token, redis_con.clone()).unwrap(), None);
+    }
+}
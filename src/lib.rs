@@ -8,16 +8,37 @@ pub enum State {
     ReceiveMedicine {
         patient_id: String,
     },
+    ReceiveIngredients {
+        patient_id: String,
+        medicine: String,
+    },
     ReceiveDosage {
         patient_id: String,
         medicine: String,
     },
+    ReceiveConcentration {
+        patient_id: String,
+        medicine: String,
+        mg_per_kg: f64,
+    },
+    ReceiveMaxDose {
+        patient_id: String,
+        medicine: String,
+        weight_dose: String,
+    },
     ReceiveFrequency {
         patient_id: String,
         medicine: String,
         dosage: String,
     },
 
+    ConfirmInteraction {
+        patient_id: String,
+        medicine: String,
+        dosage: String,
+        frequency: String,
+    },
+
     StartAddPatient,
     ReceivePatientName,
     TakeMedicine,
@@ -35,6 +56,51 @@ pub enum State {
     MedicineLog {
         patient_id: String,
     },
+    SelectReportPeriod {
+        patient_id: String,
+    },
+    SelectCalendarPatient {
+        days: i64,
+    },
+    SelectMeasurementKind {
+        patient_id: String,
+    },
+    ReceiveMeasurement {
+        patient_id: String,
+        kind: String,
+    },
+    SelectThresholdKind {
+        patient_id: String,
+    },
+    ReceiveThreshold {
+        patient_id: String,
+        kind: String,
+    },
+    SelectInviteRole {
+        patient_id: String,
+    },
+    SelectTransferOwner {
+        patient_id: String,
+    },
+    ReceivePatientWeight {
+        patient_id: String,
+    },
+    ReceiveWakingHours {
+        patient_id: String,
+    },
+    SelectStockMedication {
+        patient_id: String,
+    },
+    ReceiveStock {
+        medication_id: String,
+    },
+    SelectStockAlertDays {
+        medication_id: String,
+    },
+    SelectTimezone,
+    ReceivePatientTimezone {
+        patient_id: String,
+    },
 }
 
 #[derive(BotCommands, Clone)]
@@ -44,7 +110,7 @@ pub enum State {
 )]
 pub enum Command {
     #[command(description = "start interacting with the bot.")]
-    Start,
+    Start(String),
     #[command(description = "display this text.")]
     Help,
     #[command(description = "manage my patients")]
@@ -57,6 +123,30 @@ pub enum Command {
     Cancel,
     #[command(description = "gets all the patients and meds you have access to.")]
     GetAll,
-    #[command(description = "set the user's timezone - defaults to utc.")]
+    #[command(
+        description = "set your timezone, from a list or your location (ie, /settimezone Madrid)."
+    )]
     SetTimezone(String),
+    #[command(description = "see how well medication plans were followed lately.")]
+    Stats,
+    #[command(
+        description = "export the upcoming doses to a calendar file, for 7 days or the given number of days."
+    )]
+    Calendar(String),
+    #[command(
+        description = "set the time of the daily summary (ie, /digest 08:00), or turn it off with /digest off."
+    )]
+    Digest(String),
+    #[command(description = "talk to me in another language (ie, /language es).")]
+    Language(String),
+    #[command(description = "link patients to a family group, to follow them together there.")]
+    Link,
+    #[command(
+        description = "see everything on a web page, for a tablet or computer (ie, /dashboard off to stop the link)."
+    )]
+    Dashboard(String),
+    #[command(
+        description = "get a token to use the API from other apps, like home automation (ie, /apitoken off to revoke it)."
+    )]
+    ApiToken(String),
 }
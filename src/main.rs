@@ -1,12 +1,22 @@
 use crate::{
     commands::{cancel, get_all_command, help, start},
     flows::add_medication::*,
+    flows::calendar::*,
+    flows::group::*,
+    flows::inline::*,
+    flows::inventory::*,
+    flows::measurements::*,
     flows::patients::*,
     flows::take_medicine::*,
+    flows::timezone::*,
+};
+use commands::{
+    api_token_command, dashboard_command, digest_command, language_command, set_timezone,
+    stats_command,
 };
-use commands::set_timezone;
 use dotenv::dotenv;
 use dptree::filter;
+use i18n::{Language, Text, COMMANDS};
 use medibot::{Command, State};
 use redis::Connection;
 use std::{
@@ -19,19 +29,36 @@ use teloxide::{
         UpdateHandler,
     },
     prelude::*,
+    types::{BotCommand, Me},
     update_listeners::webhooks,
-    utils::command::BotCommands,
 };
 
 use url::Url;
 
+mod api;
+mod calendar;
+mod catalog;
+mod chart;
 mod commands;
+mod dashboard;
+mod digest;
+mod dosage;
 mod err_handling;
 mod flows;
+mod free_text;
 mod frequency;
+mod i18n;
+mod interactions;
+mod inventory;
+mod invite;
+mod measurement;
 mod medication;
 mod patient;
+mod report;
+mod stats;
+mod timezone;
 mod user;
+mod waking_hours;
 
 type MyDialogue = Dialogue<State, InMemStorage<State>>;
 type HandlerResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
@@ -39,6 +66,10 @@ type HandlerResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
 #[derive(Clone)]
 pub struct ConfigParameters {
     redis_connection: Arc<Mutex<Connection>>,
+    // where the dashboard is served, only when using webhooks
+    dashboard_url: Option<Url>,
+    // with webhooks, or on API_PORT when polling
+    api_enabled: bool,
 }
 
 #[tokio::main]
@@ -57,27 +88,62 @@ async fn main() {
         .await
         .expect("Error setting chat menu button");
 
-    bot.set_my_commands(Command::bot_commands())
-        .await
-        .expect("Error setting my commands");
+    for language in Language::ALL {
+        let commands = COMMANDS
+            .iter()
+            .map(|(command, text)| BotCommand::new(*command, language.get(*text)))
+            .collect::<Vec<BotCommand>>();
+
+        // the English ones are the default for everyone else
+        let mut request = bot.set_my_commands(commands);
+        if language != Language::English {
+            request = request.language_code(language.code());
+        }
+
+        request.await.expect("Error setting my commands");
+    }
 
     let redis_connection = client
         .get_connection()
         .expect("Could not get a Redis connection");
 
+    // Heroku host example: "heroku-ping-pong-bot.herokuapp.com"
+    let webhook_url = env::var("WEBHOOK_URL")
+        .ok()
+        .filter(|host| !host.is_empty())
+        .map(|host| Url::parse(&host).expect("HOST env var Url malformed"));
+
+    let api_port: Option<u16> = env::var("API_PORT")
+        .ok()
+        .filter(|port| !port.is_empty())
+        .map(|port| {
+            port.parse()
+                .expect("API_PORT env variable value is not an integer")
+        });
+
     let parameters: ConfigParameters = ConfigParameters {
         redis_connection: Arc::new(Mutex::new(redis_connection)),
+        dashboard_url: webhook_url.clone(),
+        api_enabled: webhook_url.is_some() || api_port.is_some(),
     };
 
+    digest::migrate_default_digests(parameters.redis_connection.clone())
+        .expect("Error turning on the default digests");
+
+    tokio::spawn(digest::run_digests(
+        bot.clone(),
+        parameters.redis_connection.clone(),
+    ));
+
+    let redis_connection = parameters.redis_connection.clone();
+
     let mut dispatch_builder = Dispatcher::builder(bot.clone(), schema())
         .dependencies(dptree::deps![parameters, InMemStorage::<State>::new()])
         .enable_ctrlc_handler()
         .build();
 
-    let webhook_url = env::var("WEBHOOK_URL");
-
     match webhook_url {
-        Ok(host) if host.len() > 0 => {
+        Some(host) => {
             // using webhooks
             let port: u16 = env::var("PORT")
                 .expect("PORT env variable is not set")
@@ -88,23 +154,54 @@ async fn main() {
 
             let addr = ([0, 0, 0, 0], port).into();
 
-            // Heroku host example: "heroku-ping-pong-bot.herokuapp.com"
-            let url = Url::parse(&host)
-                .expect("HOST env var Url malformed")
+            let url = host
                 .join("/webhookBot") // TODO should this be token?
                 .expect("Invalid WEBHOOK_URL");
 
-            let listener = webhooks::axum(bot.clone(), webhooks::Options::new(addr, url))
-                .await
-                .expect("Couldn't setup webhook");
+            let (listener, stop_flag, router) =
+                webhooks::axum_to_router(bot.clone(), webhooks::Options::new(addr, url))
+                    .await
+                    .expect("Couldn't setup webhook");
+
+            // the dashboard and the API are served by the same server as the webhook
+            let app = router
+                .merge(dashboard::router(redis_connection.clone()))
+                .merge(api::router(bot.clone(), redis_connection));
+
+            tokio::spawn(async move {
+                let tcp_listener = tokio::net::TcpListener::bind(addr)
+                    .await
+                    .expect("Couldn't bind to the address");
+
+                axum::serve(tcp_listener, app)
+                    .with_graceful_shutdown(stop_flag)
+                    .await
+                    .expect("Axum server error");
+            });
 
             dispatch_builder
                 .dispatch_with_listener(listener, err_handling::MyErrorHandler::new())
                 .await;
         }
-        _ => {
+        None => {
             log::info!("Using long polling");
 
+            if let Some(port) = api_port {
+                log::info!("Serving the API on port {}", port);
+
+                let app = api::router(bot.clone(), redis_connection);
+
+                tokio::spawn(async move {
+                    let tcp_listener = tokio::net::TcpListener::bind(("0.0.0.0", port))
+                        .await
+                        .expect("Couldn't bind to the API port");
+
+                    axum::serve(tcp_listener, app)
+                        .await
+                        .expect("Axum server error");
+                });
+            }
+
             // long polling
             dispatch_builder.dispatch().await;
         }
@@ -114,22 +211,46 @@ async fn main() {
 fn schema() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'static>> {
     use dptree::case;
 
-    let command_handler = teloxide::filter_command::<Command, _>().branch(
-        filter(|update: Update| update.chat().unwrap().is_private())
-            .branch(case![Command::Help].endpoint(help))
-            .branch(case![Command::Start].endpoint(start))
-            .branch(case![Command::AddMedication].endpoint(start_add_medication))
-            .branch(case![Command::GetAll].endpoint(get_all_command))
-            .branch(case![Command::Take].endpoint(take_medicine_command))
-            .branch(case![Command::Patients].endpoint(patients_command))
-            .branch(case![Command::SetTimezone(timezone)].endpoint(set_timezone))
-            .branch(case![Command::Cancel].endpoint(cancel)),
-    );
+    let command_handler = teloxide::filter_command::<Command, _>()
+        .branch(
+            filter(|update: Update| update.chat().unwrap().is_private())
+                .branch(case![Command::Help].endpoint(help))
+                .branch(case![Command::Start(token)].endpoint(start))
+                .branch(case![Command::AddMedication].endpoint(start_add_medication))
+                .branch(case![Command::GetAll].endpoint(get_all_command))
+                .branch(case![Command::Take].endpoint(take_medicine_command))
+                .branch(case![Command::Patients].endpoint(patients_command))
+                .branch(case![Command::SetTimezone(timezone)].endpoint(set_timezone))
+                .branch(case![Command::Stats].endpoint(stats_command))
+                .branch(case![Command::Calendar(days)].endpoint(calendar_command))
+                .branch(case![Command::Digest(time)].endpoint(digest_command))
+                .branch(case![Command::Language(language)].endpoint(language_command))
+                .branch(case![Command::Link].endpoint(link_command))
+                .branch(case![Command::Dashboard(answer)].endpoint(dashboard_command))
+                .branch(case![Command::ApiToken(answer)].endpoint(api_token_command))
+                .branch(case![Command::Cancel].endpoint(cancel)),
+        )
+        .branch(
+            filter(is_group_chat)
+                .branch(case![Command::Help].endpoint(help))
+                .branch(case![Command::Take].endpoint(group_take_command))
+                .branch(case![Command::GetAll].endpoint(get_all_command))
+                .branch(case![Command::Link].endpoint(link_command))
+                .branch(case![Command::Digest(time)].endpoint(digest_command)),
+        );
 
     let message_handler = Update::filter_message()
+        .branch(filter(is_sent_via_me).endpoint(ignore_message))
         .branch(command_handler)
         .branch(dptree::case![State::ReceiveName].endpoint(receive_name))
         .branch(dptree::case![State::ReceiveMedicine { patient_id }].endpoint(receive_medicine))
+        .branch(
+            dptree::case![State::ReceiveIngredients {
+                patient_id,
+                medicine
+            }]
+            .endpoint(receive_ingredients),
+        )
         .branch(
             dptree::case![State::ReceiveDosage {
                 patient_id,
@@ -137,6 +258,22 @@ fn schema() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'static>>
             }]
             .endpoint(receive_dosage),
         )
+        .branch(
+            dptree::case![State::ReceiveConcentration {
+                patient_id,
+                medicine,
+                mg_per_kg,
+            }]
+            .endpoint(receive_concentration),
+        )
+        .branch(
+            dptree::case![State::ReceiveMaxDose {
+                patient_id,
+                medicine,
+                weight_dose,
+            }]
+            .endpoint(receive_max_dose),
+        )
         .branch(
             dptree::case![State::ReceiveFrequency {
                 patient_id,
@@ -146,16 +283,49 @@ fn schema() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'static>>
             .endpoint(receive_frequency),
         )
         .branch(dptree::case![State::ReceivePatientName].endpoint(receive_new_patient_name))
+        .branch(
+            dptree::case![State::ReceivePatientWeight { patient_id }]
+                .endpoint(receive_patient_weight),
+        )
+        .branch(
+            dptree::case![State::ReceiveWakingHours { patient_id }].endpoint(receive_waking_hours),
+        )
+        .branch(
+            dptree::case![State::ReceivePatientTimezone { patient_id }]
+                .endpoint(receive_patient_timezone),
+        )
+        .branch(dptree::case![State::ReceiveStock { medication_id }].endpoint(receive_stock))
+        .branch(
+            dptree::case![State::ReceiveMeasurement { patient_id, kind }]
+                .endpoint(receive_measurement),
+        )
+        .branch(
+            dptree::case![State::ReceiveThreshold { patient_id, kind }].endpoint(receive_threshold),
+        )
         .branch(
             dptree::case![State::ReceiveTelegramUserForSharePatient { patient_id }]
                 .endpoint(receive_telegram_user_name),
         )
-        .branch(filter(|update: Update| update.chat().unwrap().is_group()).endpoint(group_handler))
+        .branch(dptree::case![State::SelectTimezone].endpoint(receive_timezone))
+        .branch(filter(is_group_chat).endpoint(group_handler))
         .branch(dptree::endpoint(default_handler));
 
     let callback_handler = Update::filter_callback_query()
+        .branch(filter(is_group_link).endpoint(link_callback_handler))
+        .branch(filter(is_group_intake).endpoint(group_intake_callback_handler))
+        .branch(filter(is_transfer_response).endpoint(transfer_response_callback_handler))
+        .branch(filter(is_intake_confirmation).endpoint(intake_confirmation_callback_handler))
         .branch(dptree::case![State::ReceiveName].endpoint(receive_name_callback_handler))
         .branch(dptree::case![State::TakeMedicine].endpoint(take_medicine_callback_handler))
+        .branch(
+            dptree::case![State::ConfirmInteraction {
+                patient_id,
+                medicine,
+                dosage,
+                frequency,
+            }]
+            .endpoint(confirm_interaction_callback_handler),
+        )
         .branch(
             dptree::case![State::TakeMedicineFinal { patient_id }]
                 .endpoint(take_medicine_second_callback_handler),
@@ -167,28 +337,114 @@ fn schema() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'static>>
         .branch(
             dptree::case![State::MedicineLog { patient_id }]
                 .endpoint(medicine_log_callback_handler),
+        )
+        .branch(
+            dptree::case![State::SelectCalendarPatient { days }]
+                .endpoint(calendar_patient_callback_handler),
+        )
+        .branch(
+            dptree::case![State::SelectMeasurementKind { patient_id }]
+                .endpoint(select_measurement_kind_callback_handler),
+        )
+        .branch(
+            dptree::case![State::SelectThresholdKind { patient_id }]
+                .endpoint(select_threshold_kind_callback_handler),
+        )
+        .branch(
+            dptree::case![State::SelectReportPeriod { patient_id }]
+                .endpoint(report_period_callback_handler),
+        )
+        .branch(
+            dptree::case![State::SelectInviteRole { patient_id }]
+                .endpoint(invite_role_callback_handler),
+        )
+        .branch(
+            dptree::case![State::SelectTransferOwner { patient_id }]
+                .endpoint(transfer_owner_callback_handler),
+        )
+        .branch(
+            dptree::case![State::SelectStockMedication { patient_id }]
+                .endpoint(select_stock_medication_callback_handler),
+        )
+        .branch(
+            dptree::case![State::SelectStockAlertDays { medication_id }]
+                .endpoint(stock_alert_days_callback_handler),
+        )
+        .branch(dptree::case![State::SelectTimezone].endpoint(select_timezone_callback_handler));
+
+    // inline queries and the messages they send don't belong to any chat, so have no dialogue
+    let inline_handler = dptree::entry()
+        .branch(
+            Update::filter_inline_query()
+                .map(get_update_language)
+                .endpoint(inline_query_handler),
+        )
+        .branch(
+            Update::filter_callback_query()
+                .filter(is_inline_intake)
+                .map(get_update_language)
+                .endpoint(inline_intake_callback_handler),
         );
 
-    dialogue::enter::<Update, InMemStorage<State>, State, _>()
-        .branch(callback_handler)
-        .branch(message_handler)
+    dptree::entry().branch(inline_handler).branch(
+        dialogue::enter::<Update, InMemStorage<State>, State, _>()
+            .map(get_update_language)
+            .branch(callback_handler)
+            .branch(message_handler),
+    )
 }
 
-async fn group_handler(bot: Bot, _dialogue: MyDialogue, msg: Message) -> HandlerResult {
-    bot.send_message(
-        msg.chat.id,
-        "Sorry I can only reply to private messages, come and have a chat! :)",
-    )
-    .await?;
+fn is_group_chat(update: Update) -> bool {
+    update
+        .chat()
+        .is_some_and(|chat| chat.is_group() || chat.is_supergroup())
+}
+
+// status cards shared with inline mode, they're not meant for the bot
+fn is_sent_via_me(msg: Message, me: Me) -> bool {
+    msg.via_bot.is_some_and(|bot| bot.id == me.id)
+}
+
+async fn ignore_message() -> HandlerResult {
     Ok(())
 }
 
-async fn default_handler(bot: Bot, _dialogue: MyDialogue, msg: Message) -> HandlerResult {
+fn get_update_language(cfg: ConfigParameters, update: Update) -> Language {
+    match update.from() {
+        Some(user) => user::detect_user_language(
+            cfg.redis_connection,
+            &user.id.to_string(),
+            user.language_code.as_deref(),
+        ),
+        None => Language::default(),
+    }
+}
+
+async fn group_handler(
+    bot: Bot,
+    _dialogue: MyDialogue,
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
+    bot.send_message(msg.chat.id, lang.get(Text::PrivateOnly))
+        .await?;
+    Ok(())
+}
+
+async fn default_handler(
+    cfg: ConfigParameters,
+    bot: Bot,
+    _dialogue: MyDialogue,
+    lang: Language,
+    msg: Message,
+) -> HandlerResult {
     log::info!("{:?}\n\n{:?}", _dialogue.get().await?.unwrap(), msg);
-    bot.send_message(
-        msg.chat.id,
-        "Didn't quite get that. Try /addmedication, /patients or /help!",
-    )
-    .await?;
+
+    if free_text_intake(cfg, bot.clone(), lang, msg.clone()).await? {
+        return Ok(());
+    }
+
+    bot.send_message(msg.chat.id, lang.get(Text::UnknownMessage))
+        .await?;
     Ok(())
 }
@@ -0,0 +1,499 @@
+use std::fmt::Display;
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use redis::{Commands, Connection, RedisError};
+use redis_macros::{FromRedisValue, ToRedisArgs};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{Language, Text};
+use crate::patient::Patient;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum MeasurementKind {
+    Temperature,
+    BloodPressure,
+    Glucose,
+    Weight,
+    Symptom,
+}
+
+impl MeasurementKind {
+    pub const ALL: [MeasurementKind; 5] = [
+        MeasurementKind::Temperature,
+        MeasurementKind::BloodPressure,
+        MeasurementKind::Glucose,
+        MeasurementKind::Weight,
+        MeasurementKind::Symptom,
+    ];
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        MeasurementKind::ALL
+            .into_iter()
+            .find(|k| k.to_string() == kind)
+    }
+
+    pub fn name(&self, lang: Language) -> &'static str {
+        match self {
+            MeasurementKind::Temperature => lang.get(Text::Temperature),
+            MeasurementKind::BloodPressure => lang.get(Text::BloodPressure),
+            MeasurementKind::Glucose => lang.get(Text::Glucose),
+            MeasurementKind::Weight => lang.get(Text::BodyWeight),
+            MeasurementKind::Symptom => lang.get(Text::Symptom),
+        }
+    }
+
+    pub fn example(&self, lang: Language) -> &'static str {
+        match self {
+            MeasurementKind::Temperature => "38.2",
+            MeasurementKind::BloodPressure => "120/80",
+            MeasurementKind::Glucose => "95 mg/dl, 5.3 mmol/l",
+            MeasurementKind::Weight => "12.5 kg",
+            MeasurementKind::Symptom => lang.get(Text::SymptomExample),
+        }
+    }
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            MeasurementKind::Temperature => "🌡️",
+            MeasurementKind::BloodPressure => "💓",
+            MeasurementKind::Glucose => "🩸",
+            MeasurementKind::Weight => "⚖️",
+            MeasurementKind::Symptom => "🤒",
+        }
+    }
+
+    /// Symptoms are free text, so they can't have a threshold.
+    pub fn has_threshold(&self) -> bool {
+        *self != MeasurementKind::Symptom
+    }
+}
+
+impl Display for MeasurementKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            MeasurementKind::Temperature => write!(f, "temperature"),
+            MeasurementKind::BloodPressure => write!(f, "blood_pressure"),
+            MeasurementKind::Glucose => write!(f, "glucose"),
+            MeasurementKind::Weight => write!(f, "weight"),
+            MeasurementKind::Symptom => write!(f, "symptom"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum MeasurementError {
+    Empty,
+    InvalidNumber,
+    OutOfRange,
+}
+
+impl MeasurementError {
+    pub fn print(&self, lang: Language) -> &'static str {
+        match self {
+            MeasurementError::Empty => lang.get(Text::MeasurementEmpty),
+            MeasurementError::InvalidNumber => lang.get(Text::MeasurementInvalidNumber),
+            MeasurementError::OutOfRange => lang.get(Text::MeasurementOutOfRange),
+        }
+    }
+}
+
+impl Display for MeasurementError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "{}", self.print(Language::English))
+    }
+}
+
+/// Temperatures in ºC, glucose in mg/dl, weights in kg.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum Reading {
+    Temperature(f64),
+    BloodPressure { systolic: f64, diastolic: f64 },
+    Glucose(f64),
+    Weight(f64),
+    Symptom(String),
+}
+
+impl Reading {
+    // 38.2, 38,2ºC, 101 F
+    // 120/80
+    // 95, 5.3 mmol/l
+    pub fn parse(kind: MeasurementKind, reading: &str) -> Result<Self, MeasurementError> {
+        let lower = reading.trim().to_lowercase();
+
+        if lower.is_empty() {
+            return Err(MeasurementError::Empty);
+        }
+
+        match kind {
+            MeasurementKind::Temperature => {
+                let (degrees, unit) = split_unit(&lower);
+                let celsius = to_standard_unit(kind, parse_number(degrees)?, unit)
+                    .ok_or(MeasurementError::InvalidNumber)?;
+
+                match celsius {
+                    c if (30.0..45.0).contains(&c) => Ok(Reading::Temperature(round(c))),
+                    _ => Err(MeasurementError::OutOfRange),
+                }
+            }
+            MeasurementKind::BloodPressure => {
+                let (systolic, diastolic) = lower
+                    .split_once('/')
+                    .ok_or(MeasurementError::InvalidNumber)?;
+                let systolic = parse_number(systolic)?;
+                let diastolic = parse_number(diastolic)?;
+
+                match systolic > diastolic && (40.0..300.0).contains(&systolic) && diastolic > 20.0
+                {
+                    true => Ok(Reading::BloodPressure {
+                        systolic,
+                        diastolic,
+                    }),
+                    false => Err(MeasurementError::OutOfRange),
+                }
+            }
+            MeasurementKind::Glucose => {
+                let (glucose, unit) = split_unit(&lower);
+                let mg_dl = to_standard_unit(kind, parse_number(glucose)?, unit)
+                    .ok_or(MeasurementError::InvalidNumber)?;
+
+                match mg_dl {
+                    g if (10.0..1000.0).contains(&g) => Ok(Reading::Glucose(round(g))),
+                    _ => Err(MeasurementError::OutOfRange),
+                }
+            }
+            MeasurementKind::Weight => Patient::parse_weight(&lower)
+                .map(Reading::Weight)
+                .ok_or(MeasurementError::InvalidNumber),
+            MeasurementKind::Symptom => Ok(Reading::Symptom(reading.trim().to_string())),
+        }
+    }
+
+    pub fn kind(&self) -> MeasurementKind {
+        match self {
+            Reading::Temperature(_) => MeasurementKind::Temperature,
+            Reading::BloodPressure { .. } => MeasurementKind::BloodPressure,
+            Reading::Glucose(_) => MeasurementKind::Glucose,
+            Reading::Weight(_) => MeasurementKind::Weight,
+            Reading::Symptom(_) => MeasurementKind::Symptom,
+        }
+    }
+
+    /// The value thresholds apply to, the systolic one for blood pressure.
+    pub fn get_value(&self) -> Option<f64> {
+        match self {
+            Reading::Temperature(value) | Reading::Glucose(value) | Reading::Weight(value) => {
+                Some(*value)
+            }
+            Reading::BloodPressure { systolic, .. } => Some(*systolic),
+            Reading::Symptom(_) => None,
+        }
+    }
+}
+
+impl Display for Reading {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            Reading::Temperature(celsius) => write!(f, "{} ºC", celsius),
+            Reading::BloodPressure {
+                systolic,
+                diastolic,
+            } => write!(f, "{}/{} mmHg", systolic, diastolic),
+            Reading::Glucose(mg_dl) => write!(f, "{} mg/dl", mg_dl),
+            Reading::Weight(kg) => write!(f, "{} kg", kg),
+            Reading::Symptom(symptom) => write!(f, "{}", symptom),
+        }
+    }
+}
+
+fn parse_number(text: &str) -> Result<f64, MeasurementError> {
+    let number: String = text
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.' || *c == ',')
+        .collect();
+
+    number
+        .replace(',', ".")
+        .parse::<f64>()
+        .map_err(|_| MeasurementError::InvalidNumber)
+}
+
+// "38,2ºc" into "38,2" and "ºc"
+fn split_unit(text: &str) -> (&str, &str) {
+    let end = text
+        .rfind(|c: char| c.is_ascii_digit())
+        .map_or(0, |i| i + 1);
+
+    (text[..end].trim(), text[end..].trim())
+}
+
+/// Converts a value written in `unit` to the one readings are stored in, None for units that
+/// can't be converted.
+fn to_standard_unit(kind: MeasurementKind, value: f64, unit: &str) -> Option<f64> {
+    match kind {
+        MeasurementKind::Temperature => match unit.trim_start_matches(['º', '°']) {
+            // nobody's body is at 50ºC, so those are in fahrenheit
+            "" if value > 50.0 => Some((value - 32.0) * 5.0 / 9.0),
+            "" | "c" => Some(value),
+            "f" => Some((value - 32.0) * 5.0 / 9.0),
+            _ => None,
+        },
+        MeasurementKind::Glucose => match unit {
+            "" | "mg/dl" => Some(value),
+            "mmol" | "mmol/l" => Some(value * 18.0),
+            _ => None,
+        },
+        MeasurementKind::BloodPressure => match unit {
+            "" | "mmhg" => Some(value),
+            _ => None,
+        },
+        MeasurementKind::Weight => match unit {
+            "" | "kg" | "kgs" => Some(value),
+            _ => None,
+        },
+        MeasurementKind::Symptom => None,
+    }
+}
+
+fn round(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+// "above 38" in every language
+const ABOVE: [&str; 10] = [
+    "above ",
+    "over ",
+    ">",
+    "más de ",
+    "mas de ",
+    "por encima de ",
+    "més de ",
+    "per sobre de ",
+    "mais de ",
+    "acima de ",
+];
+
+const BELOW: [&str; 8] = [
+    "below ",
+    "under ",
+    "<",
+    "menos de ",
+    "por debajo de ",
+    "menys de ",
+    "per sota de ",
+    "abaixo de ",
+];
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct Threshold {
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl Threshold {
+    // above 38
+    // below 70
+    // 70-180, 3.9-10 mmol/l
+    // in the units readings of `kind` are stored in, like them
+    pub fn parse(kind: MeasurementKind, threshold: &str) -> Option<Self> {
+        let lower = threshold.trim().to_lowercase();
+        let (values, unit) = split_unit(&lower);
+        let parse_value =
+            |value: &str| to_standard_unit(kind, parse_number(value).ok()?, unit).map(round);
+
+        if let Some(max) = ABOVE.iter().find_map(|prefix| values.strip_prefix(prefix)) {
+            return Some(Threshold {
+                min: None,
+                max: Some(parse_value(max)?),
+            });
+        }
+
+        if let Some(min) = BELOW.iter().find_map(|prefix| values.strip_prefix(prefix)) {
+            return Some(Threshold {
+                min: Some(parse_value(min)?),
+                max: None,
+            });
+        }
+
+        let (min, max) = values.split_once('-')?;
+        let (min, max) = (parse_value(min)?, parse_value(max)?);
+
+        match min < max {
+            true => Some(Threshold {
+                min: Some(min),
+                max: Some(max),
+            }),
+            false => None,
+        }
+    }
+
+    pub fn is_exceeded(&self, value: f64) -> bool {
+        self.min.is_some_and(|min| value < min) || self.max.is_some_and(|max| value > max)
+    }
+
+    pub fn print(&self, lang: Language) -> String {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => lang.fill(Text::Outside, &[("min", &min), ("max", &max)]),
+            (Some(min), None) => lang.fill(Text::Below, &[("min", &min)]),
+            (None, Some(max)) => lang.fill(Text::Above, &[("max", &max)]),
+            (None, None) => lang.get(Text::Never).to_string(),
+        }
+    }
+}
+
+impl Display for Threshold {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "{}", self.print(Language::English))
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
+pub struct Measurement {
+    pub reading: Reading,
+    pub user_id: String,
+    pub user_name: String,
+    pub taken_at: i64,
+}
+
+impl Measurement {
+    pub fn new(reading: Reading, user_id: String, user_name: String) -> Measurement {
+        Measurement {
+            reading,
+            user_id,
+            user_name,
+            taken_at: Utc::now().timestamp(),
+        }
+    }
+
+    pub fn save(
+        &self,
+        patient_id: &str,
+        connection: Arc<Mutex<Connection>>,
+    ) -> Result<(), RedisError> {
+        log::info!("saving measurement {:?}", self);
+
+        connection
+            .lock()
+            .unwrap()
+            .lpush::<String, &Measurement, ()>(
+                format!("medi:patient:{}:measurements", patient_id),
+                self,
+            )
+    }
+
+    /// The last `count` measurements of a patient, most recent first.
+    pub fn get_by_patient_id(
+        patient_id: &str,
+        count: isize,
+        connection: Arc<Mutex<Connection>>,
+    ) -> Result<Vec<Self>, RedisError> {
+        connection
+            .lock()
+            .unwrap()
+            .lrange::<String, Vec<Measurement>>(
+                format!("medi:patient:{}:measurements", patient_id),
+                0,
+                count - 1,
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reading() {
+        let cases = [
+            (
+                MeasurementKind::Temperature,
+                "38,2ºC",
+                Reading::Temperature(38.2),
+            ),
+            (
+                MeasurementKind::Temperature,
+                "101 F",
+                Reading::Temperature(38.3),
+            ),
+            (
+                MeasurementKind::BloodPressure,
+                "120/80",
+                Reading::BloodPressure {
+                    systolic: 120.0,
+                    diastolic: 80.0,
+                },
+            ),
+            (MeasurementKind::Glucose, "5 mmol/l", Reading::Glucose(90.0)),
+            (MeasurementKind::Weight, "12.5 kg", Reading::Weight(12.5)),
+        ];
+
+        for (kind, input, expected) in cases {
+            assert_eq!(
+                Reading::parse(kind, input),
+                Ok(expected),
+                "parsing {}",
+                input
+            );
+        }
+
+        assert_eq!(
+            Reading::parse(MeasurementKind::Temperature, "hot"),
+            Err(MeasurementError::InvalidNumber)
+        );
+        assert_eq!(
+            Reading::parse(MeasurementKind::BloodPressure, "80/120"),
+            Err(MeasurementError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_threshold() {
+        let fever = Threshold::parse(MeasurementKind::Temperature, "above 38").unwrap();
+        assert!(fever.is_exceeded(38.5));
+        assert!(!fever.is_exceeded(37.0));
+
+        let glucose = Threshold::parse(MeasurementKind::Glucose, "70-180").unwrap();
+        assert!(glucose.is_exceeded(60.0));
+        assert!(glucose.is_exceeded(200.0));
+        assert!(!glucose.is_exceeded(100.0));
+        assert_eq!(glucose.to_string(), "outside 70-180");
+
+        assert_eq!(Threshold::parse(MeasurementKind::Glucose, "180-70"), None);
+
+        let fiebre = Threshold::parse(MeasurementKind::Temperature, "más de 38").unwrap();
+        assert_eq!(fiebre, fever);
+        assert_eq!(fiebre.print(Language::Spanish), "por encima de 38");
+        assert_eq!(
+            Threshold::parse(MeasurementKind::Glucose, "per sota de 70")
+                .unwrap()
+                .print(Language::Catalan),
+            "per sota de 70"
+        );
+    }
+
+    #[test]
+    fn test_threshold_units() {
+        // stored like the readings, in mg/dl and ºC
+        let glucose = Threshold::parse(MeasurementKind::Glucose, "above 10 mmol/l").unwrap();
+        assert_eq!(glucose.to_string(), "above 180");
+        assert!(!glucose.is_exceeded(95.0));
+        assert!(glucose.is_exceeded(200.0));
+
+        let glucose = Threshold::parse(MeasurementKind::Glucose, "3.9-10 mmol/l").unwrap();
+        assert_eq!(glucose.to_string(), "outside 70.2-180");
+
+        let fever = Threshold::parse(MeasurementKind::Temperature, "above 100.4 F").unwrap();
+        assert_eq!(fever.to_string(), "above 38");
+        assert!(fever.is_exceeded(38.5));
+
+        assert_eq!(
+            Threshold::parse(MeasurementKind::Temperature, "above 100.4 ºF"),
+            Some(fever)
+        );
+        assert_eq!(
+            Threshold::parse(MeasurementKind::Glucose, "above 10 stones"),
+            None
+        );
+    }
+}
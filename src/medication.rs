@@ -4,27 +4,53 @@ use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
 use teloxide::types::InlineKeyboardButton;
 
-use crate::{frequency::Frequency, patient::Patient};
+use crate::{
+    catalog::lookup_ingredients,
+    dosage::{Dosage, Unit, WeightDose},
+    frequency::Frequency,
+    i18n::{Language, Text},
+    interactions::{find_interactions, Interaction},
+    inventory::Stock,
+    patient::Patient,
+    stats::{get_schedule, Adherence, Dose},
+    timezone::{add_local, print_patient_time},
+    waking_hours::WakingHours,
+};
 use redis::{Commands, Connection, RedisError};
 use redis_macros::{FromRedisValue, ToRedisArgs};
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
 pub struct Medication {
-    id: String,
+    pub id: String,
     pub patient_id: String,
     pub medicine: String,
-    pub dosage: String,
+    pub dosage: Dosage,
     frequency: Frequency,
     user_id: String,
     pub last_taken: Option<i64>,
     pub patient_name: Option<String>,
+    #[serde(default)]
+    stock: Option<Stock>,
+    #[serde(default)]
+    weight_dose: Option<WeightDose>,
+    #[serde(default)]
+    ingredients: Vec<String>,
+    // intakes in the last day of this and any of the patient's medications sharing an
+    // ingredient with it. Filled in when loading, never stored.
+    #[serde(skip)]
+    shared_intakes: Vec<i64>,
+    #[serde(skip)]
+    sharing_medicines: Vec<String>,
+    // the patient's waking hours and their timezone, filled in when loading
+    #[serde(skip)]
+    waking_hours: Option<(WakingHours, Tz)>,
 }
 
 impl Medication {
     pub fn new(
         patient_id: String,
         medicine: String,
-        dosage: String,
+        dosage: Dosage,
         frequency: Frequency,
         user_id: String,
     ) -> Medication {
@@ -37,6 +63,12 @@ impl Medication {
             user_id,
             last_taken: None,
             patient_name: None,
+            stock: None,
+            weight_dose: None,
+            ingredients: vec![],
+            shared_intakes: vec![],
+            sharing_medicines: vec![],
+            waking_hours: None,
         }
     }
 
@@ -61,7 +93,7 @@ impl Medication {
         };
 
         con.sadd::<String, String, ()>(
-            format!("medi:patient_meds:{}", self.patient_id.to_string()),
+            format!("medi:patient_meds:{}", self.patient_id),
             self.id.to_string(),
         )
         .expect("Error adding medication to patient set array");
@@ -69,21 +101,208 @@ impl Medication {
         Ok(())
     }
 
-    pub fn set_taken_now(&mut self, connection: Arc<Mutex<Connection>>) -> Result<(), RedisError> {
-        self.last_taken = Some(Utc::now().timestamp());
+    /// Registers an intake at `taken_at`, which can be in the past when it's logged afterwards.
+    pub fn set_taken_at(
+        &mut self,
+        taken_at: i64,
+        connection: Arc<Mutex<Connection>>,
+    ) -> Result<(), RedisError> {
+        self.last_taken = Some(self.last_taken.unwrap_or(taken_at).max(taken_at));
 
         connection
             .clone()
             .lock()
             .unwrap()
-            .lpush::<String, i64, ()>(
-                format!("medi:{}:taken", self.id),
-                self.last_taken.unwrap(),
-            )?;
+            .lpush::<String, i64, ()>(format!("medi:{}:taken", self.id), taken_at)?;
+
+        if let Some(stock) = self.stock.as_mut() {
+            stock.take(&self.dosage);
+        }
+
+        self.shared_intakes.push(taken_at);
 
         self.save(connection)
     }
 
+    /// A fixed dosage, replacing any weight based one.
+    pub fn set_dosage(&mut self, dosage: Dosage) {
+        self.dosage = dosage;
+        self.weight_dose = None;
+    }
+
+    pub fn set_weight_dose(&mut self, weight_dose: WeightDose, weight_kg: f64) {
+        self.dosage = weight_dose.get_dosage(weight_kg);
+        self.weight_dose = Some(weight_dose);
+    }
+
+    /// Recomputes the volume of a weight based dosage. Returns false for fixed dosages.
+    pub fn recalculate_dosage(&mut self, weight_kg: f64) -> bool {
+        match self.weight_dose.as_ref() {
+            Some(weight_dose) => {
+                self.dosage = weight_dose.get_dosage(weight_kg);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn print_max_dose_warning(&self, weight_kg: f64, lang: Language) -> Option<String> {
+        let weight_dose = self.weight_dose.as_ref()?;
+
+        if !weight_dose.exceeds_max(weight_kg) {
+            return None;
+        }
+
+        Some(lang.fill(
+            Text::MaxDoseWarning,
+            &[
+                // rounded like any other dosage
+                ("mg", &Dosage::new(weight_dose.get_mg(weight_kg), Unit::Mg)),
+                ("medicine", &self.medicine),
+                (
+                    "max",
+                    &Dosage::new(weight_dose.max_mg.unwrap_or_default(), Unit::Mg),
+                ),
+            ],
+        ))
+    }
+
+    pub fn set_frequency(&mut self, frequency: Frequency) {
+        self.frequency = frequency;
+    }
+
+    pub fn get_frequency(&self) -> &Frequency {
+        &self.frequency
+    }
+
+    /// Ingredients entered when adding the plan, otherwise from the bundled catalog, otherwise
+    /// the medicine itself.
+    pub fn get_ingredients(&self) -> Vec<String> {
+        if !self.ingredients.is_empty() {
+            return self.ingredients.clone();
+        }
+
+        lookup_ingredients(&self.medicine).unwrap_or(vec![self.medicine.to_lowercase()])
+    }
+
+    pub fn set_ingredients(&mut self, ingredients: Vec<String>) {
+        self.ingredients = ingredients;
+    }
+
+    pub fn shares_ingredient_with(&self, other: &Medication) -> bool {
+        let ingredients = self.get_ingredients();
+        other
+            .get_ingredients()
+            .iter()
+            .any(|ingredient| ingredients.contains(ingredient))
+    }
+
+    /// Known interactions between this medication and `others`, skipping itself.
+    pub fn find_interactions<'a>(
+        &self,
+        others: &'a [Medication],
+    ) -> Vec<(&'a Medication, &'static Interaction)> {
+        others
+            .iter()
+            .filter(|other| other.id != self.id)
+            .flat_map(|other| {
+                find_interactions(&self.get_ingredients(), &other.get_ingredients())
+                    .into_iter()
+                    .map(move |interaction| (other, interaction))
+            })
+            .collect()
+    }
+
+    pub fn print_interactions(&self, others: &[Medication], lang: Language) -> String {
+        let ingredients = self.get_ingredients();
+
+        let duplicates = others
+            .iter()
+            .filter(|other| other.id != self.id)
+            .filter_map(|other| {
+                let shared: Vec<String> = other
+                    .get_ingredients()
+                    .into_iter()
+                    .filter(|ingredient| ingredients.contains(ingredient))
+                    .collect();
+
+                if shared.is_empty() {
+                    None
+                } else {
+                    Some(
+                        lang.fill(
+                            Text::SharedIngredients,
+                            &[
+                                ("medicine", &self.medicine),
+                                ("other", &other.medicine),
+                                ("ingredients", &shared.join(", ")),
+                            ],
+                        ) + "\n",
+                    )
+                }
+            })
+            .collect::<String>();
+
+        let interactions = self
+            .find_interactions(others)
+            .iter()
+            .map(|(other, interaction)| interaction.print(&self.medicine, &other.medicine) + "\n")
+            .collect::<String>();
+
+        duplicates + &interactions
+    }
+
+    /// Interactions between every pair of `medications`, each pair reported once.
+    pub fn print_all_interactions(medications: &[Medication], lang: Language) -> String {
+        medications
+            .iter()
+            .enumerate()
+            .map(|(i, medication)| medication.print_interactions(&medications[i + 1..], lang))
+            .collect::<String>()
+    }
+
+    pub fn get_stock(&self) -> Option<&Stock> {
+        self.stock.as_ref()
+    }
+
+    pub fn set_stock(&mut self, stock: Stock) {
+        self.stock = Some(stock);
+    }
+
+    /// Returns true the first time the stock drops under its alert threshold, and remembers
+    /// the alert was sent. The medication needs saving afterwards.
+    pub fn should_alert_low_stock(&mut self) -> bool {
+        let minutes = self.frequency.get_minutes();
+        match self.stock.as_mut() {
+            Some(stock) => stock.should_alert(&self.dosage, minutes),
+            None => false,
+        }
+    }
+
+    pub fn print_stock(&self, tz: &str, lang: Language) -> Option<String> {
+        let stock = self.stock.as_ref()?;
+
+        match stock.days_left(&self.dosage, self.frequency.get_minutes()) {
+            None => Some(stock.to_string()),
+            Some(days) => {
+                let runs_out = Utc::now() + TimeDelta::minutes((days * 24.0 * 60.0) as i64);
+                let date = match tz.parse::<Tz>() {
+                    Err(_) => runs_out.format("%Y-%m-%d").to_string(),
+                    Ok(tz) => runs_out.with_timezone(&tz).format("%Y-%m-%d").to_string(),
+                };
+
+                Some(lang.fill(
+                    Text::StockLeft,
+                    &[
+                        ("stock", &stock),
+                        ("days", &format!("{:.1}", days)),
+                        ("date", &date),
+                    ],
+                ))
+            }
+        }
+    }
+
     pub fn get_medication_log(
         &self,
         connection: Arc<Mutex<Connection>>,
@@ -95,12 +314,179 @@ impl Medication {
             .lrange::<String, Vec<i64>>(format!("medi:{}:taken", self.id), 0, 10)
     }
 
+    /// Every intake since the plan was added, most recent first.
+    pub fn get_intake_history(
+        &self,
+        connection: Arc<Mutex<Connection>>,
+    ) -> Result<Vec<i64>, RedisError> {
+        connection.lock().unwrap().lrange::<String, Vec<i64>>(
+            format!("medi:{}:taken", self.id),
+            0,
+            -1,
+        )
+    }
+
+    // doses are due when reminders say so, waking hours and timezone included
+    fn get_next_due_timestamp(&self, last: i64) -> i64 {
+        self.get_next_dose_after(DateTime::from_timestamp(last, 0).unwrap())
+            .timestamp()
+    }
+
+    /// How well the plan was followed over the last `days`, None if it hasn't started yet.
+    pub fn get_adherence(&self, intakes: &[i64], days: i64) -> Option<Adherence> {
+        if self.frequency.get_minutes() <= 0 {
+            return None;
+        }
+
+        let now = Utc::now();
+
+        Adherence::compute(
+            intakes,
+            |last| self.get_next_due_timestamp(last),
+            (now - TimeDelta::days(days)).timestamp(),
+            now.timestamp(),
+        )
+    }
+
+    /// Doses that were due over the last `days` and the intakes that covered them.
+    pub fn get_schedule(&self, intakes: &[i64], days: i64) -> Option<Vec<Dose>> {
+        self.get_schedule_since(intakes, Utc::now() - TimeDelta::days(days))
+    }
+
+    pub fn get_schedule_since(&self, intakes: &[i64], from: DateTime<Utc>) -> Option<Vec<Dose>> {
+        if self.frequency.get_minutes() <= 0 {
+            return None;
+        }
+
+        get_schedule(
+            intakes,
+            |last| self.get_next_due_timestamp(last),
+            from.timestamp(),
+            Utc::now().timestamp(),
+        )
+    }
+
+    fn get_recent_intakes(&self, connection: Arc<Mutex<Connection>>) -> Vec<i64> {
+        let since = (Utc::now() - TimeDelta::days(1)).timestamp();
+
+        connection
+            .lock()
+            .unwrap()
+            .lrange::<String, Vec<i64>>(format!("medi:{}:taken", self.id), 0, 49)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|ts| *ts > since)
+            .collect()
+    }
+
+    fn load_shared_intakes(medications: &mut [Medication], con: Arc<Mutex<Connection>>) {
+        let intakes: Vec<Vec<i64>> = medications
+            .iter()
+            .map(|m| m.get_recent_intakes(con.clone()))
+            .collect();
+
+        let shared: Vec<(Vec<i64>, Vec<String>)> = medications
+            .iter()
+            .enumerate()
+            .map(|(i, medication)| {
+                let mut shared_intakes = intakes[i].clone();
+                let mut sharing_medicines = vec![];
+
+                for (j, other) in medications.iter().enumerate() {
+                    if i != j && medication.shares_ingredient_with(other) {
+                        shared_intakes.extend(&intakes[j]);
+                        sharing_medicines.push(other.medicine.clone());
+                    }
+                }
+
+                (shared_intakes, sharing_medicines)
+            })
+            .collect();
+
+        for (medication, (shared_intakes, sharing_medicines)) in medications.iter_mut().zip(shared)
+        {
+            medication.shared_intakes = shared_intakes;
+            medication.sharing_medicines = sharing_medicines;
+        }
+    }
+
+    /// Plans taken a number of times a day are spread over the patient's waking hours.
+    pub fn follows_waking_hours(&self) -> bool {
+        self.waking_hours.is_some() && self.frequency.get_times_a_day().is_some()
+    }
+
+    /// When the dose after one given at `last` is due, the next morning when it would be due
+    /// while the patient is asleep. Plans following the patient's clock rather than a number
+    /// of hours are computed in their timezone, so they don't move across daylight saving.
+    pub fn get_next_dose_after(&self, last: DateTime<Utc>) -> DateTime<Utc> {
+        match (self.waking_hours.as_ref(), self.frequency.get_times_a_day()) {
+            (Some((waking_hours, tz)), Some(times)) => {
+                waking_hours.clamp(add_local(last, waking_hours.get_gap(times), tz), tz)
+            }
+            (Some((_, tz)), None) if self.frequency.keeps_time_of_day() => {
+                add_local(last, self.frequency.get_interval(), tz)
+            }
+            _ => last + self.frequency.get_interval(),
+        }
+    }
+
+    /// Doses due from the next one until `until`.
+    pub fn get_due_times(&self, until: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        if self.frequency.get_minutes() <= 0 {
+            return vec![];
+        }
+
+        std::iter::successors(Some(self.get_can_take_next_date()), |due| {
+            Some(self.get_next_dose_after(*due))
+        })
+        .take_while(|due| *due < until)
+        .collect()
+    }
+
+    /// When the next dose is allowed, applying the interval since the last intake of any
+    /// medication sharing an ingredient, and the daily maximum the interval implies.
+    fn get_next_allowed_date(&self) -> Option<DateTime<Utc>> {
+        let minutes = self.frequency.get_minutes();
+        let last = self
+            .shared_intakes
+            .iter()
+            .copied()
+            .chain(self.last_taken)
+            .max()?;
+
+        let mut next = self.get_next_dose_after(DateTime::from_timestamp(last, 0).unwrap());
+
+        if minutes > 0 && minutes < 24 * 60 {
+            let max_per_day = match self.frequency.get_times_a_day() {
+                Some(times) => times as usize,
+                None => (24 * 60 / minutes) as usize,
+            };
+            let since = (Utc::now() - TimeDelta::days(1)).timestamp();
+
+            let mut recent: Vec<i64> = self
+                .shared_intakes
+                .iter()
+                .copied()
+                .filter(|ts| *ts > since)
+                .collect();
+            recent.sort();
+            recent.dedup();
+            recent.reverse();
+
+            if recent.len() >= max_per_day {
+                let frees_up = DateTime::from_timestamp(recent[max_per_day - 1], 0).unwrap()
+                    + TimeDelta::days(1);
+                next = next.max(frees_up);
+            }
+        }
+
+        Some(next)
+    }
+
     pub fn can_take(&self) -> bool {
-        if self.last_taken.is_none() {
-            true
-        } else {
-            let lt = DateTime::from_timestamp(self.last_taken.unwrap(), 0).unwrap();
-            lt + TimeDelta::hours(self.frequency.get_hours().into()) < Utc::now()
+        match self.get_next_allowed_date() {
+            None => true,
+            Some(next) => next < Utc::now(),
         }
     }
 
@@ -113,89 +499,123 @@ impl Medication {
     }
 
     pub fn get_can_take_next_date(&self) -> DateTime<Utc> {
-        if self.can_take() {
-            Utc::now()
-        } else {
-            let next_take = DateTime::from_timestamp(self.last_taken.unwrap(), 0).unwrap()
-                + TimeDelta::hours(self.frequency.get_hours().into());
-
-            next_take
+        match self.get_next_allowed_date() {
+            Some(next) if next >= Utc::now() => next,
+            _ => Utc::now(),
         }
     }
 
-    pub fn print_can_take_next(&self, tz: &str) -> String {
+    pub fn print_can_take_next(&self, tz: &str, lang: Language) -> String {
         if self.can_take() {
-            "Right now".to_string()
+            lang.get(Text::RightNow).to_string()
         } else {
             let next_take = self.get_can_take_next_date();
             let now = Utc::now();
             let dif = next_take - now;
             let delta = if dif.num_hours() > 0 {
-                format!(
-                    "in {} hours and {} minutes",
-                    dif.num_hours().to_string(),
-                    (dif.checked_sub(&TimeDelta::hours(dif.num_hours())))
-                        .unwrap()
-                        .num_minutes()
+                lang.fill(
+                    Text::InHoursAndMinutes,
+                    &[
+                        ("hours", &dif.num_hours()),
+                        (
+                            "minutes",
+                            &(dif.checked_sub(&TimeDelta::hours(dif.num_hours())))
+                                .unwrap()
+                                .num_minutes(),
+                        ),
+                    ],
                 )
             } else {
-                format!("in {} minutes", dif.num_minutes().to_string())
+                lang.fill(Text::InMinutes, &[("minutes", &dif.num_minutes())])
             };
-            match tz.parse::<Tz>() {
-                Err(_) => format!("{} ({})", next_take.to_string(), delta),
-                Ok(tz) => {
-                    let dt = next_take.with_timezone(&tz);
-                    format!("{} ({})", dt.to_string(), delta)
-                }
+            format!("{} ({})", self.print_date(next_take, tz, lang), delta)
+        }
+    }
+
+    // in the patient's timezone, next to the viewer's own when they live somewhere else
+    fn print_date(&self, date: DateTime<Utc>, tz: &str, lang: Language) -> String {
+        match tz.parse::<Tz>() {
+            Err(_) => date.to_string(),
+            Ok(tz) => {
+                let patient_tz = self.waking_hours.map_or(tz, |(_, patient_tz)| patient_tz);
+                print_patient_time(date, "%Y-%m-%d %H:%M:%S %Z", &patient_tz, &tz, lang)
             }
         }
     }
 
-    pub fn print_in_list(&self, tz: &str) -> String {
+    pub fn print_in_list(&self, tz: &str, lang: Language) -> String {
         let can_take = if self.can_take() { "✅" } else { "🙅" };
 
-        format!(
-            "{} ({}) - {}. Last taken: {}. Can take next: {} {}.",
-            self.medicine,
-            self.dosage,
-            self.frequency, // TODO implement display
-            self.print_last_taken(tz),
-            self.print_can_take_next(tz),
-            can_take
-        )
+        let dosage = match self.weight_dose.as_ref() {
+            Some(weight_dose) => format!("{}, {} mg/kg", self.dosage, weight_dose.mg_per_kg),
+            None => self.dosage.to_string(),
+        };
+
+        let stock = match self.print_stock(tz, lang) {
+            Some(stock) => lang.fill(Text::MedicationStock, &[("stock", &stock)]),
+            None => "".to_string(),
+        };
+
+        let sharing = if self.sharing_medicines.is_empty() {
+            "".to_string()
+        } else {
+            lang.fill(
+                Text::MedicationSharing,
+                &[("medicines", &self.sharing_medicines.join(", "))],
+            )
+        };
+
+        lang.fill(
+            Text::MedicationInList,
+            &[
+                ("medicine", &self.medicine),
+                ("dosage", &dosage),
+                ("frequency", &self.frequency.print(lang)),
+                ("last_taken", &self.print_last_taken(tz, lang)),
+                ("next", &self.print_can_take_next(tz, lang)),
+                ("emoji", &can_take),
+            ],
+        ) + &stock
+            + &sharing
     }
 
-    pub fn print_last_taken(&self, tz: &str) -> String {
+    pub fn print_last_taken(&self, tz: &str, lang: Language) -> String {
         match self.last_taken {
-            None => "Not yet".to_string(),
+            None => lang.get(Text::NotYet).to_string(),
             Some(ts) => {
                 let date = DateTime::from_timestamp(ts, 0).unwrap();
                 let now = Utc::now();
                 let dif = now - date;
                 match dif.num_hours() {
-                    h if h > 0 && h < 24 => format!("{} hours ago", dif.num_hours()),
+                    h if h > 0 && h < 24 => lang.fill(Text::HoursAgo, &[("hours", &h)]),
                     h if h == 0 && dif.num_minutes() > 0 => {
-                        format!("{} minutes ago", dif.num_minutes())
+                        lang.fill(Text::MinutesAgo, &[("minutes", &dif.num_minutes())])
                     }
-                    _ if dif.num_minutes() == 0 => format!("Just now"),
-                    _ => match tz.parse::<Tz>() {
-                        Err(_) => date.to_string(),
-                        Ok(tz) => {
-                            let dt = date.with_timezone(&tz);
-                            dt.to_string()
-                        }
-                    },
+                    _ if dif.num_minutes() == 0 => lang.get(Text::JustNow).to_string(),
+                    _ => self.print_date(date, tz, lang),
                 }
             }
         }
     }
 
-    pub fn get_by_id(id: &str, con: Arc<Mutex<Connection>>) -> Result<Self, RedisError> {
+    fn get_stored_by_id(id: &str, con: Arc<Mutex<Connection>>) -> Result<Self, RedisError> {
         con.lock()
             .unwrap()
             .get::<String, Medication>(format!("medi:{}", id))
     }
 
+    pub fn get_by_id(id: &str, con: Arc<Mutex<Connection>>) -> Result<Self, RedisError> {
+        let medication = Medication::get_stored_by_id(id, con.clone())?;
+
+        // the intakes of the patient's other medications are needed for can_take
+        Ok(
+            Medication::get_all_by_patient_id(&medication.patient_id, con)
+                .into_iter()
+                .find(|m| m.id == medication.id)
+                .unwrap_or(medication),
+        )
+    }
+
     pub fn get_all_by_patient_id(patient_id: &str, con: Arc<Mutex<Connection>>) -> Vec<Medication> {
         let ids = con
             .lock()
@@ -203,15 +623,29 @@ impl Medication {
             .smembers::<String, Vec<String>>(format!("medi:patient_meds:{}", patient_id))
             .unwrap();
 
-        ids.into_iter()
-            .map(|id| Medication::get_by_id(&id, con.clone()))
-            .filter_map(|m| if m.is_ok() { Some(m.unwrap()) } else { None })
-            .collect::<Vec<Medication>>()
+        let mut medications = ids
+            .into_iter()
+            .map(|id| Medication::get_stored_by_id(&id, con.clone()))
+            .filter_map(|m| m.ok())
+            .collect::<Vec<Medication>>();
+
+        Medication::load_shared_intakes(&mut medications, con.clone());
+
+        if let Ok(patient) = Patient::get_by_id(patient_id, con.clone()) {
+            let waking_hours = (patient.get_waking_hours(), patient.get_timezone(con));
+
+            for medication in medications.iter_mut() {
+                medication.waking_hours = Some(waking_hours);
+            }
+        }
+
+        medications
     }
 
     pub fn generate_medication_keyboard(
         patient_id: &str,
         con: Arc<Mutex<Connection>>,
+        lang: Language,
     ) -> Vec<Vec<InlineKeyboardButton>> {
         let mut keyboard: Vec<Vec<InlineKeyboardButton>> = vec![];
 
@@ -232,7 +666,7 @@ impl Medication {
         }
 
         keyboard.push(vec![InlineKeyboardButton::callback(
-            "Cancel".to_string(),
+            lang.get(Text::Cancel).to_string(),
             "cancel".to_string(),
         )]);
 
@@ -256,9 +690,9 @@ impl Medication {
 */
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
+    use chrono::TimeZone;
 
     fn create_redis_connection() -> redis::Connection {
         // creating a real connection actually
@@ -284,8 +718,8 @@ mod tests {
         let mut medication = Medication::new(
             patient.id.clone(),
             "nurofen".to_string(),
-            "5ml".to_string(),
-            Frequency::new(3),
+            Dosage::parse("5ml").unwrap(),
+            Frequency::new(3 * 60),
             user_id.clone(),
         );
 
@@ -307,4 +741,219 @@ mod tests {
             1
         );
     }
+
+    #[test]
+    fn test_max_dose_warning() {
+        let mut medication = Medication::new(
+            "patient".to_string(),
+            "Calpol".to_string(),
+            Dosage::parse("5ml").unwrap(),
+            Frequency::new(6 * 60),
+            "user".to_string(),
+        );
+        let mut weight_dose = WeightDose::new(13.3, 120.0, 5.0);
+        weight_dose.max_mg = Some(500.0);
+        medication.set_weight_dose(weight_dose, 40.1);
+
+        assert_eq!(
+            medication.print_max_dose_warning(30.0, Language::English),
+            None
+        );
+        assert_eq!(
+            medication.print_max_dose_warning(40.1, Language::English),
+            Some("⚠️ 533.33 mg of Calpol is above its maximum single dose of 500 mg!".to_string())
+        );
+    }
+
+    #[test]
+    fn test_medication_interactions() {
+        let medication = |medicine: &str| {
+            Medication::new(
+                "patient".to_string(),
+                medicine.to_string(),
+                Dosage::parse("5ml").unwrap(),
+                Frequency::new(6 * 60),
+                "user".to_string(),
+            )
+        };
+
+        let medications = vec![
+            medication("Ibuprofen"),
+            medication("Cetirizine"),
+            medication("Warfarin"),
+        ];
+
+        assert_eq!(medications[1].find_interactions(&medications).len(), 0);
+        assert_eq!(medications[0].find_interactions(&medications).len(), 1);
+        assert_eq!(
+            Medication::print_all_interactions(&medications, Language::English)
+                .lines()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_can_take_shared_ingredient() {
+        let user_id = uuid::Uuid::new_v4().to_string();
+        let patient = Patient::new("xavi".to_string(), user_id.clone());
+        let redis_con = Arc::new(Mutex::new(create_redis_connection()));
+        patient.save(redis_con.clone()).unwrap();
+
+        let mut calpol = Medication::new(
+            patient.id.clone(),
+            "calpol".to_string(),
+            Dosage::parse("5ml").unwrap(),
+            Frequency::new(4 * 60),
+            user_id.clone(),
+        );
+        let mut lemsip = Medication::new(
+            patient.id.clone(),
+            "lemsip".to_string(),
+            Dosage::Text("1 sachet".to_string()),
+            Frequency::new(6 * 60),
+            user_id.clone(),
+        );
+        let mut nurofen = Medication::new(
+            patient.id.clone(),
+            "nurofen".to_string(),
+            Dosage::parse("5ml").unwrap(),
+            Frequency::new(8 * 60),
+            user_id.clone(),
+        );
+
+        calpol.save(redis_con.clone()).unwrap();
+        lemsip.save(redis_con.clone()).unwrap();
+        nurofen.save(redis_con.clone()).unwrap();
+
+        calpol
+            .set_taken_at(Utc::now().timestamp(), redis_con.clone())
+            .unwrap();
+
+        let lemsip = Medication::get_by_id(&lemsip.id, redis_con.clone()).unwrap();
+        let nurofen = Medication::get_by_id(&nurofen.id, redis_con.clone()).unwrap();
+
+        assert!(!lemsip.can_take());
+        assert!(nurofen.can_take());
+    }
+
+    #[test]
+    fn test_next_dose_follows_waking_hours() {
+        let tz: Tz = "Europe/Madrid".parse().unwrap();
+        let mut calpol = Medication::new(
+            "patient".to_string(),
+            "calpol".to_string(),
+            Dosage::parse("5ml").unwrap(),
+            Frequency::parse("3 times a day").unwrap(),
+            "user".to_string(),
+        );
+        calpol.waking_hours = Some((WakingHours::default(), tz));
+
+        let doses = std::iter::successors(
+            Some(
+                tz.with_ymd_and_hms(2024, 11, 20, 7, 0, 0)
+                    .unwrap()
+                    .with_timezone(&Utc),
+            ),
+            |due| Some(calpol.get_next_dose_after(*due)),
+        )
+        .take(4)
+        .map(|due| due.with_timezone(&tz).format("%d %H:%M").to_string())
+        .collect::<Vec<String>>();
+
+        assert_eq!(doses, vec!["20 07:00", "20 14:00", "20 21:00", "21 07:00"]);
+    }
+
+    #[test]
+    fn test_adherence_follows_waking_hours() {
+        let tz = Tz::Europe__Madrid;
+        let mut calpol = Medication::new(
+            "patient".to_string(),
+            "calpol".to_string(),
+            Dosage::parse("5ml").unwrap(),
+            Frequency::parse("5 times a day").unwrap(),
+            "user".to_string(),
+        );
+        calpol.waking_hours = Some((WakingHours::default(), tz));
+
+        // every dose of the last three days right when it was due, from 07:00 to 21:00
+        let today = Utc::now().with_timezone(&tz).date_naive();
+        let intakes: Vec<i64> = (1..=3)
+            .flat_map(|days_ago| {
+                let date = today - TimeDelta::days(days_ago);
+                ["07:00", "10:30", "14:00", "17:30", "21:00"]
+                    .into_iter()
+                    .map(move |time| {
+                        let time = chrono::NaiveTime::parse_from_str(time, "%H:%M").unwrap();
+                        tz.from_local_datetime(&date.and_time(time))
+                            .unwrap()
+                            .timestamp()
+                    })
+            })
+            .collect();
+
+        let doses = calpol.get_schedule(&intakes, 2).unwrap();
+        assert!(!doses.is_empty());
+        assert!(doses.iter().all(|dose| dose.taken == Some(dose.due)));
+
+        let adherence = calpol.get_adherence(&intakes, 2).unwrap();
+        assert_eq!((adherence.late, adherence.missed), (0, 0));
+    }
+
+    #[test]
+    fn test_next_dose_across_daylight_saving() {
+        let next_doses = |frequency: &str, tz: Tz, first: (u32, u32, u32)| {
+            let mut medication = Medication::new(
+                "patient".to_string(),
+                "calpol".to_string(),
+                Dosage::parse("5ml").unwrap(),
+                Frequency::parse(frequency).unwrap(),
+                "user".to_string(),
+            );
+            medication.waking_hours = Some((WakingHours::default(), tz));
+
+            let (month, day, hour) = first;
+            std::iter::successors(
+                Some(
+                    tz.with_ymd_and_hms(2024, month, day, hour, 0, 0)
+                        .unwrap()
+                        .with_timezone(&Utc),
+                ),
+                |due| Some(medication.get_next_dose_after(*due)),
+            )
+            .take(3)
+            .map(|due| due.with_timezone(&tz).format("%d %H:%M").to_string())
+            .collect::<Vec<String>>()
+        };
+
+        // daily plans keep their time when springing forward and falling back
+        assert_eq!(
+            next_doses("every day", Tz::Europe__Madrid, (3, 30, 9)),
+            vec!["30 09:00", "31 09:00", "01 09:00"]
+        );
+        assert_eq!(
+            next_doses("every day", Tz::Europe__Madrid, (10, 26, 9)),
+            vec!["26 09:00", "27 09:00", "28 09:00"]
+        );
+        assert_eq!(
+            next_doses("every other day", Tz::America__New_York, (11, 2, 20)),
+            vec!["02 20:00", "04 20:00", "06 20:00"]
+        );
+        assert_eq!(
+            next_doses("every day", Tz::Australia__Sydney, (10, 5, 8)),
+            vec!["05 08:00", "06 08:00", "07 08:00"]
+        );
+
+        // so do plans spread over the waking hours
+        assert_eq!(
+            next_doses("3 times a day", Tz::America__New_York, (3, 9, 21)),
+            vec!["09 21:00", "10 07:00", "10 14:00"]
+        );
+
+        // while intervals in hours are hours, whatever the clock says
+        assert_eq!(
+            next_doses("every 12 hours", Tz::Europe__Madrid, (3, 30, 21)),
+            vec!["30 21:00", "31 10:00", "31 22:00"]
+        );
+    }
 }
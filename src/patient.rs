@@ -1,16 +1,83 @@
+use std::collections::HashMap;
+use std::fmt::Display;
 use std::sync::{Arc, Mutex};
 
+use chrono_tz::Tz;
 use redis::{Commands, Connection, RedisError};
 use redis_macros::{FromRedisValue, ToRedisArgs};
 use serde::{Deserialize, Serialize};
 use teloxide::types::InlineKeyboardButton;
 
+use crate::i18n::{Language, Text};
+use crate::measurement::{MeasurementKind, Threshold};
+use crate::user::get_user_timezone;
+use crate::waking_hours::WakingHours;
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
 pub struct Patient {
     pub id: String,
     pub name: String,
     creator_user_id: String,
     shared_with: Vec<String>,
+    // users in shared_with without an entry here were shared before roles existed: caregivers
+    #[serde(default)]
+    roles: HashMap<String, Role>,
+    // shared user that has been offered ownership but hasn't accepted yet
+    #[serde(default)]
+    pending_owner: Option<String>,
+    #[serde(default)]
+    pub weight_kg: Option<f64>,
+    // caregivers are notified of measurements outside these
+    #[serde(default)]
+    thresholds: HashMap<MeasurementKind, Threshold>,
+    #[serde(default)]
+    waking_hours: Option<WakingHours>,
+    // for patients living somewhere else than their owner, IANA name
+    #[serde(default)]
+    timezone: Option<String>,
+    // group chats following the patient, ids as strings like users
+    #[serde(default)]
+    groups: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum Role {
+    Owner,
+    Caregiver,
+    Viewer,
+}
+
+impl Role {
+    pub fn parse(role: &str) -> Option<Self> {
+        match role {
+            "owner" => Some(Role::Owner),
+            "caregiver" => Some(Role::Caregiver),
+            "viewer" => Some(Role::Viewer),
+            _ => None,
+        }
+    }
+
+    pub fn can_edit(&self) -> bool {
+        *self != Role::Viewer
+    }
+
+    pub fn print(&self, lang: Language) -> &'static str {
+        match self {
+            Role::Owner => lang.get(Text::RoleOwner),
+            Role::Caregiver => lang.get(Text::RoleCaregiver),
+            Role::Viewer => lang.get(Text::RoleViewer),
+        }
+    }
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            Role::Owner => write!(f, "owner"),
+            Role::Caregiver => write!(f, "caregiver"),
+            Role::Viewer => write!(f, "viewer"),
+        }
+    }
 }
 
 impl Patient {
@@ -20,6 +87,13 @@ impl Patient {
             name,
             creator_user_id: user_id,
             shared_with: vec![],
+            roles: HashMap::new(),
+            pending_owner: None,
+            weight_kg: None,
+            thresholds: HashMap::new(),
+            waking_hours: None,
+            timezone: None,
+            groups: vec![],
         }
     }
 
@@ -38,7 +112,7 @@ impl Patient {
         };
 
         con.sadd::<String, String, ()>(
-            format!("medi:user_patient:{}", self.creator_user_id.to_string()),
+            format!("medi:user_patient:{}", self.creator_user_id),
             self.id.to_string(),
         )
         .expect("Error adding new patient to user set array");
@@ -53,14 +127,21 @@ impl Patient {
         con.del::<String, ()>(format!("medi:patient:{}", self.id))
             .expect("Error deleting patient on del");
 
-        for user_id in self.shared_with.iter() {
+        for user_id in self.get_all_shared_users().iter() {
             con.srem::<String, String, ()>(
-                format!("medi:user_patient:{}", user_id.to_string()),
+                format!("medi:user_patient:{}", user_id),
                 self.id.to_string(),
             )
             .expect("Error removing patient from user set array");
         }
 
+        for chat_id in self.groups.iter() {
+            con.srem::<String, String, ()>(
+                format!("medi:group_patient:{}", chat_id),
+                self.id.to_string(),
+            )?;
+        }
+
         Ok(())
     }
 
@@ -83,19 +164,44 @@ impl Patient {
         Ok(ids
             .into_iter()
             .map(|id| Patient::get_by_id(&id, con.clone()))
-            .filter_map(|m| if m.is_ok() { Some(m.unwrap()) } else { None })
+            .filter_map(|m| m.ok())
             .collect::<Vec<Patient>>())
     }
 
+    /// Patients linked to a group chat.
+    pub fn get_group_patients(
+        chat_id: &str,
+        con: Arc<Mutex<Connection>>,
+    ) -> Result<Vec<Self>, RedisError> {
+        let ids = con
+            .lock()
+            .unwrap()
+            .smembers::<String, Vec<String>>(format!("medi:group_patient:{}", chat_id))?;
+
+        Ok(ids
+            .into_iter()
+            .filter_map(|id| Patient::get_by_id(&id, con.clone()).ok())
+            .collect())
+    }
+
     pub fn share(
         &mut self,
         telegram_user_id: u64,
         con: Arc<Mutex<Connection>>,
+    ) -> Result<(), RedisError> {
+        self.share_with_role(telegram_user_id, Role::Caregiver, con)
+    }
+
+    pub fn share_with_role(
+        &mut self,
+        telegram_user_id: u64,
+        role: Role,
+        con: Arc<Mutex<Connection>>,
     ) -> Result<(), RedisError> {
         con.lock()
             .unwrap()
             .sadd::<String, String, ()>(
-                format!("medi:user_patient:{}", telegram_user_id.to_string()),
+                format!("medi:user_patient:{}", telegram_user_id),
                 self.id.to_string(),
             )
             .expect("Error adding new patient to user set array");
@@ -103,8 +209,9 @@ impl Patient {
         let str_telegram_id = telegram_user_id.to_string();
 
         if !self.shared_with.contains(&str_telegram_id) {
-            self.shared_with.push(str_telegram_id);
+            self.shared_with.push(str_telegram_id.clone());
         }
+        self.roles.insert(str_telegram_id, role);
 
         Ok(())
     }
@@ -113,16 +220,181 @@ impl Patient {
         &self.shared_with
     }
 
+    pub fn get_role(&self, user_id: &str) -> Option<Role> {
+        if self.creator_user_id == user_id {
+            Some(Role::Owner)
+        } else if self.shared_with.iter().any(|id| id == user_id) {
+            Some(*self.roles.get(user_id).unwrap_or(&Role::Caregiver))
+        } else {
+            None
+        }
+    }
+
+    pub fn can_edit(&self, user_id: &str) -> bool {
+        self.get_role(user_id).is_some_and(|role| role.can_edit())
+    }
+
+    pub fn is_owner(&self, user_id: &str) -> bool {
+        self.creator_user_id == user_id
+    }
+
+    pub fn get_owner(&self) -> &str {
+        &self.creator_user_id
+    }
+
+    pub fn get_pending_owner(&self) -> Option<&String> {
+        self.pending_owner.as_ref()
+    }
+
+    /// Offers ownership to one of the users the patient is shared with. Returns false if the
+    /// user doesn't have access to the patient yet.
+    pub fn request_transfer(&mut self, user_id: &str) -> bool {
+        if !self.shared_with.iter().any(|id| id == user_id) {
+            return false;
+        }
+
+        self.pending_owner = Some(user_id.to_string());
+        true
+    }
+
+    pub fn cancel_transfer(&mut self) {
+        self.pending_owner = None;
+    }
+
+    /// Makes the pending owner the owner of the patient, the previous owner keeps access as a
+    /// caregiver. Returns false if there's no transfer pending for this user.
+    pub fn accept_transfer(
+        &mut self,
+        user_id: &str,
+        con: Arc<Mutex<Connection>>,
+    ) -> Result<bool, RedisError> {
+        if self.pending_owner.as_deref() != Some(user_id) {
+            return Ok(false);
+        }
+
+        let previous_owner = std::mem::replace(&mut self.creator_user_id, user_id.to_string());
+
+        self.shared_with.retain(|id| id != user_id);
+        self.roles.remove(user_id);
+        self.shared_with.push(previous_owner.clone());
+        self.roles.insert(previous_owner, Role::Caregiver);
+        self.pending_owner = None;
+
+        con.lock().unwrap().sadd::<String, String, ()>(
+            format!("medi:user_patient:{}", user_id),
+            self.id.to_string(),
+        )?;
+
+        Ok(true)
+    }
+
+    pub fn get_threshold(&self, kind: MeasurementKind) -> Option<&Threshold> {
+        self.thresholds.get(&kind)
+    }
+
+    pub fn set_threshold(&mut self, kind: MeasurementKind, threshold: Option<Threshold>) {
+        match threshold {
+            Some(threshold) => self.thresholds.insert(kind, threshold),
+            None => self.thresholds.remove(&kind),
+        };
+    }
+
+    /// The patient's waking hours, 07:00-21:00 unless set otherwise.
+    pub fn get_waking_hours(&self) -> WakingHours {
+        self.waking_hours.unwrap_or_default()
+    }
+
+    pub fn set_waking_hours(&mut self, waking_hours: WakingHours) {
+        self.waking_hours = Some(waking_hours);
+    }
+
+    /// The timezone the patient lives in, for waking hours, schedules and digests. The owner's
+    /// unless set otherwise.
+    pub fn get_timezone(&self, con: Arc<Mutex<Connection>>) -> Tz {
+        match self.timezone.as_ref().and_then(|tz| tz.parse().ok()) {
+            Some(tz) => tz,
+            None => get_user_timezone(con, &self.creator_user_id)
+                .parse()
+                .unwrap_or(Tz::UTC),
+        }
+    }
+
+    pub fn set_timezone(&mut self, timezone: Option<Tz>) {
+        self.timezone = timezone.map(|tz| tz.name().to_string());
+    }
+
     pub fn get_all_shared_users(&self) -> Vec<String> {
         let mut tmp = self.shared_with.clone();
         tmp.push(self.creator_user_id.clone());
         tmp
     }
 
+    pub fn is_linked_to(&self, chat_id: &str) -> bool {
+        self.groups.iter().any(|id| id == chat_id)
+    }
+
+    /// Posts the patient's digests and intakes to a group chat as well. The patient needs
+    /// saving afterwards.
+    pub fn link_group(
+        &mut self,
+        chat_id: &str,
+        con: Arc<Mutex<Connection>>,
+    ) -> Result<(), RedisError> {
+        con.lock().unwrap().sadd::<String, String, ()>(
+            format!("medi:group_patient:{}", chat_id),
+            self.id.to_string(),
+        )?;
+
+        if !self.is_linked_to(chat_id) {
+            self.groups.push(chat_id.to_string());
+        }
+
+        Ok(())
+    }
+
+    pub fn unlink_group(
+        &mut self,
+        chat_id: &str,
+        con: Arc<Mutex<Connection>>,
+    ) -> Result<(), RedisError> {
+        con.lock().unwrap().srem::<String, String, ()>(
+            format!("medi:group_patient:{}", chat_id),
+            self.id.to_string(),
+        )?;
+
+        self.groups.retain(|id| id != chat_id);
+
+        Ok(())
+    }
+
+    /// Users with access and linked groups, everyone told about intakes and low stock.
+    pub fn get_notified_chats(&self) -> Vec<String> {
+        let mut chats = self.get_all_shared_users();
+        chats.extend(self.groups.iter().cloned());
+        chats
+    }
+
+    // 12.5
+    // 12,5 kg
+    pub fn parse_weight(weight: &str) -> Option<f64> {
+        let lower = weight.trim().to_lowercase();
+        let number = lower
+            .trim_end_matches("kgs")
+            .trim_end_matches("kg")
+            .trim()
+            .replace(',', ".");
+
+        match number.parse::<f64>() {
+            Ok(kg) if kg > 0.0 && kg < 500.0 => Some(kg),
+            _ => None,
+        }
+    }
+
     pub fn generate_patient_keyboard(
         con: Arc<Mutex<Connection>>,
         user_id: String,
         show_add: bool,
+        lang: Language,
     ) -> Vec<Vec<InlineKeyboardButton>> {
         let mut keyboard: Vec<Vec<InlineKeyboardButton>> = vec![];
 
@@ -143,13 +415,13 @@ impl Patient {
 
         if show_add {
             keyboard.push(vec![InlineKeyboardButton::callback(
-                "Add new patient...".to_string(),
+                lang.get(Text::AddNewPatient).to_string(),
                 "add_new".to_string(),
             )]);
         }
 
         keyboard.push(vec![InlineKeyboardButton::callback(
-            "Cancel".to_string(),
+            lang.get(Text::Cancel).to_string(),
             "cancel".to_string(),
         )]);
 
@@ -158,7 +430,6 @@ impl Patient {
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
 
@@ -201,4 +472,88 @@ mod tests {
             1
         );
     }
+
+    #[test]
+    fn test_parse_weight() {
+        assert_eq!(Patient::parse_weight("12.5"), Some(12.5));
+        assert_eq!(Patient::parse_weight("12,5 kg"), Some(12.5));
+        assert_eq!(Patient::parse_weight("30Kg"), Some(30.0));
+        assert_eq!(Patient::parse_weight("heavy"), None);
+        assert_eq!(Patient::parse_weight("0"), None);
+    }
+
+    #[test]
+    fn test_patient_timezone() {
+        let owner = uuid::Uuid::new_v4().to_string();
+        let mut patient = Patient::new("avi".to_string(), owner.clone());
+
+        let redis_con = Arc::new(Mutex::new(create_redis_connection()));
+        redis_con
+            .lock()
+            .unwrap()
+            .set::<String, &str, ()>(format!("medi:{}:timezone", owner), "Europe/Madrid")
+            .unwrap();
+
+        assert_eq!(patient.get_timezone(redis_con.clone()), Tz::Europe__Madrid);
+
+        patient.set_timezone(Some(Tz::America__Argentina__Buenos_Aires));
+        assert_eq!(
+            patient.get_timezone(redis_con.clone()),
+            Tz::America__Argentina__Buenos_Aires
+        );
+
+        patient.set_timezone(None);
+        assert_eq!(patient.get_timezone(redis_con.clone()), Tz::Europe__Madrid);
+    }
+
+    #[test]
+    fn test_link_group() {
+        let mut patient = Patient::new("xavi".to_string(), "1".to_string());
+        let chat_id = uuid::Uuid::new_v4().to_string();
+        let group_ids = |con: Arc<Mutex<Connection>>| {
+            Patient::get_group_patients(&chat_id, con)
+                .unwrap()
+                .into_iter()
+                .map(|patient| patient.id)
+                .collect::<Vec<String>>()
+        };
+
+        let redis_con = Arc::new(Mutex::new(create_redis_connection()));
+        patient.link_group(&chat_id, redis_con.clone()).unwrap();
+        patient.link_group(&chat_id, redis_con.clone()).unwrap();
+        patient.save(redis_con.clone()).unwrap();
+
+        assert!(patient.is_linked_to(&chat_id));
+        assert_eq!(patient.get_notified_chats(), vec!["1", chat_id.as_str()]);
+        assert_eq!(group_ids(redis_con.clone()), vec![patient.id.clone()]);
+
+        patient.unlink_group(&chat_id, redis_con.clone()).unwrap();
+
+        assert!(!patient.is_linked_to(&chat_id));
+        assert!(group_ids(redis_con.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_transfer_ownership() {
+        let mut patient = Patient::new("xavi".to_string(), "1".to_string());
+
+        let redis_con = Arc::new(Mutex::new(create_redis_connection()));
+        patient.share(2, redis_con.clone()).unwrap();
+
+        assert!(!patient.request_transfer("3"));
+        assert!(patient.request_transfer("2"));
+        assert!(!patient.accept_transfer("3", redis_con.clone()).unwrap());
+        assert!(patient.accept_transfer("2", redis_con.clone()).unwrap());
+
+        assert!(patient.is_owner("2"));
+        assert_eq!(patient.get_role("1"), Some(Role::Caregiver));
+        assert_eq!(patient.get_shared_with(), &vec!["1".to_string()]);
+        assert_eq!(patient.get_pending_owner(), None);
+
+        assert!(redis_con
+            .lock()
+            .unwrap()
+            .sismember::<String, String, bool>("medi:user_patient:2".to_string(), patient.id)
+            .unwrap());
+    }
 }
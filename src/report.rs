@@ -0,0 +1,185 @@
+use std::fmt::Write;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use chrono_tz::Tz;
+
+use crate::{i18n::Language, medication::Medication, patient::Patient};
+
+pub const REPORT_PERIODS: [i64; 3] = [7, 30, 90];
+
+const STYLE: &str = "body{font-family:sans-serif;font-size:12px;margin:2em}\
+h1{font-size:18px}h2{font-size:14px;margin-top:1.5em}\
+table{border-collapse:collapse;width:100%}th,td{border:1px solid #ccc;padding:2px 6px;text-align:left}\
+.late{color:#b86e00}.missed{color:#c0392b}\
+@media print{body{margin:0}}";
+
+/// One page HTML summary of a patient for doctor visits, with the intakes of the last `days`.
+/// `intakes` holds the intake history of each of the `medications`, in the same order.
+pub fn render_report(
+    patient: &Patient,
+    medications: &[Medication],
+    intakes: &[Vec<i64>],
+    days: i64,
+    tz: &Tz,
+) -> String {
+    let format_ts = |ts: i64| {
+        DateTime::from_timestamp(ts, 0)
+            .unwrap()
+            .with_timezone(tz)
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    };
+
+    let mut html = String::new();
+
+    let _ = write!(
+        html,
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{name}</title><style>{STYLE}</style></head><body>\
+        <h1>{name}</h1><p>Last {days} days, generated {generated}. Times in {tz}.",
+        name = escape(&patient.name),
+        generated = Utc::now().with_timezone(tz).format("%Y-%m-%d %H:%M"),
+    );
+
+    if let Some(kg) = patient.weight_kg {
+        let _ = write!(html, " Weight: {} kg.", kg);
+    }
+
+    html.push_str("</p><h2>Current plans</h2>");
+
+    if medications.is_empty() {
+        html.push_str("<p>No medications.</p>");
+    } else {
+        html.push_str("<table><tr><th>Medicine</th><th>Dosage</th><th>Frequency</th><th>Started</th><th>Adherence</th></tr>");
+
+        for (medication, intakes) in medications.iter().zip(intakes) {
+            let _ = write!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape(&medication.medicine),
+                escape(&medication.dosage.to_string()),
+                medication.get_frequency(),
+                intakes
+                    .iter()
+                    .min()
+                    .map(|ts| format_ts(*ts))
+                    .unwrap_or("not started".to_string()),
+                medication
+                    .get_adherence(intakes, days)
+                    .map(|adherence| adherence.to_string())
+                    .unwrap_or_default(),
+            );
+        }
+
+        html.push_str("</table>");
+    }
+
+    // (timestamp, medicine, remark) for every intake and missed dose in the period
+    let mut history: Vec<(i64, &str, String)> = vec![];
+    let since = (Utc::now() - TimeDelta::days(days)).timestamp();
+
+    for (medication, intakes) in medications.iter().zip(intakes) {
+        let doses = medication.get_schedule(intakes, days).unwrap_or_default();
+
+        for ts in intakes.iter().filter(|ts| **ts >= since) {
+            let remark = match doses.iter().find(|dose| dose.taken == Some(*ts)) {
+                Some(dose) if dose.is_late() => format!(
+                    "<span class=\"late\">{} min late</span>",
+                    (ts - dose.due) / 60
+                ),
+                _ => "".to_string(),
+            };
+            history.push((*ts, &medication.medicine, remark));
+        }
+
+        for dose in doses.iter().filter(|dose| dose.taken.is_none()) {
+            history.push((
+                dose.due,
+                &medication.medicine,
+                "<span class=\"missed\">missed</span>".to_string(),
+            ));
+        }
+    }
+
+    history.sort_by_key(|(ts, _, _)| *ts);
+
+    html.push_str("<h2>Intake history</h2>");
+
+    if history.is_empty() {
+        html.push_str("<p>No intakes in this period.</p>");
+    } else {
+        html.push_str("<table><tr><th>Time</th><th>Medicine</th><th></th></tr>");
+
+        for (ts, medicine, remark) in history {
+            let _ = write!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                format_ts(ts),
+                escape(medicine),
+                remark
+            );
+        }
+
+        html.push_str("</table>");
+    }
+
+    let mut notes: Vec<String> = Medication::print_all_interactions(medications, Language::English)
+        .lines()
+        .map(|line| line.to_string())
+        .collect();
+
+    if let Some(kg) = patient.weight_kg {
+        notes.extend(
+            medications
+                .iter()
+                .filter_map(|m| m.print_max_dose_warning(kg, Language::English)),
+        );
+    }
+
+    if !notes.is_empty() {
+        html.push_str("<h2>Notes</h2><ul>");
+        for note in notes {
+            let _ = write!(html, "<li>{}</li>", escape(&note));
+        }
+        html.push_str("</ul>");
+    }
+
+    html.push_str("</body></html>");
+    html
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dosage::Dosage;
+    use crate::frequency::Frequency;
+
+    #[test]
+    fn test_render_report() {
+        let patient = Patient::new("Xavi <3".to_string(), "user".to_string());
+        let medication = Medication::new(
+            patient.id.clone(),
+            "Calpol".to_string(),
+            Dosage::parse("5ml").unwrap(),
+            Frequency::new(6 * 60),
+            "user".to_string(),
+        );
+
+        let now = Utc::now().timestamp();
+        // on time, then two hours late and nothing since, the dose due 4 hours ago isn't missed yet
+        let intakes = vec![now - 30 * 3600, now - 24 * 3600, now - 16 * 3600];
+
+        let html = render_report(&patient, &[medication], &[intakes], 7, &Tz::UTC);
+
+        assert!(html.contains("<h1>Xavi &lt;3</h1>"));
+        assert!(html.contains("<td>Calpol</td><td>5 ml</td><td>every 6 hours</td>"));
+        assert!(html.contains("3/4 taken, 1 late (avg 120 min), 1 missed"));
+        assert_eq!(html.matches("missed</span>").count(), 1);
+    }
+}
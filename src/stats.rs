@@ -0,0 +1,218 @@
+use std::fmt::Display;
+
+use crate::i18n::{Language, Text};
+
+// intakes within this many seconds after they're due aren't counted as late
+const LATE_TOLERANCE_SECONDS: i64 = 30 * 60;
+
+#[derive(Debug, PartialEq)]
+pub struct Dose {
+    pub due: i64,
+    pub taken: Option<i64>,
+}
+
+impl Dose {
+    pub fn is_late(&self) -> bool {
+        self.taken
+            .is_some_and(|ts| ts - self.due > LATE_TOLERANCE_SECONDS)
+    }
+}
+
+/// Matches the intakes against the doses due between `from` and `now`. Plans start with their
+/// first intake and every dose is due `next_due` of the previous one actually taken, the same
+/// way `can_take` works. Each dose can be taken until the next one would be due, so one that
+/// can still be taken isn't missed yet and is left out. Returns None for plans that haven't
+/// started.
+pub fn get_schedule(
+    intakes: &[i64],
+    next_due: impl Fn(i64) -> i64,
+    from: i64,
+    now: i64,
+) -> Option<Vec<Dose>> {
+    let mut intakes = intakes.to_vec();
+    intakes.sort();
+
+    let first = *intakes.first()?;
+
+    let mut due = match intakes.iter().rev().find(|ts| **ts < from) {
+        Some(last_before) => next_due(*last_before).max(from),
+        None => first.max(from),
+    };
+
+    let mut remaining = intakes.into_iter().filter(|ts| *ts >= from).peekable();
+    let mut doses = vec![];
+
+    while due <= now {
+        let interval_seconds = next_due(due) - due;
+
+        // doses given a bit early count for the slot they were given for
+        while remaining
+            .next_if(|ts| *ts < due - interval_seconds / 2)
+            .is_some()
+        {}
+
+        let taken = remaining.next_if(|ts| *ts < due + interval_seconds);
+
+        if taken.is_none() && due + interval_seconds > now {
+            break;
+        }
+
+        doses.push(Dose { due, taken });
+
+        due = match taken {
+            Some(ts) => next_due(ts),
+            None => due + interval_seconds,
+        };
+    }
+
+    Some(doses)
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Adherence {
+    pub expected: u32,
+    pub taken: u32,
+    pub late: u32,
+    pub average_delay_minutes: i64,
+    pub missed: u32,
+}
+
+impl Adherence {
+    pub fn compute(
+        intakes: &[i64],
+        next_due: impl Fn(i64) -> i64,
+        from: i64,
+        now: i64,
+    ) -> Option<Self> {
+        let doses = get_schedule(intakes, next_due, from, now)?;
+
+        let late: Vec<i64> = doses
+            .iter()
+            .filter(|dose| dose.is_late())
+            .filter_map(|dose| Some(dose.taken? - dose.due))
+            .collect();
+
+        let taken = doses.iter().filter(|dose| dose.taken.is_some()).count() as u32;
+
+        Some(Adherence {
+            expected: doses.len() as u32,
+            taken,
+            late: late.len() as u32,
+            average_delay_minutes: match late.len() {
+                0 => 0,
+                n => late.iter().sum::<i64>() / n as i64 / 60,
+            },
+            missed: doses.len() as u32 - taken,
+        })
+    }
+}
+
+impl Adherence {
+    pub fn print(&self, lang: Language) -> String {
+        let mut adherence = lang.fill(
+            Text::AdherenceTaken,
+            &[("taken", &self.taken), ("expected", &self.expected)],
+        );
+
+        if self.late > 0 {
+            adherence += &lang.fill(
+                Text::AdherenceLate,
+                &[
+                    ("late", &self.late),
+                    ("minutes", &self.average_delay_minutes),
+                ],
+            );
+        }
+
+        adherence + &lang.fill(Text::AdherenceMissed, &[("missed", &self.missed)])
+    }
+}
+
+impl Display for Adherence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "{}", self.print(Language::English))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 60 * 60;
+
+    fn every(hours: i64) -> impl Fn(i64) -> i64 {
+        move |ts| ts + hours * HOUR
+    }
+
+    #[test]
+    fn test_adherence_all_taken() {
+        let intakes = [0, 6 * HOUR, 12 * HOUR, 18 * HOUR];
+
+        assert_eq!(
+            Adherence::compute(&intakes, every(6), 0, 20 * HOUR),
+            Some(Adherence {
+                expected: 4,
+                taken: 4,
+                late: 0,
+                average_delay_minutes: 0,
+                missed: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_adherence_late_and_missed() {
+        // second dose an hour late, then nothing for a day, the one due at 25 can still be taken
+        let intakes = [0, 7 * HOUR];
+
+        assert_eq!(
+            Adherence::compute(&intakes, every(6), 0, 25 * HOUR),
+            Some(Adherence {
+                expected: 4,
+                taken: 2,
+                late: 1,
+                average_delay_minutes: 60,
+                missed: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_adherence_dose_just_due() {
+        let intakes = [0];
+
+        // the second dose was due a minute ago, it isn't missed
+        assert_eq!(
+            Adherence::compute(&intakes, every(6), 0, 6 * HOUR + 60),
+            Some(Adherence {
+                expected: 1,
+                taken: 1,
+                late: 0,
+                average_delay_minutes: 0,
+                missed: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_adherence_window_starts_mid_plan() {
+        let intakes = [0, 8 * HOUR, 16 * HOUR, 24 * HOUR];
+
+        // from hour 10 on, doses were due at 16 and 24
+        assert_eq!(
+            Adherence::compute(&intakes, every(8), 10 * HOUR, 26 * HOUR),
+            Some(Adherence {
+                expected: 2,
+                taken: 2,
+                late: 0,
+                average_delay_minutes: 0,
+                missed: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_adherence_not_started() {
+        assert_eq!(Adherence::compute(&[], every(6), 0, 20 * HOUR), None);
+    }
+}
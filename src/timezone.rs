@@ -0,0 +1,311 @@
+use std::sync::OnceLock;
+
+use chrono::{DateTime, LocalResult, NaiveDateTime, Offset, TimeDelta, TimeZone, Utc};
+use chrono_tz::{Tz, TZ_VARIANTS};
+
+use crate::i18n::{Language, Text};
+
+// bundled so shared locations can be resolved offline, one timezone per line
+const TIMEZONES_CSV: &str = include_str!("data/timezones.csv");
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+#[derive(Debug, PartialEq)]
+struct City {
+    timezone: Tz,
+    latitude: f64,
+    longitude: f64,
+}
+
+impl City {
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split(',');
+
+        Some(City {
+            timezone: fields.next()?.trim().parse().ok()?,
+            latitude: fields.next()?.trim().parse().ok()?,
+            longitude: fields.next()?.trim().parse().ok()?,
+        })
+    }
+
+    // great-circle distance, so it works across the antimeridian and near the poles
+    fn distance_km(&self, latitude: f64, longitude: f64) -> f64 {
+        let (lat_a, lat_b) = (self.latitude.to_radians(), latitude.to_radians());
+        let delta_lat = lat_b - lat_a;
+        let delta_lon = (longitude - self.longitude).to_radians();
+
+        let a = (delta_lat / 2.0).sin().powi(2)
+            + lat_a.cos() * lat_b.cos() * (delta_lon / 2.0).sin().powi(2);
+
+        2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+    }
+}
+
+fn get_cities() -> &'static Vec<City> {
+    static CITIES: OnceLock<Vec<City>> = OnceLock::new();
+
+    CITIES.get_or_init(|| {
+        TIMEZONES_CSV
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let city = City::parse(line);
+                if city.is_none() {
+                    log::warn!("Skipping malformed timezone: {}", line);
+                }
+                city
+            })
+            .collect()
+    })
+}
+
+/// Regions of the bundled timezones (ie, Europe, America), in alphabetical order.
+pub fn get_regions() -> Vec<&'static str> {
+    let mut regions: Vec<&str> = get_cities()
+        .iter()
+        .filter_map(|city| city.timezone.name().split_once('/'))
+        .map(|(region, _)| region)
+        .collect();
+
+    regions.dedup();
+    regions
+}
+
+pub fn get_region_timezones(region: &str) -> Vec<Tz> {
+    get_cities()
+        .iter()
+        .map(|city| city.timezone)
+        .filter(|timezone| timezone.name().split_once('/').map(|(r, _)| r) == Some(region))
+        .collect()
+}
+
+/// Timezone of the bundled city closest to the coordinates.
+pub fn find_timezone(latitude: f64, longitude: f64) -> Option<Tz> {
+    get_cities()
+        .iter()
+        .min_by(|a, b| {
+            a.distance_km(latitude, longitude)
+                .total_cmp(&b.distance_km(latitude, longitude))
+        })
+        .map(|city| city.timezone)
+}
+
+/// "Buenos Aires" for America/Argentina/Buenos_Aires.
+pub fn print_city(timezone: &Tz) -> String {
+    timezone
+        .name()
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+        .replace('_', " ")
+}
+
+// Europe/Madrid, europe/madrid, Madrid, new york
+pub fn parse_timezone(timezone: &str) -> Option<Tz> {
+    let timezone = timezone.trim();
+
+    if let Ok(tz) = timezone.parse::<Tz>() {
+        return Some(tz);
+    }
+
+    let name = timezone.to_lowercase().replace(' ', "_");
+    if name.is_empty() {
+        return None;
+    }
+
+    TZ_VARIANTS.iter().copied().find(|tz| {
+        let tz_name = tz.name().to_lowercase();
+        tz_name == name || tz_name.rsplit('/').next() == Some(name.as_str())
+    })
+}
+
+/// The instant a wall clock in `tz` shows `local`. Times skipped when the clocks go forward are
+/// moved forward by the length of the gap, times repeated when they go back are the first one.
+pub fn resolve_local(local: NaiveDateTime, tz: &Tz) -> DateTime<Utc> {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => time.with_timezone(&Utc),
+        LocalResult::None => {
+            // a day earlier is always before the change
+            let offset = tz
+                .offset_from_local_datetime(&(local - TimeDelta::days(1)))
+                .earliest()
+                .map_or(0, |offset| offset.fix().local_minus_utc());
+
+            (local - TimeDelta::seconds(offset as i64)).and_utc()
+        }
+    }
+}
+
+/// Adds `delta` the way a wall clock in `tz` would, so a dose a day later is at the same local
+/// time even when daylight saving starts or ends in between.
+pub fn add_local(time: DateTime<Utc>, delta: TimeDelta, tz: &Tz) -> DateTime<Utc> {
+    resolve_local(time.with_timezone(tz).naive_local() + delta, tz)
+}
+
+/// A time as the patient lives it, followed by the viewer's own when they're in a different
+/// timezone (ie, "09:00 (14:00 your time)").
+pub fn print_patient_time(
+    time: DateTime<Utc>,
+    format: &str,
+    patient: &Tz,
+    viewer: &Tz,
+    lang: Language,
+) -> String {
+    let local = time.with_timezone(patient);
+    let own = time.with_timezone(viewer);
+
+    if local.offset().fix() == own.offset().fix() {
+        return local.format(format).to_string();
+    }
+
+    lang.fill(
+        Text::YourTime,
+        &[
+            ("time", &local.format(format).to_string()),
+            ("own", &own.format(format).to_string()),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_timezones_parse() {
+        let lines = TIMEZONES_CSV
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+            .count();
+
+        assert_eq!(get_cities().len(), lines);
+        assert!(get_regions().contains(&"Europe"));
+        assert!(get_region_timezones("Europe").contains(&Tz::Europe__Madrid));
+    }
+
+    #[test]
+    fn test_find_timezone() {
+        // Barcelona, Porto, Brooklyn and somewhere in Fiji across the antimeridian
+        assert_eq!(find_timezone(41.39, 2.17), Some(Tz::Europe__Madrid));
+        assert_eq!(find_timezone(41.15, -8.61), Some(Tz::Europe__Lisbon));
+        assert_eq!(find_timezone(40.68, -73.94), Some(Tz::America__New_York));
+        assert_eq!(find_timezone(-16.8, -179.9), Some(Tz::Pacific__Fiji));
+    }
+
+    #[test]
+    fn test_parse_timezone() {
+        assert_eq!(parse_timezone("Europe/Madrid"), Some(Tz::Europe__Madrid));
+        assert_eq!(parse_timezone(" europe/madrid "), Some(Tz::Europe__Madrid));
+        assert_eq!(parse_timezone("Madrid"), Some(Tz::Europe__Madrid));
+        assert_eq!(parse_timezone("new york"), Some(Tz::America__New_York));
+        assert_eq!(
+            parse_timezone("buenos aires"),
+            Some(Tz::America__Argentina__Buenos_Aires)
+        );
+        assert_eq!(parse_timezone("Atlantis"), None);
+        assert_eq!(parse_timezone(""), None);
+
+        assert_eq!(
+            print_city(&Tz::America__Argentina__Buenos_Aires),
+            "Buenos Aires"
+        );
+    }
+
+    #[test]
+    fn test_resolve_local() {
+        let resolve = |tz: Tz, local: &str| {
+            let local = NaiveDateTime::parse_from_str(local, "%Y-%m-%d %H:%M").unwrap();
+            resolve_local(local, &tz).to_rfc3339()
+        };
+
+        // skipped when springing forward, so it's the time it would have been an hour later
+        assert_eq!(
+            resolve(Tz::Europe__Madrid, "2024-03-31 02:30"),
+            "2024-03-31T01:30:00+00:00"
+        );
+        assert_eq!(
+            resolve(Tz::America__New_York, "2024-03-10 02:30"),
+            "2024-03-10T07:30:00+00:00"
+        );
+        assert_eq!(
+            resolve(Tz::Australia__Sydney, "2024-10-06 02:30"),
+            "2024-10-05T16:30:00+00:00"
+        );
+        // Chile changes at midnight
+        assert_eq!(
+            resolve(Tz::America__Santiago, "2024-09-08 00:00"),
+            "2024-09-08T04:00:00+00:00"
+        );
+
+        // repeated when falling back, the first one
+        assert_eq!(
+            resolve(Tz::Europe__Madrid, "2024-10-27 02:30"),
+            "2024-10-27T00:30:00+00:00"
+        );
+        assert_eq!(
+            resolve(Tz::America__New_York, "2024-11-03 01:30"),
+            "2024-11-03T05:30:00+00:00"
+        );
+        assert_eq!(
+            resolve(Tz::Australia__Sydney, "2024-04-07 02:30"),
+            "2024-04-06T15:30:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_add_local_keeps_the_time_of_day() {
+        let day = TimeDelta::days(1);
+        let at = |tz: Tz, local: &str| {
+            resolve_local(
+                NaiveDateTime::parse_from_str(local, "%Y-%m-%d %H:%M").unwrap(),
+                &tz,
+            )
+        };
+
+        for (tz, spring, fall) in [
+            (Tz::Europe__Madrid, "2024-03-30", "2024-10-26"),
+            (Tz::America__New_York, "2024-03-09", "2024-11-02"),
+            (Tz::Australia__Sydney, "2024-10-05", "2024-04-06"),
+        ] {
+            for date in [spring, fall] {
+                let before = at(tz, &format!("{} 09:00", date));
+                let after = add_local(before, day, &tz);
+
+                assert_eq!(
+                    after.with_timezone(&tz).format("%H:%M").to_string(),
+                    "09:00"
+                );
+                assert_ne!(after - before, day);
+            }
+        }
+    }
+
+    #[test]
+    fn test_print_patient_time() {
+        let time = DateTime::parse_from_rfc3339("2024-11-20T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let buenos_aires = Tz::America__Argentina__Buenos_Aires;
+
+        assert_eq!(
+            print_patient_time(
+                time,
+                "%H:%M",
+                &buenos_aires,
+                &Tz::Europe__Madrid,
+                Language::English
+            ),
+            "09:00 (13:00 your time)"
+        );
+        assert_eq!(
+            print_patient_time(
+                time,
+                "%H:%M",
+                &Tz::Europe__Paris,
+                &Tz::Europe__Madrid,
+                Language::English
+            ),
+            "13:00"
+        );
+    }
+}
@@ -1,6 +1,9 @@
 use std::sync::{Arc, Mutex};
 
-use redis::{Commands as _, Connection};
+use chrono_tz::Tz;
+use redis::{Commands as _, Connection, RedisError};
+
+use crate::i18n::Language;
 
 pub fn get_user_timezone(con: Arc<Mutex<Connection>>, user_id: &str) -> String {
     con.lock()
@@ -8,3 +11,164 @@ pub fn get_user_timezone(con: Arc<Mutex<Connection>>, user_id: &str) -> String {
         .get::<String, String>(format!("medi:{}:timezone", user_id))
         .unwrap_or("UTC".to_string())
 }
+
+/// Whether the user picked a timezone, UTC is only a fallback until they do.
+pub fn has_user_timezone(con: Arc<Mutex<Connection>>, user_id: &str) -> bool {
+    con.lock()
+        .unwrap()
+        .exists::<String, bool>(format!("medi:{}:timezone", user_id))
+        .unwrap_or_default()
+}
+
+pub fn set_user_timezone(
+    con: Arc<Mutex<Connection>>,
+    user_id: &str,
+    timezone: &Tz,
+) -> Result<(), RedisError> {
+    con.lock()
+        .unwrap()
+        .set::<String, &str, ()>(format!("medi:{}:timezone", user_id), timezone.name())
+}
+
+/// The language the user picked, English when they haven't talked to the bot yet.
+pub fn get_user_language(con: Arc<Mutex<Connection>>, user_id: &str) -> Language {
+    con.lock()
+        .unwrap()
+        .get::<String, Option<String>>(format!("medi:{}:language", user_id))
+        .ok()
+        .flatten()
+        .and_then(|language| Language::parse(&language))
+        .unwrap_or_default()
+}
+
+pub fn set_user_language(
+    con: Arc<Mutex<Connection>>,
+    user_id: &str,
+    language: Language,
+) -> Result<(), RedisError> {
+    con.lock()
+        .unwrap()
+        .set::<String, &str, ()>(format!("medi:{}:language", user_id), language.code())
+}
+
+/// The language the user picked, otherwise the one of their Telegram app, which is remembered
+/// so notifications and digests sent to them use it too.
+pub fn detect_user_language(
+    con: Arc<Mutex<Connection>>,
+    user_id: &str,
+    language_code: Option<&str>,
+) -> Language {
+    let stored = con
+        .lock()
+        .unwrap()
+        .exists::<String, bool>(format!("medi:{}:language", user_id))
+        .unwrap_or_default();
+
+    if stored {
+        return get_user_language(con, user_id);
+    }
+
+    let language = language_code.and_then(Language::parse).unwrap_or_default();
+
+    if let Err(e) = set_user_language(con, user_id, language) {
+        log::warn!(
+            "Failed to save detected language: telegram user id {}. Error {}",
+            user_id,
+            e
+        )
+    }
+
+    language
+}
+
+/// Secret token to reach the user's patients from outside Telegram, `kind` being what it opens
+/// (ie, "dashboard", "api"). Created the first time it's asked for, it works until revoked.
+pub fn get_user_token(
+    con: Arc<Mutex<Connection>>,
+    user_id: &str,
+    kind: &str,
+) -> Result<String, RedisError> {
+    let mut con = con.lock().unwrap();
+
+    if let Some(token) = con.get::<String, Option<String>>(format!("medi:{}:{}", user_id, kind))? {
+        return Ok(token);
+    }
+
+    let token = uuid::Uuid::new_v4().to_string().replace("-", "");
+
+    con.set::<String, &str, ()>(format!("medi:{}:{}", user_id, kind), &token)?;
+    con.set::<String, &str, ()>(format!("medi:{}:{}", kind, token), user_id)?;
+
+    Ok(token)
+}
+
+pub fn revoke_user_token(
+    con: Arc<Mutex<Connection>>,
+    user_id: &str,
+    kind: &str,
+) -> Result<(), RedisError> {
+    let mut con = con.lock().unwrap();
+
+    if let Some(token) =
+        con.get_del::<String, Option<String>>(format!("medi:{}:{}", user_id, kind))?
+    {
+        con.del::<String, ()>(format!("medi:{}:{}", kind, token))?;
+    }
+
+    Ok(())
+}
+
+/// The user a token belongs to, None once it's revoked.
+pub fn get_token_user(con: Arc<Mutex<Connection>>, kind: &str, token: &str) -> Option<String> {
+    con.lock()
+        .unwrap()
+        .get::<String, Option<String>>(format!("medi:{}:{}", kind, token))
+        .ok()
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_redis_connection() -> redis::Connection {
+        // creating a real connection actually
+        let client = redis::Client::open("redis://127.0.0.1/").expect("Could not connect to Redis");
+        let mut redis_connection = client
+            .get_connection()
+            .expect("Could not get a Redis connection");
+
+        redis::cmd("SELECT")
+            .arg(1) // selecting db 1 for tests to preserve data on the other one (default, 0)
+            .exec(&mut redis_connection)
+            .unwrap();
+        redis::cmd("FLUSHDB").exec(&mut redis_connection).unwrap();
+
+        redis_connection
+    }
+
+    #[test]
+    fn test_user_token() {
+        let con = Arc::new(Mutex::new(create_redis_connection()));
+
+        let token = get_user_token(con.clone(), "user", "dashboard").unwrap();
+        assert_eq!(
+            get_user_token(con.clone(), "user", "dashboard").unwrap(),
+            token
+        );
+        assert_eq!(
+            get_token_user(con.clone(), "dashboard", &token).as_deref(),
+            Some("user")
+        );
+        // each kind has its own
+        assert_eq!(get_token_user(con.clone(), "api", &token), None);
+        assert_ne!(get_user_token(con.clone(), "user", "api").unwrap(), token);
+
+        revoke_user_token(con.clone(), "user", "dashboard").unwrap();
+        assert_eq!(get_token_user(con.clone(), "dashboard", &token), None);
+        assert_ne!(
+            get_user_token(con.clone(), "user", "dashboard").unwrap(),
+            token
+        );
+    }
+}
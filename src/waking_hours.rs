@@ -0,0 +1,122 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, NaiveTime, TimeDelta, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::digest::parse_digest_time;
+use crate::timezone::resolve_local;
+
+// doses due this long after the window ends are still given that evening
+const GRACE_MINUTES: i64 = 60;
+
+/// Local time window in which doses taken a number of times a day are spread, so the dose
+/// after the evening one is the next morning instead of in the middle of the night.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct WakingHours {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl Default for WakingHours {
+    fn default() -> Self {
+        WakingHours {
+            start: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(21, 0, 0).unwrap(),
+        }
+    }
+}
+
+impl WakingHours {
+    // 07:00-21:00
+    // 7-21
+    // 7:30 to 20:30
+    pub fn parse(waking_hours: &str) -> Option<Self> {
+        let lower = waking_hours.to_lowercase();
+        let (start, end) = lower.split_once('-').or(lower.split_once(" to "))?;
+        let (start, end) = (parse_digest_time(start)?, parse_digest_time(end)?);
+
+        match start < end {
+            true => Some(WakingHours { start, end }),
+            false => None,
+        }
+    }
+
+    /// Time between doses when taking `times` a day, the first one when waking up and the
+    /// last one before going to bed.
+    pub fn get_gap(&self, times: i64) -> TimeDelta {
+        (self.end - self.start) / (times.max(2) - 1) as i32
+    }
+
+    /// Moves doses due while asleep to the next morning.
+    pub fn clamp(&self, due: DateTime<Utc>, tz: &Tz) -> DateTime<Utc> {
+        let local = due.with_timezone(tz);
+        let date = local.date_naive();
+
+        let wake_up = if local.time() < self.start {
+            date
+        } else if local.time() > self.end + TimeDelta::minutes(GRACE_MINUTES) {
+            date + TimeDelta::days(1)
+        } else {
+            return due;
+        };
+
+        resolve_local(wake_up.and_time(self.start), tz)
+    }
+}
+
+impl Display for WakingHours {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_waking_hours() {
+        assert_eq!(
+            WakingHours::parse("07:00-21:00"),
+            Some(WakingHours::default())
+        );
+        assert_eq!(WakingHours::parse("7 to 21"), Some(WakingHours::default()));
+        assert_eq!(
+            WakingHours::parse("7:30-20:30").unwrap().to_string(),
+            "07:30-20:30"
+        );
+        assert_eq!(WakingHours::parse("21-7"), None);
+        assert_eq!(WakingHours::parse("all day"), None);
+    }
+
+    #[test]
+    fn test_doses_while_asleep_move_to_the_morning() {
+        let tz: Tz = "Europe/Madrid".parse().unwrap();
+        let waking_hours = WakingHours::default();
+        let at = |day: u32, hour: u32, minute: u32| {
+            tz.with_ymd_and_hms(2024, 11, day, hour, minute, 0)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+
+        // 3 times a day is 07:00, 14:00 and 21:00
+        let gap = waking_hours.get_gap(3);
+        assert_eq!(gap, TimeDelta::hours(7));
+
+        assert_eq!(waking_hours.clamp(at(20, 7, 0) + gap, &tz), at(20, 14, 0));
+        // a bit late is still that evening
+        assert_eq!(
+            waking_hours.clamp(at(20, 14, 30) + gap, &tz),
+            at(20, 21, 30)
+        );
+        assert_eq!(waking_hours.clamp(at(20, 21, 0) + gap, &tz), at(21, 7, 0));
+        assert_eq!(waking_hours.clamp(at(20, 23, 30) + gap, &tz), at(21, 7, 0));
+    }
+}